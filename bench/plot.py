@@ -1,3 +1,6 @@
+import json
+import os
+
 import matplotlib.pyplot as plt
 import numpy as np
 
@@ -90,3 +93,49 @@ plt.tight_layout()
 # save the plot
 plt.savefig("plot.png", dpi=300)
 plt.close()
+
+
+def plot_stages(results_path, output_path):
+    """Plots the per-stage timings of each backend, as written by `cargo bench --bench prove`."""
+    with open(results_path) as f:
+        reports = json.load(f)
+
+    backends = list(dict.fromkeys(report["backend"] for report in reports))
+    fig, axes = plt.subplots(
+        1, len(backends), figsize=(7 * len(backends), 8), squeeze=False
+    )
+    for ax, backend in zip(axes[0], backends):
+        backend_reports = [r for r in reports if r["backend"] == backend]
+        circuits = [r["circuit"] for r in backend_reports]
+        stages = list(
+            dict.fromkeys(s["stage"] for r in backend_reports for s in r["stages"])
+        )
+
+        x = np.arange(len(circuits))
+        bottom = np.zeros(len(circuits))
+        for stage in stages:
+            means = np.array(
+                [
+                    next((s["meanMs"] for s in r["stages"] if s["stage"] == stage), 0.0)
+                    for r in backend_reports
+                ]
+            )
+            ax.bar(x, means, 0.6, bottom=bottom, label=stage, alpha=0.8)
+            bottom += means
+
+        ax.set_title(f"{backend} ({backend_reports[0]['iterations']} iterations)", fontsize=14)
+        ax.set_xlabel("Circuit", fontsize=12)
+        ax.set_ylabel("Mean Proving Time (ms)", fontsize=12)
+        ax.set_xticks(x)
+        ax.set_xticklabels(circuits, rotation=45, ha="right", fontsize=10)
+        ax.legend(fontsize=10)
+        ax.grid(True, axis="y", linestyle="--", alpha=0.6)
+
+    plt.tight_layout()
+    plt.savefig(output_path, dpi=300)
+    plt.close()
+
+
+# per-stage timings of the Rust benchmarks, if they are run
+if os.path.exists("results.json"):
+    plot_stages("results.json", "stages.png")
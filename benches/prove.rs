@@ -0,0 +1,119 @@
+//! Benchmarks every backend on the circuits of `example/circuits.json` that are built under `example/build`,
+//! and writes the timings of each stage to `bench/results.json`, which `bench/plot.py` reads.
+//!
+//! ```sh
+//! cargo bench --bench prove
+//! ```
+//!
+//! The number of iterations is set by `BENCH_ITERATIONS` (5 by default), and the backends by
+//! `BENCH_BACKENDS` as a comma-separated list (all backends of the library by default).
+
+use circomkit_ffi::arkworks::r1cs_info;
+use circomkit_ffi::bench::{benchmark, BenchmarkReport};
+use circomkit_ffi::prover::{ProveRequest, ProverRegistry};
+use std::path::Path;
+
+const RESULTS_PATH: &str = "bench/results.json";
+
+fn main() -> eyre::Result<()> {
+    let iterations = std::env::var("BENCH_ITERATIONS")
+        .map(|n| n.parse())
+        .unwrap_or(Ok(5))?;
+    let backends = std::env::var("BENCH_BACKENDS")
+        .map(|names| names.split(',').map(str::to_string).collect())
+        .unwrap_or_else(|_| {
+            ProverRegistry::default()
+                .names()
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        });
+
+    let capabilities = ProverRegistry::default().capabilities();
+
+    let circuits: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&std::fs::read_to_string("example/circuits.json")?)?;
+
+    let mut reports: Vec<BenchmarkReport> = Vec::new();
+    for circuit in circuits.keys() {
+        let dir = Path::new("example/build").join(circuit);
+        let wtns_path = dir
+            .join("default") // input name
+            .join("witness")
+            .with_extension("wtns");
+        if !wtns_path.exists() {
+            eprintln!("Skipping {}, it is not built", circuit);
+            continue;
+        }
+
+        let prime = r1cs_info(dir.join(circuit).with_extension("r1cs"))?.curve;
+
+        for backend in &backends {
+            // e.g. Lambdaworks only proves circuits compiled with `--prime bls12381`
+            if capabilities
+                .get(backend.as_str())
+                .is_some_and(|c| !c.curves.contains(&prime))
+            {
+                eprintln!(
+                    "Skipping {} on {}, it does not support {}",
+                    backend, circuit, prime
+                );
+                continue;
+            }
+
+            let request = bench_request(backend, &dir, circuit, &wtns_path)?;
+            let mut report = benchmark(backend, &request, iterations)?;
+            report.circuit = circuit.clone();
+
+            println!(
+                "{} on {}: {:.2}ms ({})",
+                backend,
+                circuit,
+                report.total.mean_ms,
+                report
+                    .stages
+                    .iter()
+                    .map(|s| format!("{} {:.2}ms", s.stage, s.mean_ms))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            reports.push(report);
+        }
+    }
+
+    std::fs::write(RESULTS_PATH, serde_json::to_string_pretty(&reports)?)?;
+    println!("Results written to {}", RESULTS_PATH);
+
+    Ok(())
+}
+
+/// Returns the request of a backend for a circuit, with the files that the backend requires.
+fn bench_request(
+    backend: &str,
+    dir: &Path,
+    circuit: &str,
+    wtns_path: &Path,
+) -> eyre::Result<ProveRequest> {
+    // Lambdaworks proves over BLS12-381 with a setup of its own, which is done once outside of the timings
+    if backend == "lambdaworks" {
+        let r1cs_path = dir.join(circuit).with_extension("r1cs.json");
+        let keys_dir = std::env::temp_dir()
+            .join("circomkit-ffi-bench")
+            .join(circuit);
+        circomkit_ffi::lambdaworks::setup(&r1cs_path, &keys_dir)?;
+
+        return Ok(ProveRequest {
+            wtns_path: wtns_path.to_path_buf(),
+            r1cs_path: Some(r1cs_path),
+            pkey_path: Some(keys_dir.join(circomkit_ffi::lambdaworks::PKEY_FILENAME)),
+            ..Default::default()
+        });
+    }
+
+    Ok(ProveRequest {
+        wtns_path: wtns_path.to_path_buf(),
+        r1cs_path: Some(dir.join(circuit).with_extension("r1cs")),
+        pkey_path: Some(dir.join("groth16_pkey").with_extension("zkey")),
+        ..Default::default()
+    })
+}
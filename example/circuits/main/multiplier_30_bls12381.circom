@@ -0,0 +1,6 @@
+// same as multiplier_30, to be compiled with `--prime bls12381`
+pragma circom 2.1.2;
+
+include "../multiplier.circom";
+
+component main = Multiplier(30);
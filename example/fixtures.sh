@@ -0,0 +1,54 @@
+#!/usr/bin/env bash
+# Builds the circuit artifacts under `build` that the tests need besides the committed ones,
+# skipping those that already exist. CI runs this before the tests.
+#
+# Requires Circom & SnarkJS, whose commands can be overridden, e.g. `SNARKJS="bunx snarkjs" ./fixtures.sh`.
+set -euo pipefail
+cd "$(dirname "$0")"
+
+CIRCOM=${CIRCOM:-circom}
+SNARKJS=${SNARKJS:-snarkjs}
+
+# PLONK & FFLONK keys of `multiplier_30`, over a universal ptau that is large enough
+CIRCUIT=multiplier_30
+DIR=build/$CIRCUIT
+PTAU=ptau/powersOfTau28_hez_final_12.ptau
+for PROTOCOL in plonk fflonk; do
+  if [ ! -f $DIR/${PROTOCOL}_pkey.zkey ]; then
+    if [ ! -f $PTAU ]; then
+      mkdir -p ptau
+      curl -fsSL -o $PTAU https://storage.googleapis.com/zkevm/$PTAU
+    fi
+    $SNARKJS $PROTOCOL setup $DIR/$CIRCUIT.r1cs $PTAU $DIR/${PROTOCOL}_pkey.zkey
+  fi
+  if [ ! -f $DIR/${PROTOCOL}_vkey.json ]; then
+    $SNARKJS zkey export verificationkey $DIR/${PROTOCOL}_pkey.zkey $DIR/${PROTOCOL}_vkey.json
+  fi
+done
+
+# the same circuit as `multiplier_30` over BLS12-381, e.g. for Lambdaworks
+CIRCUIT=multiplier_30_bls12381
+DIR=build/$CIRCUIT
+mkdir -p $DIR/default
+if [ ! -f $DIR/$CIRCUIT.r1cs ]; then
+  $CIRCOM circuits/main/$CIRCUIT.circom --prime bls12381 --r1cs --wasm -o $DIR
+  $SNARKJS r1cs export json $DIR/$CIRCUIT.r1cs $DIR/$CIRCUIT.r1cs.json
+fi
+if [ ! -f $DIR/default/witness.wtns ]; then
+  node $DIR/${CIRCUIT}_js/generate_witness.js $DIR/${CIRCUIT}_js/$CIRCUIT.wasm inputs/$CIRCUIT/default.json $DIR/default/witness.wtns
+  $SNARKJS wtns export json $DIR/default/witness.wtns $DIR/default/witness.wtns.json
+fi
+if [ ! -f $DIR/groth16_pkey.zkey ]; then
+  # a ptau over BLS12-381 that is just large enough, as there is no universal one to download
+  $SNARKJS powersoftau new bls12381 8 $DIR/pot_0.ptau
+  $SNARKJS powersoftau contribute $DIR/pot_0.ptau $DIR/pot_1.ptau -e="circomkit-ffi"
+  $SNARKJS powersoftau prepare phase2 $DIR/pot_1.ptau $DIR/pot.ptau
+  $SNARKJS groth16 setup $DIR/$CIRCUIT.r1cs $DIR/pot.ptau $DIR/groth16_pkey.zkey
+  rm $DIR/pot*.ptau
+fi
+if [ ! -f $DIR/groth16_vkey.json ]; then
+  $SNARKJS zkey export verificationkey $DIR/groth16_pkey.zkey $DIR/groth16_vkey.json
+fi
+if [ ! -f $DIR/default/public.json ]; then
+  $SNARKJS groth16 prove $DIR/groth16_pkey.zkey $DIR/default/witness.wtns $DIR/default/groth16_proof.json $DIR/default/public.json
+fi
@@ -1,8 +1,21 @@
 import { dlopen, FFIType } from "bun:ffi";
+import type { Groth16Proof, PublicSignals } from "snarkjs";
 import type {
+  BenchmarkReport,
+  FflonkProofWithPublicSignals,
+  GnarkExport,
+  Groth16VerificationKey,
   IcicleDevice,
+  LogLevel,
+  PlonkProofWithPublicSignals,
+  ProofEncoding,
   ProofWithPublicSignals,
+  ProveRequest,
   ProverBackend,
+  ProverCapabilities,
+  ProverConfig,
+  R1CSInfo,
+  SolidityCalldata,
 } from "./interface";
 import { isBun } from "./common";
 import { existsSync } from "fs";
@@ -49,13 +62,19 @@ export class CircomkitFFIBun implements ProverBackend {
   arkworks_prove(
     wtnsPath: string,
     r1csPath: string,
-    pkeyPath: string
+    pkeyPath: string,
+    calldata: boolean = false
   ): ProofWithPublicSignals {
     const {
       symbols: { arkworks_prove },
     } = dlopen(this.path, {
       arkworks_prove: {
-        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+        args: [
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.bool,
+        ],
         returns: FFIType.cstring,
       },
     });
@@ -63,22 +82,236 @@ export class CircomkitFFIBun implements ProverBackend {
     const result = arkworks_prove(
       new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
       new Uint8Array(Buffer.from(r1csPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8")),
+      calldata
+    );
+
+    return JSON.parse(result.toString());
+  }
+
+  arkworks_prove_zkey(
+    wtnsPath: string,
+    pkeyPath: string,
+    calldata: boolean = false
+  ): ProofWithPublicSignals {
+    const {
+      symbols: { arkworks_prove_zkey },
+    } = dlopen(this.path, {
+      arkworks_prove_zkey: {
+        args: [FFIType.cstring, FFIType.cstring, FFIType.bool],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = arkworks_prove_zkey(
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8")),
+      calldata
+    );
+
+    return JSON.parse(result.toString());
+  }
+
+  plonk_prove(
+    wtnsPath: string,
+    pkeyPath: string
+  ): PlonkProofWithPublicSignals {
+    const {
+      symbols: { plonk_prove },
+    } = dlopen(this.path, {
+      plonk_prove: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = plonk_prove(
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
       new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8"))
     );
+    return JSON.parse(result.toString());
+  }
+
+  fflonk_prove(
+    wtnsPath: string,
+    pkeyPath: string
+  ): FflonkProofWithPublicSignals {
+    const {
+      symbols: { fflonk_prove },
+    } = dlopen(this.path, {
+      fflonk_prove: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = fflonk_prove(
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  r1cs_info(r1csPath: string): R1CSInfo {
+    const {
+      symbols: { r1cs_info },
+    } = dlopen(this.path, {
+      r1cs_info: {
+        args: [FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = r1cs_info(
+      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  solidity_verifier(vkeyPath: string): string {
+    const {
+      symbols: { solidity_verifier },
+    } = dlopen(this.path, {
+      solidity_verifier: {
+        args: [FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = solidity_verifier(
+      new Uint8Array(Buffer.from(vkeyPath + "\0", "utf8"))
+    );
+    return result.toString();
+  }
 
+  solidity_calldata(proofPath: string, publicPath: string): SolidityCalldata {
+    const {
+      symbols: { solidity_calldata },
+    } = dlopen(this.path, {
+      solidity_calldata: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = solidity_calldata(
+      new Uint8Array(Buffer.from(proofPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(publicPath + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  encode_proof(
+    proofPath: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): string {
+    const {
+      symbols: { encode_proof },
+    } = dlopen(this.path, {
+      encode_proof: {
+        args: [FFIType.cstring, FFIType.bool, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = encode_proof(
+      new Uint8Array(Buffer.from(proofPath + "\0", "utf8")),
+      compressed,
+      new Uint8Array(Buffer.from(encoding + "\0", "utf8"))
+    );
+    return result.toString();
+  }
+
+  decode_proof(
+    data: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): Groth16Proof {
+    const {
+      symbols: { decode_proof },
+    } = dlopen(this.path, {
+      decode_proof: {
+        args: [FFIType.cstring, FFIType.bool, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = decode_proof(
+      new Uint8Array(Buffer.from(data + "\0", "utf8")),
+      compressed,
+      new Uint8Array(Buffer.from(encoding + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  rerandomize_proof(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof
+  ): Groth16Proof {
+    const {
+      symbols: { rerandomize_proof },
+    } = dlopen(this.path, {
+      rerandomize_proof: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = rerandomize_proof(
+      new Uint8Array(Buffer.from(JSON.stringify(vkey) + "\0", "utf8")),
+      new Uint8Array(Buffer.from(JSON.stringify(proof) + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  gnark_export(
+    proofPath: string,
+    vkeyPath: string,
+    publicPath: string,
+    compressed: boolean
+  ): GnarkExport {
+    const {
+      symbols: { gnark_export },
+    } = dlopen(this.path, {
+      gnark_export: {
+        args: [
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.bool,
+        ],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = gnark_export(
+      new Uint8Array(Buffer.from(proofPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(vkeyPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(publicPath + "\0", "utf8")),
+      compressed
+    );
     return JSON.parse(result.toString());
   }
 
   icicle_prove(
     wtnsPath: string,
     pkeyPath: string,
-    device: IcicleDevice
+    device: IcicleDevice,
+    calldata: boolean = false,
+    backendDir: string = ""
   ): ProofWithPublicSignals {
     const {
       symbols: { icicle_prove },
     } = dlopen(this.path, {
       icicle_prove: {
-        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+        args: [
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.bool,
+        ],
         returns: FFIType.cstring,
       },
     });
@@ -86,33 +319,257 @@ export class CircomkitFFIBun implements ProverBackend {
     const result = icicle_prove(
       new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
       new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8")),
-      new Uint8Array(Buffer.from(device + "\0", "utf8"))
+      new Uint8Array(Buffer.from(device + "\0", "utf8")),
+      new Uint8Array(Buffer.from(backendDir + "\0", "utf8")),
+      calldata
     );
     return JSON.parse(result.toString());
   }
 
-  lambdaworks_prove(
+  icicle_list_devices(backendDir: string = ""): string[] {
+    const {
+      symbols: { icicle_list_devices },
+    } = dlopen(this.path, {
+      icicle_list_devices: {
+        args: [FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = icicle_list_devices(
+      new Uint8Array(Buffer.from(backendDir + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  icicle_session_open(
+    pkeyPath: string,
+    device: IcicleDevice,
+    backendDir: string = ""
+  ): number {
+    const {
+      symbols: { icicle_session_open },
+    } = dlopen(this.path, {
+      icicle_session_open: {
+        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+        returns: FFIType.u32,
+      },
+    });
+
+    return icicle_session_open(
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(device + "\0", "utf8")),
+      new Uint8Array(Buffer.from(backendDir + "\0", "utf8"))
+    );
+  }
+
+  icicle_session_prove(
+    sessionId: number,
     wtnsPath: string,
-    r1csPath: string
+    calldata: boolean = false
   ): ProofWithPublicSignals {
-    // make sure r1cs path is JSON
-    if (!r1csPath.endsWith(".json")) {
-      throw new Error("r1csPath must be a JSON file");
-    }
+    const {
+      symbols: { icicle_session_prove },
+    } = dlopen(this.path, {
+      icicle_session_prove: {
+        args: [FFIType.u32, FFIType.cstring, FFIType.bool],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = icicle_session_prove(
+      sessionId,
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
+      calldata
+    );
+    return JSON.parse(result.toString());
+  }
+
+  icicle_session_close(sessionId: number): boolean {
+    const {
+      symbols: { icicle_session_close },
+    } = dlopen(this.path, {
+      icicle_session_close: {
+        args: [FFIType.u32],
+        returns: FFIType.bool,
+      },
+    });
+
+    return icicle_session_close(sessionId);
+  }
+
+  prove(backend: string, request: ProveRequest): ProofWithPublicSignals {
+    const {
+      symbols: { prove },
+    } = dlopen(this.path, {
+      prove: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = prove(
+      new Uint8Array(Buffer.from(backend + "\0", "utf8")),
+      new Uint8Array(Buffer.from(JSON.stringify(request) + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  prover_backends(): Record<string, ProverCapabilities> {
+    const {
+      symbols: { prover_backends },
+    } = dlopen(this.path, {
+      prover_backends: {
+        args: [],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = prover_backends();
+    return JSON.parse(result.toString());
+  }
+
+  benchmark(
+    backend: string,
+    r1csPath: string,
+    wtnsPath: string,
+    pkeyPath: string,
+    iterations: number = 5
+  ): BenchmarkReport {
+    const {
+      symbols: { benchmark },
+    } = dlopen(this.path, {
+      benchmark: {
+        args: [
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.cstring,
+          FFIType.u32,
+        ],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = benchmark(
+      new Uint8Array(Buffer.from(backend + "\0", "utf8")),
+      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8")),
+      iterations
+    );
+    return JSON.parse(result.toString());
+  }
+
+  prover_config(config?: ProverConfig): ProverConfig {
+    const {
+      symbols: { prover_config },
+    } = dlopen(this.path, {
+      prover_config: {
+        args: [FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const configJson = config ? JSON.stringify(config) : "";
+    const result = prover_config(
+      new Uint8Array(Buffer.from(configJson + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  set_log_level(level: LogLevel): boolean {
+    const {
+      symbols: { set_log_level },
+    } = dlopen(this.path, {
+      set_log_level: {
+        args: [FFIType.cstring],
+        returns: FFIType.bool,
+      },
+    });
+
+    return set_log_level(new Uint8Array(Buffer.from(level + "\0", "utf8")));
+  }
 
+  lambdaworks_prove(
+    wtnsPath: string,
+    r1csPath: string,
+    vkeyPath: string = ""
+  ): ProofWithPublicSignals {
     const {
       symbols: { lambdaworks_prove },
     } = dlopen(this.path, {
       lambdaworks_prove: {
-        args: [FFIType.cstring, FFIType.cstring],
+        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
         returns: FFIType.cstring,
       },
     });
 
     const result = lambdaworks_prove(
       new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
-      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8"))
+      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(vkeyPath + "\0", "utf8"))
     );
     return JSON.parse(result.toString());
   }
+
+  lambdaworks_setup(r1csPath: string, outDir: string): Groth16VerificationKey {
+    const {
+      symbols: { lambdaworks_setup },
+    } = dlopen(this.path, {
+      lambdaworks_setup: {
+        args: [FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = lambdaworks_setup(
+      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(outDir + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  lambdaworks_prove_with_pkey(
+    wtnsPath: string,
+    r1csPath: string,
+    pkeyPath: string
+  ): ProofWithPublicSignals {
+    const {
+      symbols: { lambdaworks_prove_with_pkey },
+    } = dlopen(this.path, {
+      lambdaworks_prove_with_pkey: {
+        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+        returns: FFIType.cstring,
+      },
+    });
+
+    const result = lambdaworks_prove_with_pkey(
+      new Uint8Array(Buffer.from(wtnsPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(r1csPath + "\0", "utf8")),
+      new Uint8Array(Buffer.from(pkeyPath + "\0", "utf8"))
+    );
+    return JSON.parse(result.toString());
+  }
+
+  lambdaworks_verify(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof,
+    publicSignals: PublicSignals
+  ): boolean {
+    const {
+      symbols: { lambdaworks_verify },
+    } = dlopen(this.path, {
+      lambdaworks_verify: {
+        args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
+        returns: FFIType.bool,
+      },
+    });
+
+    return lambdaworks_verify(
+      new Uint8Array(Buffer.from(JSON.stringify(vkey) + "\0", "utf8")),
+      new Uint8Array(Buffer.from(JSON.stringify(proof) + "\0", "utf8")),
+      new Uint8Array(Buffer.from(JSON.stringify(publicSignals) + "\0", "utf8"))
+    );
+  }
 }
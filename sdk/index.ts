@@ -1,4 +1,23 @@
 export { isBun, getLibPath, getLibFilename, downloadRelease } from "./common";
 
 // export types as well
-export { ProofWithPublicSignals, ProverBackend } from "./interface";
+export {
+  BenchmarkReport,
+  CircomPrime,
+  FflonkProof,
+  FflonkProofWithPublicSignals,
+  FileFormat,
+  GnarkExport,
+  LogLevel,
+  Groth16VerificationKey,
+  PlonkProofWithPublicSignals,
+  ProofWithPublicSignals,
+  ProofEncoding,
+  ProveRequest,
+  ProverBackend,
+  ProverCapabilities,
+  ProverConfig,
+  R1CSInfo,
+  SolidityCalldata,
+  StageTiming,
+} from "./interface";
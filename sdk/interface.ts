@@ -1,10 +1,156 @@
-import type { Groth16Proof, PublicSignals } from "snarkjs";
+import type { Groth16Proof, PlonkProof, PublicSignals } from "snarkjs";
 
 export type ProofWithPublicSignals = {
   proof: Groth16Proof;
   publicSignals: PublicSignals;
+  /** Solidity calldata, only present if requested while proving. */
+  calldata?: SolidityCalldata;
 };
 
+export type PlonkProofWithPublicSignals = {
+  proof: PlonkProof;
+  publicSignals: PublicSignals;
+};
+
+/** A FFLONK proof as exported by SnarkJS, where points are projective coordinates. */
+export type FflonkProof = {
+  polynomials: { C1: string[]; C2: string[]; W1: string[]; W2: string[] };
+  evaluations: {
+    ql: string;
+    qr: string;
+    qm: string;
+    qo: string;
+    qc: string;
+    s1: string;
+    s2: string;
+    s3: string;
+    a: string;
+    b: string;
+    c: string;
+    z: string;
+    zw: string;
+    t1w: string;
+    t2w: string;
+    inv: string;
+  };
+  protocol: "fflonk";
+  curve: "bn128";
+};
+
+export type FflonkProofWithPublicSignals = {
+  proof: FflonkProof;
+  publicSignals: PublicSignals;
+};
+
+/** Solidity calldata for the `verifyProof` function of a Groth16 verifier contract. */
+export type SolidityCalldata = {
+  /** Calldata as printed by `snarkjs zkey export soliditycalldata`. */
+  text: string;
+  /** ABI-encoded call to `verifyProof` with its selector, as a `0x`-prefixed hex string. */
+  abi: string;
+};
+
+/** A Groth16 verification key, as SnarkJS exports it. */
+export type Groth16VerificationKey = {
+  protocol: "groth16";
+  curve: string;
+  nPublic: number;
+  vk_alpha_1: string[];
+  vk_beta_2: string[][];
+  vk_gamma_2: string[][];
+  vk_delta_2: string[][];
+  vk_alphabeta_12: string[][][];
+  IC: string[][];
+};
+
+/** A prime supported by Circom, i.e. the values of `circom --prime`. */
+export type CircomPrime =
+  | "bn128"
+  | "bls12381"
+  | "bls12377"
+  | "goldilocks"
+  | "grumpkin"
+  | "pallas"
+  | "vesta"
+  | "secq256r1";
+
+/** Statistics of an R1CS file, see `r1cs_info`. */
+export type R1CSInfo = {
+  prime: string;
+  curve: CircomPrime;
+  nWires: number;
+  nPublicOutputs: number;
+  nPublicInputs: number;
+  nPrivateInputs: number;
+  nLabels: number;
+  nConstraints: number;
+  /** Maps the number of terms to the number of linear combinations with that many terms. */
+  lcHistogram: Record<string, number>;
+};
+
+/** A request to prove a circuit with the `prove` function, the required files depend on the backend. */
+export type ProveRequest = {
+  /** Witness file path (`.wtns` or `.json`). */
+  wtnsPath: string;
+  /** Format of the witness file, detected from its contents if not given. */
+  wtnsFormat?: FileFormat;
+  /** R1CS file path, must be JSON for Lambdaworks. */
+  r1csPath?: string;
+  /** Format of the R1CS file, detected from its contents if not given. */
+  r1csFormat?: FileFormat;
+  /** Prover key file path, e.g. `.zkey` for Arkworks & ICICLE. */
+  pkeyPath?: string;
+  /** Device to prove on, for backends with hardware acceleration. */
+  device?: string;
+  /** Installation directory of the device backends, for backends with hardware acceleration. */
+  backendDir?: string;
+  /** Whether to include Solidity calldata in the output. */
+  calldata?: boolean;
+};
+
+/** What a backend of the `prove` function supports, and which files of a `ProveRequest` it requires. */
+export type ProverCapabilities = {
+  curves: CircomPrime[];
+  requiresR1cs: boolean;
+  requiresPkey: boolean;
+  /** Devices that the backend can prove on, empty if the backend only runs on the CPU. */
+  devices: string[];
+};
+
+/** Timings of a stage over all iterations of a benchmark, in milliseconds. */
+export type StageTiming = {
+  stage: string;
+  meanMs: number;
+  minMs: number;
+  maxMs: number;
+};
+
+/** Result of benchmarking a backend on a circuit. */
+export type BenchmarkReport = {
+  backend: string;
+  circuit: string;
+  iterations: number;
+  /** Timings of each stage, e.g. `parse`, `witness_map`, `msm` and `serialize` for Arkworks. */
+  stages: StageTiming[];
+  total: StageTiming;
+};
+
+/** Thread & memory limits of the provers, shared by all proofs of the library. */
+export type ProverConfig = {
+  /** Maximum number of threads per proof, defaults to the number of CPUs. */
+  maxThreads?: number | null;
+  /** Whether each proof gets a thread pool of its own, instead of sharing one pool with the other proofs. */
+  perProofPool?: boolean;
+  /** Memory budget in bytes for the data that a proof decodes at once, e.g. the points of an MSM. */
+  memoryBudget?: number | null;
+};
+
+/** Format of an input file, i.e. the magic of a binary Circom or SnarkJS file, or JSON. */
+export type FileFormat = "wtns" | "r1cs" | "zkey" | "json";
+
+/** Maximum level of the logs of the library, from the least to the most verbose. */
+export type LogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
 export interface ProverBackend {
   /** The path to the shared library. */
   path: string;
@@ -20,43 +166,321 @@ export interface ProverBackend {
   echo(input: string): string;
 
   /**
-   * Prove with Arkworks.
+   * Prove with Arkworks, over BN254 or BLS12-381 depending on the prime of the circuit.
    *
    * @param wtnsPath witness file path (`.json` or `.wtns.json`)
    * @param r1csPath r1cs file path (`.r1cs`)
    * @param zkeyPath proving key file path (`.zkey`)
+   * @param calldata whether to include Solidity calldata in the output
    * @returns SnarkJS Groth16 proof & public signals
    */
   arkworks_prove(
     wtnsPath: string,
     r1csPath: string,
-    pkeyPath: string
+    pkeyPath: string,
+    calldata?: boolean
   ): ProofWithPublicSignals;
 
+  /**
+   * Prove with Arkworks without the R1CS, reading the constraints from the proving key instead.
+   *
+   * The curve is given by the proving key, and the witness must have a value for every signal.
+   *
+   * @param wtnsPath witness file path (`.wtns` or `.json`)
+   * @param pkeyPath proving key file path (`.zkey`)
+   * @param calldata whether to include Solidity calldata in the output
+   * @returns SnarkJS Groth16 proof & public signals
+   */
+  arkworks_prove_zkey(
+    wtnsPath: string,
+    pkeyPath: string,
+    calldata?: boolean
+  ): ProofWithPublicSignals;
+
+  /**
+   * Prove with PLONK, using a SnarkJS PLONK proving key over BN254.
+   *
+   * @param wtnsPath witness file path (`.json` or `.wtns.json`)
+   * @param pkeyPath PLONK proving key file path (`.zkey`)
+   * @returns SnarkJS PLONK proof & public signals
+   */
+  plonk_prove(wtnsPath: string, pkeyPath: string): PlonkProofWithPublicSignals;
+
+  /**
+   * Prove with FFLONK, using a SnarkJS FFLONK proving key over BN254.
+   *
+   * @param wtnsPath witness file path (`.json` or `.wtns.json`)
+   * @param pkeyPath FFLONK proving key file path (`.zkey`)
+   * @returns SnarkJS FFLONK proof & public signals
+   */
+  fflonk_prove(
+    wtnsPath: string,
+    pkeyPath: string
+  ): FflonkProofWithPublicSignals;
+
+  /**
+   * Returns the statistics of an R1CS file.
+   *
+   * @param r1csPath r1cs file path (`.r1cs`)
+   * @returns number of wires, constraints, signals and such
+   */
+  r1cs_info(r1csPath: string): R1CSInfo;
+
+  /**
+   * Renders a Solidity verifier contract for Groth16 proofs over BN254,
+   * with the same ABI as the one exported by SnarkJS.
+   *
+   * @param vkeyPath verification key file path (`.json`)
+   * @returns Solidity source code of the verifier contract
+   */
+  solidity_verifier(vkeyPath: string): string;
+
+  /**
+   * Returns the Solidity calldata for a Groth16 proof over BN254.
+   *
+   * @param proofPath proof file path (`.json`)
+   * @param publicPath public signals file path (`.json`)
+   * @returns calldata as SnarkJS prints it, and its ABI-encoded form
+   */
+  solidity_calldata(proofPath: string, publicPath: string): SolidityCalldata;
+
+  /**
+   * Encodes a Groth16 proof over BN254 into binary, and returns it as a string.
+   *
+   * @param proofPath proof file path (`.json`)
+   * @param compressed whether to compress the points, 128 bytes if compressed and 256 bytes otherwise
+   * @param encoding string encoding of the binary proof
+   * @returns encoded proof
+   */
+  encode_proof(
+    proofPath: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): string;
+
+  /**
+   * Decodes a Groth16 proof over BN254 that was encoded with `encode_proof`.
+   *
+   * @param data encoded proof
+   * @param compressed whether the points are compressed
+   * @param encoding string encoding of the binary proof
+   * @returns SnarkJS Groth16 proof
+   */
+  decode_proof(
+    data: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): Groth16Proof;
+
+  /**
+   * Re-randomizes a Groth16 proof over BN254, so that it can not be linked to the given proof.
+   *
+   * @param vkey verification key of the proof
+   * @param proof proof to re-randomize
+   * @returns a new proof that verifies against the same public signals
+   */
+  rerandomize_proof(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof
+  ): Groth16Proof;
+
+  /**
+   * Converts a Groth16 proof, verification key & public signals over BN254 into gnark's binary format.
+   *
+   * @param proofPath proof file path (`.json`)
+   * @param vkeyPath verification key file path (`.json`)
+   * @param publicPath public signals file path (`.json`)
+   * @param compressed whether to compress the points
+   * @returns hex-encoded proof, verifying key and public witness
+   */
+  gnark_export(
+    proofPath: string,
+    vkeyPath: string,
+    publicPath: string,
+    compressed: boolean
+  ): GnarkExport;
+
   /**
    * Prove with Lambdaworks.
    *
    * @param wtnsPath witness file path (`.json` or `.wtns.json`)
    * @param r1csPath r1cs file path (`.r1cs`)
+   * @param vkeyPath if given, path to write the verification key of the (random) setup to
    * @returns SnarkJS Groth16 proof & public signals
    */
-  lambdaworks_prove(wtnsPath: string, r1csPath: string): ProofWithPublicSignals;
+  lambdaworks_prove(
+    wtnsPath: string,
+    r1csPath: string,
+    vkeyPath?: string
+  ): ProofWithPublicSignals;
 
   /**
-   * Prove with Ingonyama ICICLE.
+   * Run a Lambdaworks setup, so that proofs of the same circuit share a verifier.
+   *
+   * @param r1csPath r1cs file path (`.r1cs.json`)
+   * @param outDir directory to write `lambdaworks_pkey.bin` & `lambdaworks_vkey.json` to
+   * @returns SnarkJS verification key
+   */
+  lambdaworks_setup(
+    r1csPath: string,
+    outDir: string
+  ): Groth16VerificationKey;
+
+  /**
+   * Prove with Lambdaworks, using the keys of `lambdaworks_setup`.
    *
    * @param wtnsPath witness file path (`.json` or `.wtns.json`)
+   * @param r1csPath r1cs file path (`.r1cs.json`)
+   * @param pkeyPath proving key file path (`lambdaworks_pkey.bin`)
+   * @returns SnarkJS Groth16 proof & public signals
+   */
+  lambdaworks_prove_with_pkey(
+    wtnsPath: string,
+    r1csPath: string,
+    pkeyPath: string
+  ): ProofWithPublicSignals;
+
+  /**
+   * Verify a Lambdaworks proof.
+   *
+   * @param vkey verification key, as written by `lambdaworks_prove` or `lambdaworks_setup`
+   * @param proof proof to verify
+   * @param publicSignals public signals of the proof
+   * @returns whether the proof is valid
+   */
+  lambdaworks_verify(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof,
+    publicSignals: PublicSignals
+  ): boolean;
+
+  /**
+   * Prove with Ingonyama ICICLE, requires the library to be built with `icicle` feature.
+   *
+   * @param wtnsPath witness file path (`.wtns`)
    * @param zkeyPath proving key file path (`.zkey`)
    * @param device device to be used for proving
+   * @param calldata whether to include Solidity calldata in the output
+   * @param backendDir installation directory of ICICLE backends, defaults to `ICICLE_BACKEND_INSTALL_DIR` env variable
    * @returns SnarkJS Groth16 proof & public signals
-   * @deprecated **DO NOT USE UNTIL ICICLE IS FIXED**
    */
   icicle_prove(
     wtnsPath: string,
-    r1csPath: string,
-    device: IcicleDevice
+    zkeyPath: string,
+    device: IcicleDevice,
+    calldata?: boolean,
+    backendDir?: string
+  ): ProofWithPublicSignals;
+
+  /**
+   * List the devices that ICICLE could load a backend for, requires the library to be built with `icicle` feature.
+   *
+   * @param backendDir installation directory of ICICLE backends, defaults to `ICICLE_BACKEND_INSTALL_DIR` env variable
+   * @returns names of the available devices, e.g. `["CPU", "CUDA"]`
+   */
+  icicle_list_devices(backendDir?: string): string[];
+
+  /**
+   * Open an ICICLE session for a proving key, which caches the data computed from the key across proofs.
+   *
+   * The session must be closed with `icicle_session_close` once it is no longer needed.
+   *
+   * @param zkeyPath proving key file path (`.zkey`)
+   * @param device device to be used for proving
+   * @param backendDir installation directory of ICICLE backends, defaults to `ICICLE_BACKEND_INSTALL_DIR` env variable
+   * @returns id of the session
+   */
+  icicle_session_open(
+    zkeyPath: string,
+    device: IcicleDevice,
+    backendDir?: string
+  ): number;
+
+  /**
+   * Prove with an open ICICLE session.
+   *
+   * @param sessionId id of the session, as returned by `icicle_session_open`
+   * @param wtnsPath witness file path (`.wtns`)
+   * @param calldata whether to include Solidity calldata in the output
+   * @returns SnarkJS Groth16 proof & public signals
+   */
+  icicle_session_prove(
+    sessionId: number,
+    wtnsPath: string,
+    calldata?: boolean
   ): ProofWithPublicSignals;
+
+  /**
+   * Close an ICICLE session, releasing its cache.
+   *
+   * @param sessionId id of the session, as returned by `icicle_session_open`
+   * @returns whether the session was open
+   */
+  icicle_session_close(sessionId: number): boolean;
+
+  /**
+   * Prove a circuit with the Groth16 backend of the given name.
+   *
+   * @param backend name of the backend, e.g. `arkworks`, `lambdaworks` or `icicle`
+   * @param request witness & other files of the circuit, as the backend requires
+   * @returns SnarkJS Groth16 proof & public signals
+   */
+  prove(backend: string, request: ProveRequest): ProofWithPublicSignals;
+
+  /**
+   * List the backends that `prove` accepts, along with their capabilities.
+   *
+   * @returns capabilities of each backend, by their name
+   */
+  prover_backends(): Record<string, ProverCapabilities>;
+
+  /**
+   * Set the thread & memory limits of the provers, which apply to the proofs that start afterwards.
+   *
+   * The limits apply to Arkworks, the other backends are either single-threaded or run on a device.
+   *
+   * @param config new limits, omitted fields are reset to their defaults; if not given, the limits are left as is
+   * @returns the limits in effect
+   */
+  prover_config(config?: ProverConfig): ProverConfig;
+
+  /**
+   * Set the maximum level of the logs, which are written to `stderr` with the spans of each proof,
+   * e.g. the time spent loading the prover key. Logs are off by default.
+   *
+   * @param level maximum level of the logs
+   * @returns `true` if the level is set
+   */
+  set_log_level(level: LogLevel): boolean;
+
+  /**
+   * Benchmark a backend by proving a witness for a number of iterations.
+   *
+   * @param backend name of the backend, e.g. `arkworks`
+   * @param r1csPath r1cs file path, can be empty if the backend does not require it
+   * @param wtnsPath witness file path (`.wtns` or `.json`)
+   * @param pkeyPath prover key file path, can be empty if the backend does not require it
+   * @param iterations number of proofs
+   * @returns mean, min & max time of each stage
+   */
+  benchmark(
+    backend: string,
+    r1csPath: string,
+    wtnsPath: string,
+    pkeyPath: string,
+    iterations?: number
+  ): BenchmarkReport;
 }
 
+/** Proof, verifying key and public witness in gnark's binary format, as hex strings. */
+export type GnarkExport = {
+  proof: string;
+  verifyingKey: string;
+  publicWitness: string;
+};
+
+/** String encodings for binary proofs. */
+export type ProofEncoding = "hex" | "base64";
+
 /** Devices supported by ICICLE prover. */
 export type IcicleDevice = "CPU" | "CUDA" | "METAL";
@@ -5,15 +5,30 @@ import {
   type load as ffiLoad,
 } from "ffi-rs";
 
+import type { Groth16Proof, PublicSignals } from "snarkjs";
 import type {
+  BenchmarkReport,
+  FflonkProofWithPublicSignals,
+  GnarkExport,
+  Groth16VerificationKey,
   IcicleDevice,
+  LogLevel,
+  PlonkProofWithPublicSignals,
+  ProofEncoding,
   ProofWithPublicSignals,
+  ProveRequest,
   ProverBackend,
+  ProverCapabilities,
+  ProverConfig,
+  R1CSInfo,
+  SolidityCalldata,
 } from "./interface";
 import { existsSync } from "fs";
 import { isBun } from "./common";
 
 const DataTypeString = 0 satisfies DataType.String;
+const DataTypeI32 = 1 satisfies DataType.I32;
+const DataTypeBoolean = 6 satisfies DataType.Boolean;
 
 /**
  * A prover backend for Node environments.
@@ -42,6 +57,12 @@ export class CircomkitFFINode implements ProverBackend {
   /** Whether the `lib` is open. */
   isOpen = false;
 
+  /** Ids of the open ICICLE sessions, which keep the `lib` open as they live in its memory. */
+  private readonly icicleSessions = new Set<number>();
+
+  /** Level of the logs, which keeps the `lib` open unless it is `off` as the level lives in its memory. */
+  private logLevel: LogLevel = "off";
+
   /** Whether the environment is Bun, required for encoding correctly. */
   private readonly isBun = isBun();
 
@@ -78,15 +99,204 @@ export class CircomkitFFINode implements ProverBackend {
   arkworks_prove(
     wtnsPath: string,
     r1csPath: string,
-    pkeyPath: string
+    pkeyPath: string,
+    calldata: boolean = false
   ): ProofWithPublicSignals {
     this.openIfClosed();
     const result = this.load({
       library: this.LIBRARY_NAME,
       funcName: "arkworks_prove",
-      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      paramsType: [
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeBoolean,
+      ],
       retType: DataTypeString,
-      paramsValue: [wtnsPath, r1csPath, pkeyPath].map(this.mapInput),
+      paramsValue: [
+        ...[wtnsPath, r1csPath, pkeyPath].map(this.mapInput),
+        calldata,
+      ],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  arkworks_prove_zkey(
+    wtnsPath: string,
+    pkeyPath: string,
+    calldata: boolean = false
+  ): ProofWithPublicSignals {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "arkworks_prove_zkey",
+      paramsType: [DataTypeString, DataTypeString, DataTypeBoolean],
+      retType: DataTypeString,
+      paramsValue: [...[wtnsPath, pkeyPath].map(this.mapInput), calldata],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  plonk_prove(
+    wtnsPath: string,
+    pkeyPath: string
+  ): PlonkProofWithPublicSignals {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "plonk_prove",
+      paramsType: [DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [wtnsPath, pkeyPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  fflonk_prove(
+    wtnsPath: string,
+    pkeyPath: string
+  ): FflonkProofWithPublicSignals {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "fflonk_prove",
+      paramsType: [DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [wtnsPath, pkeyPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  r1cs_info(r1csPath: string): R1CSInfo {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "r1cs_info",
+      paramsType: [DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [r1csPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  solidity_verifier(vkeyPath: string): string {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "solidity_verifier",
+      paramsType: [DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [vkeyPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return result;
+  }
+
+  solidity_calldata(proofPath: string, publicPath: string): SolidityCalldata {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "solidity_calldata",
+      paramsType: [DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [proofPath, publicPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  encode_proof(
+    proofPath: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): string {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "encode_proof",
+      paramsType: [DataTypeString, DataTypeBoolean, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [
+        this.mapInput(proofPath),
+        compressed,
+        this.mapInput(encoding),
+      ],
+    });
+    this.closeIfOpen();
+
+    return result;
+  }
+
+  decode_proof(
+    data: string,
+    compressed: boolean,
+    encoding: ProofEncoding
+  ): Groth16Proof {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "decode_proof",
+      paramsType: [DataTypeString, DataTypeBoolean, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [this.mapInput(data), compressed, this.mapInput(encoding)],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  rerandomize_proof(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof
+  ): Groth16Proof {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "rerandomize_proof",
+      paramsType: [DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [JSON.stringify(vkey), JSON.stringify(proof)].map(
+        this.mapInput
+      ),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  gnark_export(
+    proofPath: string,
+    vkeyPath: string,
+    publicPath: string,
+    compressed: boolean
+  ): GnarkExport {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "gnark_export",
+      paramsType: [
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeBoolean,
+      ],
+      retType: DataTypeString,
+      paramsValue: [
+        ...[proofPath, vkeyPath, publicPath].map(this.mapInput),
+        compressed,
+      ],
     });
     this.closeIfOpen();
 
@@ -96,43 +306,259 @@ export class CircomkitFFINode implements ProverBackend {
   icicle_prove(
     wtnsPath: string,
     pkeyPath: string,
-    device: IcicleDevice
+    device: IcicleDevice,
+    calldata: boolean = false,
+    backendDir: string = ""
   ): ProofWithPublicSignals {
     this.openIfClosed();
     const result = this.load({
       library: this.LIBRARY_NAME,
       funcName: "icicle_prove",
-      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      paramsType: [
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeBoolean,
+      ],
       retType: DataTypeString,
-      paramsValue: [wtnsPath, pkeyPath, device].map(this.mapInput),
+      paramsValue: [
+        ...[wtnsPath, pkeyPath, device, backendDir].map(this.mapInput),
+        calldata,
+      ],
     });
     this.closeIfOpen();
 
     return JSON.parse(result);
   }
 
-  lambdaworks_prove(
+  icicle_list_devices(backendDir: string = ""): string[] {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "icicle_list_devices",
+      paramsType: [DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [backendDir].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  icicle_session_open(
+    pkeyPath: string,
+    device: IcicleDevice,
+    backendDir: string = ""
+  ): number {
+    this.openIfClosed();
+    const sessionId = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "icicle_session_open",
+      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      retType: DataTypeI32,
+      paramsValue: [pkeyPath, device, backendDir].map(this.mapInput),
+    });
+    this.icicleSessions.add(sessionId);
+
+    return sessionId;
+  }
+
+  icicle_session_prove(
+    sessionId: number,
     wtnsPath: string,
-    r1csPath: string
+    calldata: boolean = false
   ): ProofWithPublicSignals {
-    // make sure r1cs path is JSON
-    if (!r1csPath.endsWith(".json")) {
-      throw new Error("r1csPath must be a JSON file");
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "icicle_session_prove",
+      paramsType: [DataTypeI32, DataTypeString, DataTypeBoolean],
+      retType: DataTypeString,
+      paramsValue: [sessionId, this.mapInput(wtnsPath), calldata],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  icicle_session_close(sessionId: number): boolean {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "icicle_session_close",
+      paramsType: [DataTypeI32],
+      retType: DataTypeBoolean,
+      paramsValue: [sessionId],
+    });
+    this.icicleSessions.delete(sessionId);
+    this.closeIfOpen();
+
+    return result;
+  }
+
+  prove(backend: string, request: ProveRequest): ProofWithPublicSignals {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "prove",
+      paramsType: [DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [backend, JSON.stringify(request)].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  prover_backends(): Record<string, ProverCapabilities> {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "prover_backends",
+      paramsType: [],
+      retType: DataTypeString,
+      paramsValue: [],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  benchmark(
+    backend: string,
+    r1csPath: string,
+    wtnsPath: string,
+    pkeyPath: string,
+    iterations: number = 5
+  ): BenchmarkReport {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "benchmark",
+      paramsType: [
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeString,
+        DataTypeI32,
+      ],
+      retType: DataTypeString,
+      paramsValue: [
+        ...[backend, r1csPath, wtnsPath, pkeyPath].map(this.mapInput),
+        iterations,
+      ],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  prover_config(config?: ProverConfig): ProverConfig {
+    const configJson = config ? JSON.stringify(config) : "";
+
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "prover_config",
+      paramsType: [DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [this.mapInput(configJson)],
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  set_log_level(level: LogLevel): boolean {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "set_log_level",
+      paramsType: [DataTypeString],
+      retType: DataTypeBoolean,
+      paramsValue: [this.mapInput(level)],
+    });
+    if (result) {
+      this.logLevel = level;
     }
+    this.closeIfOpen();
+
+    return result;
+  }
 
+  lambdaworks_prove(
+    wtnsPath: string,
+    r1csPath: string,
+    vkeyPath: string = ""
+  ): ProofWithPublicSignals {
     this.openIfClosed();
     const result = this.load({
       library: this.LIBRARY_NAME,
       funcName: "lambdaworks_prove",
+      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [wtnsPath, r1csPath, vkeyPath].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  lambdaworks_setup(r1csPath: string, outDir: string): Groth16VerificationKey {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "lambdaworks_setup",
       paramsType: [DataTypeString, DataTypeString],
       retType: DataTypeString,
-      paramsValue: [wtnsPath, r1csPath].map(this.mapInput),
+      paramsValue: [r1csPath, outDir].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return JSON.parse(result);
+  }
+
+  lambdaworks_prove_with_pkey(
+    wtnsPath: string,
+    r1csPath: string,
+    pkeyPath: string
+  ): ProofWithPublicSignals {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "lambdaworks_prove_with_pkey",
+      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      retType: DataTypeString,
+      paramsValue: [wtnsPath, r1csPath, pkeyPath].map(this.mapInput),
     });
     this.closeIfOpen();
 
     return JSON.parse(result);
   }
 
+  lambdaworks_verify(
+    vkey: Groth16VerificationKey,
+    proof: Groth16Proof,
+    publicSignals: PublicSignals
+  ): boolean {
+    this.openIfClosed();
+    const result = this.load({
+      library: this.LIBRARY_NAME,
+      funcName: "lambdaworks_verify",
+      paramsType: [DataTypeString, DataTypeString, DataTypeString],
+      retType: DataTypeBoolean,
+      paramsValue: [
+        JSON.stringify(vkey),
+        JSON.stringify(proof),
+        JSON.stringify(publicSignals),
+      ].map(this.mapInput),
+    });
+    this.closeIfOpen();
+
+    return result;
+  }
+
   /** Opens the library for usage, must be done prior to calling functions here. */
   private openIfClosed() {
     if (!this.isOpen) {
@@ -144,9 +570,12 @@ export class CircomkitFFINode implements ProverBackend {
     }
   }
 
-  /** Closes the library & frees its memory. */
-  private closeIfOpen() {
-    if (this.isOpen) {
+  /** Closes the library & frees its memory, unless an ICICLE session is still open or logs are on. */
+  private closeIfOpen(force = false) {
+    if (
+      this.isOpen &&
+      (force || (this.icicleSessions.size === 0 && this.logLevel === "off"))
+    ) {
       this.close(this.LIBRARY_NAME);
       this.isOpen = false;
     }
@@ -159,6 +588,6 @@ export class CircomkitFFINode implements ProverBackend {
 
   // additional safety measure
   [Symbol.dispose]() {
-    this.closeIfOpen();
+    this.closeIfOpen(true);
   }
 }
@@ -1,63 +1,52 @@
 use ark_bn254::{Bn254, Fr};
-use ark_circom::{
-    circom::{R1CSFile, R1CS},
-    read_zkey, CircomBuilder, CircomCircuit, CircomConfig, CircomReduction,
-};
+use ark_circom::{CircomBuilder, CircomCircuit, CircomConfig, CircomReduction};
+use ark_ec::pairing::Pairing;
 use ark_ff::PrimeField;
-use ark_groth16::{Groth16, Proof, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
-use ark_serialize::SerializationError;
 use ark_std::rand::thread_rng;
 use eyre::Result;
-use std::{
-    fmt::Debug,
-    fs::File,
-    io::{self, BufReader},
-    path::Path,
-    str::FromStr,
-};
+use std::{io, path::Path};
 
-use crate::witness::parse_witness_to_elems;
-
-#[inline(always)]
-pub fn load_r1cs<F: PrimeField>(
-    r1cs_path: impl AsRef<Path>,
-) -> Result<R1CS<F>, SerializationError> {
-    let reader = BufReader::new(File::open(r1cs_path)?);
-    Ok(R1CSFile::new(reader)?.into())
-}
+use super::{read_proving_key, CircomCurve};
+use crate::prime::CircomPrime;
+use crate::witness::{parse_witness_json, parse_witness_to_elems};
 
 /// Loads proving key (which can generate verification key too) from an existing `zKey` file.
 #[inline(always)]
-pub fn load_proving_key(pkey_path: impl AsRef<Path>) -> Result<ProvingKey<Bn254>> {
-    let mut reader = BufReader::new(File::open(pkey_path)?);
-    let (params, _) = read_zkey(&mut reader)?;
-
-    Ok(params)
+#[tracing::instrument(skip_all, fields(backend = "arkworks", path = %pkey_path.as_ref().display()))]
+pub fn load_proving_key<E: CircomCurve>(pkey_path: impl AsRef<Path>) -> Result<ProvingKey<E>> {
+    read_proving_key(&std::fs::read(pkey_path)?)
 }
 
-/// Loads a witness from witness JSON file.
+/// Loads a witness from witness JSON file, see [`parse_witness_json`] for the values that it accepts.
+#[tracing::instrument(
+    name = "load_witness",
+    skip_all,
+    fields(backend = "arkworks", path = %wtns_json_path.as_ref().display()),
+)]
 pub fn load_witness_json<F: PrimeField>(
     wtns_json_path: impl AsRef<Path>,
-) -> Result<Vec<F>, io::Error>
-where
-    <F as FromStr>::Err: Debug,
-{
-    let f = File::open(wtns_json_path)?;
-    let wtns: Vec<String> = serde_json::from_reader(BufReader::new(f))?;
-
-    Ok(wtns.iter().map(|s| F::from_str(s).unwrap()).collect())
+) -> Result<Vec<F>, io::Error> {
+    let json = std::fs::read_to_string(wtns_json_path)?;
+    let wtns = parse_witness_json(&json, &F::MODULUS.into())?;
+
+    Ok(wtns.into_iter().map(F::from).collect())
 }
 
-/// Loads a witness from raw witness file.
+/// Loads a witness from raw witness file, which must be over the prime of the given field.
+#[tracing::instrument(skip_all, fields(backend = "arkworks", path = %wtns_path.as_ref().display()))]
 pub fn load_witness<F: PrimeField>(wtns_path: impl AsRef<Path>) -> Result<Vec<F>, io::Error> {
+    let prime = CircomPrime::from_modulus(&F::MODULUS.into())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
     let wtns_data = std::fs::read(wtns_path)?;
-    parse_witness_to_elems(&wtns_data, F::from_le_bytes_mod_order)
+    parse_witness_to_elems(&wtns_data, prime, F::from_le_bytes_mod_order)
 }
 
 /// Creates a circuit by computing the witness from the given inputs.
 ///
 /// This makes use of WASM as well, so it may not necessarily provide advantages for witness computation.
+#[tracing::instrument(skip_all, fields(backend = "arkworks"))]
 pub fn compute_witness<F: PrimeField>(
     cfg: CircomConfig<F>,
     inputs: Vec<(impl ToString, impl Into<num_bigint::BigInt>)>,
@@ -107,11 +96,12 @@ pub fn setup_circom_bn254_circuit(
 
 /// Creates a proof from a circuit with public inputs fed into.
 #[inline(always)]
-pub fn prove_circuit(
-    circuit: CircomCircuit<ark_bn254::Fr>,
-    pkey: &ProvingKey<Bn254>,
-) -> Result<Proof<Bn254>, SynthesisError> {
-    Groth16::<Bn254, CircomReduction>::create_random_proof_with_reduction(
+#[tracing::instrument(skip_all, fields(backend = "arkworks"))]
+pub fn prove_circuit<E: Pairing>(
+    circuit: CircomCircuit<E::ScalarField>,
+    pkey: &ProvingKey<E>,
+) -> Result<Proof<E>, SynthesisError> {
+    Groth16::<E, CircomReduction>::create_random_proof_with_reduction(
         circuit,
         pkey,
         &mut thread_rng(),
@@ -120,13 +110,13 @@ pub fn prove_circuit(
 
 /// Verifies a proof with public inputs.
 #[inline(always)]
-pub fn verify(
-    proof: &Proof<Bn254>,
-    public_inputs: &[Fr],
-    proving_key: &ProvingKey<Bn254>,
+pub fn verify<E: Pairing>(
+    proof: &Proof<E>,
+    public_inputs: &[E::ScalarField],
+    verifying_key: &VerifyingKey<E>,
 ) -> Result<bool, SynthesisError> {
-    Groth16::<Bn254, CircomReduction>::verify_proof(
-        &ark_groth16::prepare_verifying_key(&proving_key.vk),
+    Groth16::<E, CircomReduction>::verify_proof(
+        &ark_groth16::prepare_verifying_key(verifying_key),
         proof,
         public_inputs,
     )
@@ -0,0 +1,70 @@
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::{
+    pairing::Pairing,
+    short_weierstrass::{Affine, SWCurveConfig},
+};
+use ark_ff::{BigInt, Field, Fp, MontBackend, MontConfig, PrimeField};
+
+/// A pairing-friendly curve that Circom can compile circuits for, i.e. with `circom --prime`.
+pub trait CircomCurve:
+    Pairing<G1Affine = Affine<Self::G1Config>, G2Affine = Affine<Self::G2Config>>
+{
+    /// The base field of G1.
+    type Fq: MontgomeryField;
+    /// The base field of G2, as an extension of [`Self::Fq`].
+    type Fq2: Field<BasePrimeField = Self::Fq>;
+    type G1Config: SWCurveConfig<ScalarField = Self::ScalarField, BaseField = Self::Fq>;
+    type G2Config: SWCurveConfig<ScalarField = Self::ScalarField, BaseField = Self::Fq2>;
+
+    /// Name of the curve as SnarkJS calls it, e.g. in the `curve` field of proofs.
+    const NAME: &'static str;
+}
+
+impl CircomCurve for Bn254 {
+    type Fq = ark_bn254::Fq;
+    type Fq2 = ark_bn254::Fq2;
+    type G1Config = ark_bn254::g1::Config;
+    type G2Config = ark_bn254::g2::Config;
+
+    const NAME: &'static str = "bn128";
+}
+
+impl CircomCurve for Bls12_381 {
+    type Fq = ark_bls12_381::Fq;
+    type Fq2 = ark_bls12_381::Fq2;
+    type G1Config = ark_bls12_381::g1::Config;
+    type G2Config = ark_bls12_381::g2::Config;
+
+    const NAME: &'static str = "bls12381";
+}
+
+/// A prime field in Montgomery form, whose elements can be taken as they are from files that store them
+/// in Montgomery form with the same factor, i.e. `R = 2^(8 * n8)` where `n8` is the size of [`BigInt`] in bytes.
+pub trait MontgomeryField: PrimeField {
+    /// Number of bytes of an element.
+    const N8: usize;
+
+    /// Creates an element from the little-endian bytes of its Montgomery form, without any arithmetic.
+    ///
+    /// Returns `None` if the bytes are not of length [`Self::N8`], or if they are not smaller than the modulus.
+    fn from_montgomery_le(bytes: &[u8]) -> Option<Self>;
+}
+
+impl<T: MontConfig<N>, const N: usize> MontgomeryField for Fp<MontBackend<T, N>, N> {
+    const N8: usize = N * 8;
+
+    fn from_montgomery_le(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::N8 {
+            return None;
+        }
+
+        let mut limbs = [0u64; N];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().ok()?);
+        }
+        let value = BigInt::new(limbs);
+
+        (value < T::MODULUS).then(|| Self::new_unchecked(value))
+    }
+}
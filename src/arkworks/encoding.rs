@@ -0,0 +1,174 @@
+use ark_bn254::Bn254;
+use ark_groth16::Proof;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use eyre::{eyre, Result};
+use std::str::FromStr;
+
+use crate::snarkjs::SnarkjsGroth16Proof;
+
+/// Size of a compressed Groth16 proof over BN254, in bytes.
+///
+/// This is the concatenation of compressed `A` (32 bytes), `B` (64 bytes) and `C` (32 bytes),
+/// each in Arkworks' little-endian encoding with the flags at the most significant bits.
+pub const COMPRESSED_PROOF_SIZE: usize = 128;
+
+/// Size of an uncompressed Groth16 proof over BN254, in bytes.
+pub const UNCOMPRESSED_PROOF_SIZE: usize = 256;
+
+/// Text encoding for binary proofs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Lowercase hexadecimal, without `0x` prefix.
+    Hex,
+    /// Standard Base64 with padding.
+    Base64,
+}
+
+impl FromStr for TextEncoding {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "hex" => Ok(Self::Hex),
+            "base64" => Ok(Self::Base64),
+            _ => Err(eyre!("encoding must be one of [hex, base64], got {}", s)),
+        }
+    }
+}
+
+impl TextEncoding {
+    /// Encodes the given bytes into a string.
+    pub fn encode(&self, bytes: impl AsRef<[u8]>) -> String {
+        match self {
+            Self::Hex => hex::encode(bytes),
+            Self::Base64 => BASE64.encode(bytes),
+        }
+    }
+
+    /// Decodes the given string into bytes, a `0x` prefix is allowed for hex strings.
+    pub fn decode(&self, data: &str) -> Result<Vec<u8>> {
+        let data = data.trim();
+        match self {
+            Self::Hex => Ok(hex::decode(data.strip_prefix("0x").unwrap_or(data))?),
+            Self::Base64 => Ok(BASE64.decode(data)?),
+        }
+    }
+}
+
+/// Serializes a proof, either compressed or uncompressed.
+pub fn encode_proof(proof: &Proof<Bn254>, compress: bool) -> Result<Vec<u8>> {
+    let compress = if compress {
+        Compress::Yes
+    } else {
+        Compress::No
+    };
+    let mut bytes = Vec::with_capacity(proof.serialized_size(compress));
+    proof.serialize_with_mode(&mut bytes, compress)?;
+
+    Ok(bytes)
+}
+
+/// Deserializes a proof, either compressed or uncompressed.
+///
+/// The points are checked to be on the curve & within the correct subgroup.
+pub fn decode_proof(bytes: &[u8], compress: bool) -> Result<Proof<Bn254>> {
+    let (compress, expected_size) = if compress {
+        (Compress::Yes, COMPRESSED_PROOF_SIZE)
+    } else {
+        (Compress::No, UNCOMPRESSED_PROOF_SIZE)
+    };
+    if bytes.len() != expected_size {
+        return Err(eyre!(
+            "expected {} bytes for the proof, got {}",
+            expected_size,
+            bytes.len()
+        ));
+    }
+
+    Ok(Proof::deserialize_with_mode(
+        bytes,
+        compress,
+        Validate::Yes,
+    )?)
+}
+
+/// Serializes a proof into its compressed 128-byte form.
+pub fn to_compressed_bytes(proof: &Proof<Bn254>) -> [u8; COMPRESSED_PROOF_SIZE] {
+    let mut bytes = [0u8; COMPRESSED_PROOF_SIZE];
+    proof
+        .serialize_compressed(&mut bytes[..])
+        .expect("compressed proof should fit in 128 bytes");
+
+    bytes
+}
+
+/// Deserializes a proof from its compressed 128-byte form.
+pub fn from_compressed_bytes(bytes: &[u8; COMPRESSED_PROOF_SIZE]) -> Result<Proof<Bn254>> {
+    decode_proof(bytes, true)
+}
+
+impl SnarkjsGroth16Proof {
+    /// Serializes a BN254 proof, either compressed or uncompressed.
+    pub fn to_bytes(&self, compress: bool) -> Result<Vec<u8>> {
+        encode_proof(&Proof::try_from(self)?, compress)
+    }
+
+    /// Deserializes a BN254 proof, either compressed or uncompressed.
+    pub fn from_bytes(bytes: &[u8], compress: bool) -> Result<Self> {
+        decode_proof(bytes, compress).map(Self::from)
+    }
+
+    /// Serializes a BN254 proof & encodes it as a string.
+    pub fn to_encoded(&self, compress: bool, encoding: TextEncoding) -> Result<String> {
+        Ok(encoding.encode(self.to_bytes(compress)?))
+    }
+
+    /// Decodes a string & deserializes a BN254 proof from it.
+    pub fn from_encoded(data: &str, compress: bool, encoding: TextEncoding) -> Result<Self> {
+        Self::from_bytes(&encoding.decode(data)?, compress)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::read_snarkjs_json;
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_proof_encoding() -> eyre::Result<()> {
+        let proof_path = Path::new("example/build")
+            .join(CIRCUIT)
+            .join("default")
+            .join("groth16_proof.json");
+        let proof: SnarkjsGroth16Proof = read_snarkjs_json(proof_path)?;
+
+        for compress in [true, false] {
+            let bytes = proof.to_bytes(compress)?;
+            let expected_size = if compress {
+                COMPRESSED_PROOF_SIZE
+            } else {
+                UNCOMPRESSED_PROOF_SIZE
+            };
+            assert_eq!(bytes.len(), expected_size);
+
+            for encoding in [TextEncoding::Hex, TextEncoding::Base64] {
+                let encoded = proof.to_encoded(compress, encoding)?;
+                let decoded = SnarkjsGroth16Proof::from_encoded(&encoded, compress, encoding)?;
+                assert_eq!(decoded.pi_a, proof.pi_a);
+                assert_eq!(decoded.pi_b, proof.pi_b);
+                assert_eq!(decoded.pi_c, proof.pi_c);
+            }
+        }
+
+        let ark_proof = Proof::try_from(&proof)?;
+        let compressed = to_compressed_bytes(&ark_proof);
+        assert_eq!(from_compressed_bytes(&compressed)?, ark_proof);
+        assert!(decode_proof(&compressed[1..], true).is_err());
+
+        Ok(())
+    }
+}
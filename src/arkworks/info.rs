@@ -0,0 +1,65 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::r1cs::{read_constraints, R1CSHeader};
+use crate::{binfile::BinFile, prime::CircomPrime};
+
+/// Statistics of an R1CS file, similar to what `snarkjs r1cs info` prints.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct R1CSInfo {
+    /// The prime of the scalar field, as a decimal string.
+    pub prime: String,
+    /// Name of the prime, as Circom & SnarkJS name it.
+    pub curve: CircomPrime,
+    /// Number of wires, including the constant wire.
+    pub n_wires: u32,
+    /// Number of public outputs.
+    pub n_public_outputs: u32,
+    /// Number of public inputs.
+    pub n_public_inputs: u32,
+    /// Number of private inputs.
+    pub n_private_inputs: u32,
+    /// Number of labels, i.e. signals before optimizations.
+    pub n_labels: u64,
+    /// Number of constraints.
+    pub n_constraints: u32,
+    /// Histogram of linear combination sizes, mapping the number of terms
+    /// to the number of linear combinations (within `A`, `B` and `C`) with that many terms.
+    pub lc_histogram: BTreeMap<usize, usize>,
+}
+
+impl std::fmt::Display for R1CSInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+impl R1CSInfo {
+    /// Reads the statistics from the contents of an R1CS file, over any prime known to Circom.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let file = BinFile::parse(bytes, b"r1cs")?;
+        let header = R1CSHeader::read(&file)?;
+
+        // only the number of terms is needed, so the terms `(wire, coefficient)` are skipped
+        let mut lc_histogram = BTreeMap::new();
+        read_constraints(&file, &header, |section, n_terms| {
+            section.read_bytes(n_terms * (4 + header.field_size))?;
+            *lc_histogram.entry(n_terms).or_insert(0) += 1;
+            Ok(())
+        })?;
+
+        Ok(Self {
+            prime: header.prime.modulus().to_string(),
+            curve: header.prime,
+            n_wires: header.n_wires,
+            n_public_outputs: header.n_pub_out,
+            n_public_inputs: header.n_pub_in,
+            n_private_inputs: header.n_prv_in,
+            n_labels: header.n_labels,
+            n_constraints: header.n_constraints,
+            lc_histogram,
+        })
+    }
+}
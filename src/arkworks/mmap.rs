@@ -0,0 +1,330 @@
+//! Groth16 prover that reads the queries of a `.zkey` file lazily from a memory map.
+//!
+//! The `A`, `B` and `C` queries take `O(nVars)` points and the `H` query `O(domainSize)` points, which
+//! add up to several gigabytes for circuits with millions of constraints. Instead of decoding them all
+//! into a [`ProvingKey`](ark_groth16::ProvingKey) up front, each query is decoded in chunks of
+//! [`CHUNK_SIZE`] points right before its MSM, so that at most one chunk is resident at a time.
+//! The chunks are sized by the memory budget instead if one is configured, see [`crate::config`].
+
+use ark_circom::{CircomCircuit, CircomReduction};
+use ark_ec::{
+    short_weierstrass::{Affine, Projective, SWCurveConfig},
+    CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{Field, UniformRand, Zero};
+use ark_groth16::{r1cs_to_qap::R1CSToQAP, Proof, VerifyingKey};
+use ark_poly::GeneralEvaluationDomain;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+use ark_std::rand::Rng;
+use eyre::{eyre, OptionExt, Result};
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::{fs::File, path::Path};
+
+use super::zkey::{
+    Montgomery, ZkeyHeader, A_SECTION, B1_SECTION, B2_SECTION, C_SECTION, H_SECTION,
+};
+use super::{CircomCurve, MontgomeryField};
+use crate::binfile::{BinFile, SectionReader};
+use crate::config::ProverConfig;
+
+/// Number of points that are decoded at once for an MSM, without a memory budget.
+pub const CHUNK_SIZE: usize = 1 << 16;
+
+/// Full assignment of the signals, along with the coefficients of `h` for it.
+type WitnessMap<F> = (Vec<F>, Vec<F>);
+
+/// A Groth16 proving key backed by a memory-mapped `.zkey` file.
+///
+/// Only the header & the verifying key are decoded when the key is opened; the queries are read
+/// from the file while proving, see [`MmapProvingKey::prove`].
+pub struct MmapProvingKey<E: CircomCurve> {
+    mmap: Mmap,
+    header: ZkeyHeader<E>,
+    vk: VerifyingKey<E>,
+}
+
+impl<E: CircomCurve> MmapProvingKey<E> {
+    /// Memory-maps a `.zkey` file, and reads its header.
+    ///
+    /// The file must not be modified while the key is alive, as its contents are read during proofs.
+    #[tracing::instrument(
+        name = "load_proving_key",
+        skip_all,
+        fields(backend = "arkworks", path = %pkey_path.as_ref().display()),
+    )]
+    pub fn open(pkey_path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(pkey_path)?;
+        // SAFETY: the map is read-only, and zkeys are not expected to change while proving
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let bin = BinFile::parse(&mmap, b"zkey")?;
+        let header = ZkeyHeader::<E>::read(&bin)?;
+        let vk = header.read_verifying_key(&bin)?;
+
+        Ok(Self { mmap, header, vk })
+    }
+
+    /// The verifying key of this proving key.
+    pub fn verifying_key(&self) -> &VerifyingKey<E> {
+        &self.vk
+    }
+
+    /// Number of signals of the circuit, including the constant signal.
+    pub fn n_vars(&self) -> usize {
+        self.header.n_vars
+    }
+
+    /// Proves a circuit with an explicit witness, streaming the queries from the file.
+    pub fn prove<R: Rng>(
+        &self,
+        circuit: CircomCircuit<E::ScalarField>,
+        rng: &mut R,
+    ) -> Result<Proof<E>> {
+        let (assignment, h) = self.witness_map(circuit)?;
+        self.prove_with_qap(&assignment, &h, &crate::config::config(), rng)
+    }
+
+    /// Proves a witness without the R1CS of its circuit, using the constraints in the coefficients section of the file.
+    ///
+    /// The witness is the full assignment of the signals in their order within the `.zkey`, as in a `.wtns` file.
+    pub fn prove_witness<R: Rng>(
+        &self,
+        witness: &[E::ScalarField],
+        rng: &mut R,
+    ) -> Result<Proof<E>> {
+        let h = self.witness_map_from_zkey(witness)?;
+        self.prove_with_qap(witness, &h, &crate::config::config(), rng)
+    }
+
+    /// Returns the full assignment of a circuit with an explicit witness, along with the coefficients of `h` for it.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) fn witness_map(
+        &self,
+        circuit: CircomCircuit<E::ScalarField>,
+    ) -> Result<WitnessMap<E::ScalarField>> {
+        // synthesize the circuit to get the full assignment & the coefficients of `h`, as Arkworks does
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let h = CircomReduction::witness_map::<
+            E::ScalarField,
+            GeneralEvaluationDomain<E::ScalarField>,
+        >(cs.clone())?;
+        let assignment = {
+            let cs = cs
+                .borrow()
+                .ok_or_eyre("could not borrow constraint system")?;
+            [
+                cs.instance_assignment.as_slice(),
+                cs.witness_assignment.as_slice(),
+            ]
+            .concat()
+        };
+        drop(cs);
+
+        Ok((assignment, h))
+    }
+
+    /// Returns the coefficients of `h` for a full assignment of the signals, see [`Self::prove_witness`].
+    #[tracing::instrument(name = "witness_map", level = "debug", skip_all)]
+    pub(crate) fn witness_map_from_zkey(
+        &self,
+        witness: &[E::ScalarField],
+    ) -> Result<Vec<E::ScalarField>> {
+        self.check_witness_len(witness)?;
+
+        let file = BinFile::parse(&self.mmap, b"zkey")?;
+        let matrices = self.header.read_matrices(&file)?;
+        let h = CircomReduction::witness_map_from_matrices::<
+            E::ScalarField,
+            GeneralEvaluationDomain<E::ScalarField>,
+        >(
+            &matrices,
+            matrices.num_instance_variables,
+            matrices.num_constraints,
+            witness,
+        )?;
+
+        Ok(h)
+    }
+
+    /// Proves a full assignment of the signals, given the coefficients of `h` for it, i.e. computes the MSMs.
+    ///
+    /// The points are decoded in chunks that fit in the memory budget of the given configuration.
+    #[tracing::instrument(name = "msm", level = "debug", skip_all)]
+    pub(crate) fn prove_with_qap<R: Rng>(
+        &self,
+        assignment: &[E::ScalarField],
+        h: &[E::ScalarField],
+        config: &ProverConfig,
+        rng: &mut R,
+    ) -> Result<Proof<E>> {
+        let header = &self.header;
+        self.check_witness_len(assignment)?;
+        if h.len() > header.domain_size {
+            return Err(eyre!(
+                "expected a domain of size {}, got {}",
+                header.domain_size,
+                h.len()
+            ));
+        }
+
+        let file = BinFile::parse(&self.mmap, b"zkey")?;
+        let r = E::ScalarField::rand(rng);
+        let s = E::ScalarField::rand(rng);
+
+        // the first entry of each query is for the constant signal, which is part of the assignment
+        let g_a = msm_section::<_, E::G1Config>(&header.fq, &file, A_SECTION, assignment, config)?
+            + header.alpha_g1
+            + header.delta_g1 * r;
+        let g1_b =
+            msm_section::<_, E::G1Config>(&header.fq, &file, B1_SECTION, assignment, config)?
+                + header.beta_g1
+                + header.delta_g1 * s;
+        let g2_b =
+            msm_section::<_, E::G2Config>(&header.fq, &file, B2_SECTION, assignment, config)?
+                + header.beta_g2
+                + header.delta_g2 * s;
+        let l_acc = msm_section::<_, E::G1Config>(
+            &header.fq,
+            &file,
+            C_SECTION,
+            &assignment[header.n_public + 1..],
+            config,
+        )?;
+        let h_acc = msm_section::<_, E::G1Config>(&header.fq, &file, H_SECTION, h, config)?;
+        let g_c = g_a * s + g1_b * r - header.delta_g1 * (r * s) + l_acc + h_acc;
+
+        Ok(Proof {
+            a: g_a.into_affine(),
+            b: g2_b.into_affine(),
+            c: g_c.into_affine(),
+        })
+    }
+
+    /// Ensures that a witness has a value for each signal.
+    fn check_witness_len(&self, witness: &[E::ScalarField]) -> Result<()> {
+        if witness.len() != self.header.n_vars {
+            return Err(eyre!(
+                "expected {} signals in the witness, got {}",
+                self.header.n_vars,
+                witness.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Computes the MSM of the first points of a section with the given scalars, decoding the points in chunks
+/// that fit in the memory budget of the configuration.
+fn msm_section<F, P>(
+    fq: &Montgomery<F>,
+    file: &BinFile,
+    section_type: u32,
+    scalars: &[P::ScalarField],
+    config: &ProverConfig,
+) -> Result<Projective<P>>
+where
+    F: MontgomeryField,
+    P: SWCurveConfig,
+    P::BaseField: Field<BasePrimeField = F>,
+{
+    let point_size = fq.point_size(P::BaseField::extension_degree() as usize);
+    let chunk_size = config
+        .memory_budget
+        .map_or(CHUNK_SIZE, |budget| (budget / point_size).max(1));
+    let mut section = file.section(section_type)?;
+
+    let mut acc = Projective::<P>::zero();
+    for chunk in scalars.chunks(chunk_size) {
+        let bases = section
+            .read_bytes(chunk.len() * point_size)?
+            .par_chunks(point_size)
+            .map(|bytes| fq.read_point::<P>(&mut SectionReader::new(bytes)))
+            .collect::<Result<Vec<Affine<P>>>>()?;
+        acc += Projective::<P>::msm_unchecked(&bases, chunk);
+    }
+
+    Ok(acc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arkworks::{load_witness, read_r1cs, verify_snarkjs};
+    use crate::snarkjs::{
+        read_snarkjs_json, SnarkjsGroth16Proof, SnarkjsGroth16VerificationKey, SnarkjsPublicInputs,
+    };
+    use ark_bn254::{Bn254, Fr};
+    use ark_std::rand::thread_rng;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_mmap_prover() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let pkey = MmapProvingKey::<Bn254>::open(dir.join("groth16_pkey").with_extension("zkey"))?;
+        let vkey: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(dir.join("groth16_vkey").with_extension("json"))?;
+        assert_eq!(*pkey.verifying_key(), VerifyingKey::try_from(&vkey)?);
+
+        let mut r1cs = read_r1cs::<Fr>(&std::fs::read(dir.join(CIRCUIT).with_extension("r1cs"))?)?;
+        r1cs.wire_mapping = None;
+        let wtns = load_witness::<Fr>(dir.join("default").join("witness").with_extension("wtns"))?;
+        let public_signals = SnarkjsPublicInputs::from_arkworks(&wtns[1..r1cs.num_inputs]);
+        assert_eq!(wtns.len(), pkey.n_vars());
+
+        let circuit = CircomCircuit {
+            r1cs,
+            witness: Some(wtns),
+        };
+        let proof = pkey.prove(circuit, &mut thread_rng())?;
+        assert!(verify_snarkjs(
+            &vkey,
+            &SnarkjsGroth16Proof::from(&proof),
+            &public_signals
+        )?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mmap_prover_without_r1cs() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let pkey = MmapProvingKey::<Bn254>::open(dir.join("groth16_pkey").with_extension("zkey"))?;
+        let vkey: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(dir.join("groth16_vkey").with_extension("json"))?;
+
+        let wtns = load_witness::<Fr>(dir.join("default").join("witness").with_extension("wtns"))?;
+        let public_signals = SnarkjsPublicInputs::from_arkworks(&wtns[1..2]);
+        let proof = pkey.prove_witness(&wtns, &mut thread_rng())?;
+        assert!(verify_snarkjs(
+            &vkey,
+            &SnarkjsGroth16Proof::from(&proof),
+            &public_signals
+        )?);
+
+        // should prove the same with a memory budget of a few points, i.e. many small chunks
+        let config = ProverConfig {
+            memory_budget: Some(1024),
+            ..Default::default()
+        };
+        let h = pkey.witness_map_from_zkey(&wtns)?;
+        let proof = pkey.prove_with_qap(&wtns, &h, &config, &mut thread_rng())?;
+        assert!(verify_snarkjs(
+            &vkey,
+            &SnarkjsGroth16Proof::from(&proof),
+            &public_signals
+        )?);
+
+        // should not prove a partial witness
+        assert!(pkey
+            .prove_witness(&wtns[..wtns.len() - 1], &mut thread_rng())
+            .is_err());
+
+        Ok(())
+    }
+}
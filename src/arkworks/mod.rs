@@ -1,41 +1,342 @@
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
 use ark_circom::{CircomCircuit, CircomConfig};
 use eyre::{eyre, Context, OptionExt, Result};
-use std::path::Path;
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
+use crate::bench::{Stopwatch, STAGE_MSM, STAGE_PARSE, STAGE_WITNESS_MAP};
+use crate::format::FileFormat;
+use crate::prime::CircomPrime;
+use crate::prover::{ProveRequest, Prover, ProverCapabilities};
 use crate::snarkjs::*;
 
 mod snarkjs;
+pub(crate) use snarkjs::g1_to_projective_strings;
 
 mod core;
 use core::*;
 
+mod info;
+pub use info::R1CSInfo;
+
+mod curve;
+pub use curve::{CircomCurve, MontgomeryField};
+
+mod r1cs;
+pub use r1cs::{read_r1cs, read_r1cs_prime};
+
+mod zkey;
+pub use zkey::{read_proving_key, read_zkey_prime};
+#[cfg(test)]
+pub(crate) use zkey::write_proving_key;
+
+mod encoding;
+pub use encoding::*;
+
+mod mmap;
+pub use mmap::MmapProvingKey;
+
+/// Reads an R1CS file and returns its statistics, such as the number of wires & constraints.
+pub fn r1cs_info(r1cs_path: impl AsRef<Path>) -> Result<R1CSInfo> {
+    let r1cs_path = r1cs_path.as_ref();
+    std::fs::read(r1cs_path)
+        .map_err(eyre::Report::from)
+        .and_then(|bytes| R1CSInfo::from_bytes(&bytes))
+        .map_err(|e| eyre!("could not load R1CS {}: {}", r1cs_path.display(), e))
+}
+
 /// Proves a circuit with an existing witness and prover key.
 ///
-/// The witness path can be either a JSON or binary file.
+/// The witness can be either a JSON or binary file, which is detected from its contents. The curve is detected
+/// from the prime in the R1CS header, i.e. the `--prime` option of Circom, which can be `bn128` or `bls12381`.
 pub fn prove_with_existing_witness(
     r1cs_path: impl AsRef<Path>,
     wtns_path: impl AsRef<Path>,
     pkey_path: impl AsRef<Path>,
 ) -> Result<SnarkjsOutput> {
-    let r1cs_path = r1cs_path.as_ref();
-    let wtns_path = wtns_path.as_ref();
-    let pkey_path = pkey_path.as_ref();
+    let (r1cs_bytes, prime) = load_r1cs_bytes(r1cs_path.as_ref(), None)?;
+    prove_with_r1cs_bytes(
+        &r1cs_bytes,
+        prime,
+        wtns_path.as_ref(),
+        None,
+        pkey_path.as_ref(),
+        &mut Stopwatch::start(),
+    )
+}
+
+/// Proves a circuit with an existing witness and prover key, without its R1CS.
+///
+/// The constraints are read from the coefficients section of the `.zkey` instead, and the curve is detected
+/// from the prime in its header. The witness must be complete, i.e. have a value for every signal of the circuit.
+pub fn prove_with_zkey(
+    wtns_path: impl AsRef<Path>,
+    pkey_path: impl AsRef<Path>,
+) -> Result<SnarkjsOutput> {
+    prove_with_zkey_timed(
+        wtns_path.as_ref(),
+        None,
+        pkey_path.as_ref(),
+        &mut Stopwatch::start(),
+    )
+}
+
+/// Proves like [`prove_with_zkey`], recording the time spent in each stage.
+fn prove_with_zkey_timed(
+    wtns_path: &Path,
+    wtns_format: Option<FileFormat>,
+    pkey_path: &Path,
+    stopwatch: &mut Stopwatch,
+) -> Result<SnarkjsOutput> {
+    let prime = load_zkey_prime(pkey_path)
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
+
+    match prime {
+        CircomPrime::Bn128 => {
+            prove_zkey_over_curve::<Bn254>(wtns_path, wtns_format, pkey_path, stopwatch)
+        }
+        CircomPrime::Bls12381 => {
+            prove_zkey_over_curve::<Bls12_381>(wtns_path, wtns_format, pkey_path, stopwatch)
+        }
+        _ => Err(eyre!(
+            "unsupported prime {} in pkey {}, Arkworks only supports bn128 & bls12381",
+            prime,
+            pkey_path.display()
+        )),
+    }
+}
+
+/// Verifies a Groth16 proof against a SnarkJS verification key.
+///
+/// The curve is taken from the `curve` field of the verification key, which can be `bn128` or `bls12381`;
+/// the proof must be over the same curve.
+pub fn verify_snarkjs(
+    vkey: &SnarkjsGroth16VerificationKey,
+    proof: &SnarkjsGroth16Proof,
+    public_signals: &SnarkjsPublicInputs,
+) -> Result<bool> {
+    match snarkjs::parse_curve(&vkey.curve)? {
+        CircomPrime::Bn128 => verify_over_curve::<Bn254>(vkey, proof, public_signals),
+        CircomPrime::Bls12381 => verify_over_curve::<Bls12_381>(vkey, proof, public_signals),
+        prime => Err(eyre!(
+            "unsupported curve {} in verification key, Arkworks only supports bn128 & bls12381",
+            prime
+        )),
+    }
+}
+
+/// Verifies a Groth16 proof over the given curve, see [`verify_snarkjs`].
+fn verify_over_curve<E: CircomCurve>(
+    vkey: &SnarkjsGroth16VerificationKey,
+    proof: &SnarkjsGroth16Proof,
+    public_signals: &SnarkjsPublicInputs,
+) -> Result<bool> {
+    let vk = ark_groth16::VerifyingKey::<E>::try_from(vkey)?;
+    let proof = ark_groth16::Proof::<E>::try_from(proof)?;
+    let public_signals = public_signals.to_arkworks::<E::ScalarField>()?;
+    if public_signals.len() + 1 != vk.gamma_abc_g1.len() {
+        return Err(eyre!(
+            "expected {} public signals, got {}",
+            vk.gamma_abc_g1.len() - 1,
+            public_signals.len()
+        ));
+    }
+
+    ark_groth16::Groth16::<E>::verify_proof(
+        &ark_groth16::prepare_verifying_key(&vk),
+        &proof,
+        &public_signals,
+    )
+    .wrap_err("could not verify proof")
+}
+
+/// Re-randomizes a Groth16 proof over BN254, so that it can not be linked to the original proof.
+///
+/// The new proof verifies against the same verification key & public signals as the original one,
+/// which is not checked here; re-randomizing an invalid proof gives another invalid proof.
+pub fn rerandomize_snarkjs(
+    vkey: &SnarkjsGroth16VerificationKey,
+    proof: &SnarkjsGroth16Proof,
+) -> Result<SnarkjsGroth16Proof> {
+    let vk = ark_groth16::VerifyingKey::<Bn254>::try_from(vkey)?;
+    let proof = ark_groth16::Proof::<Bn254>::try_from(proof)?;
+    let proof = ark_groth16::Groth16::<Bn254>::rerandomize_proof(
+        &vk,
+        &proof,
+        &mut ark_std::rand::thread_rng(),
+    );
+
+    Ok(SnarkjsGroth16Proof::from(&proof))
+}
+
+/// Reads an R1CS file along with its prime, making sure that it is supported by Arkworks.
+///
+/// The R1CS must be binary, which is detected from its contents unless a format is given.
+#[tracing::instrument(
+    name = "load_r1cs",
+    skip_all,
+    fields(backend = "arkworks", path = %r1cs_path.display()),
+)]
+fn load_r1cs_bytes(
+    r1cs_path: &Path,
+    r1cs_format: Option<FileFormat>,
+) -> Result<(Vec<u8>, CircomPrime)> {
+    let r1cs_bytes = std::fs::read(r1cs_path)
+        .map_err(|e| eyre!("could not load R1CS {}: {}", r1cs_path.display(), e))?;
+    match r1cs_format.or_else(|| FileFormat::detect(&r1cs_bytes)) {
+        Some(FileFormat::R1cs) => {}
+        Some(format) => {
+            return Err(eyre!(
+                "R1CS {} is in {} format, Arkworks requires a binary R1CS",
+                r1cs_path.display(),
+                format
+            ))
+        }
+        None => {
+            return Err(eyre!(
+                "could not detect the format of R1CS {}",
+                r1cs_path.display()
+            ))
+        }
+    }
+    let prime = read_r1cs_prime(&r1cs_bytes)
+        .map_err(|e| eyre!("could not load R1CS {}: {}", r1cs_path.display(), e))?;
+
+    match prime {
+        CircomPrime::Bn128 | CircomPrime::Bls12381 => Ok((r1cs_bytes, prime)),
+        _ => Err(eyre!(
+            "unsupported prime {} in R1CS {}, Arkworks only supports bn128 & bls12381",
+            prime,
+            r1cs_path.display()
+        )),
+    }
+}
+
+/// Reads the prime of a prover key from its header, without reading the rest of the file.
+fn load_zkey_prime(pkey_path: &Path) -> Result<CircomPrime> {
+    let file = std::fs::File::open(pkey_path)?;
+    // SAFETY: the map is read-only, and only lives while reading the header
+    let mmap = unsafe { memmap2::Mmap::map(&file)? };
+    read_zkey_prime(&mmap)
+}
+
+/// Proves a circuit with an existing witness and prover key, where the R1CS is given by the
+/// contents of its file & its prime, as returned by [`load_r1cs_bytes`].
+fn prove_with_r1cs_bytes(
+    r1cs_bytes: &[u8],
+    prime: CircomPrime,
+    wtns_path: &Path,
+    wtns_format: Option<FileFormat>,
+    pkey_path: &Path,
+    stopwatch: &mut Stopwatch,
+) -> Result<SnarkjsOutput> {
+    match prime {
+        CircomPrime::Bn128 => {
+            prove_over_curve::<Bn254>(r1cs_bytes, wtns_path, wtns_format, pkey_path, stopwatch)
+        }
+        CircomPrime::Bls12381 => {
+            prove_over_curve::<Bls12_381>(r1cs_bytes, wtns_path, wtns_format, pkey_path, stopwatch)
+        }
+        _ => Err(eyre!(
+            "unsupported prime {}, Arkworks only supports bn128 & bls12381",
+            prime
+        )),
+    }
+}
+
+/// Arkworks backend of the [`ProverRegistry`](crate::prover::ProverRegistry), requires the prover key.
+///
+/// The R1CS is optional, without it the constraints are read from the prover key, see [`prove_with_zkey`].
+#[derive(Debug, Default)]
+pub struct ArkworksProver {
+    /// Contents of the R1CS file along with its prime if given, and the prover key path.
+    loaded: Option<(Option<(Vec<u8>, CircomPrime)>, PathBuf)>,
+}
+
+impl Prover for ArkworksProver {
+    fn capabilities(&self) -> ProverCapabilities {
+        ProverCapabilities {
+            curves: vec![CircomPrime::Bn128, CircomPrime::Bls12381],
+            requires_r1cs: false,
+            requires_pkey: true,
+            devices: vec![],
+        }
+    }
+
+    fn load(&mut self, request: &ProveRequest) -> Result<()> {
+        let pkey_path = request.require_pkey("Arkworks")?;
+        if !pkey_path.is_file() {
+            return Err(eyre!("pkey {} does not exist", pkey_path.display()));
+        }
+        let r1cs = request
+            .r1cs_path
+            .as_ref()
+            .map(|r1cs_path| load_r1cs_bytes(r1cs_path, request.r1cs_format))
+            .transpose()?;
+
+        self.loaded = Some((r1cs, pkey_path.clone()));
+        Ok(())
+    }
+
+    fn prove(&mut self, request: &ProveRequest) -> Result<SnarkjsOutput> {
+        self.prove_timed(request, &mut Stopwatch::start())
+    }
 
-    // if wtns path ends with JSON, use `load_witness_json`, otherwise, use `load_witness`
-    let wtns = if wtns_path.to_string_lossy().ends_with(".json") {
-        load_witness_json(wtns_path)
-    } else {
-        load_witness(wtns_path)
+    fn prove_timed(
+        &mut self,
+        request: &ProveRequest,
+        stopwatch: &mut Stopwatch,
+    ) -> Result<SnarkjsOutput> {
+        let (r1cs, pkey_path) = self
+            .loaded
+            .as_ref()
+            .ok_or_eyre("Arkworks prover is not loaded")?;
+        match r1cs {
+            Some((r1cs_bytes, prime)) => prove_with_r1cs_bytes(
+                r1cs_bytes,
+                *prime,
+                &request.wtns_path,
+                request.wtns_format,
+                pkey_path,
+                stopwatch,
+            ),
+            None => prove_with_zkey_timed(
+                &request.wtns_path,
+                request.wtns_format,
+                pkey_path,
+                stopwatch,
+            ),
+        }
     }
-    .map_err(|e| eyre!("could not load witness {}: {}", wtns_path.display(), e))?;
 
-    let proving_key = load_proving_key(pkey_path)
-        .map_err(|e| eyre!("could not load pkey {}: {}", wtns_path.display(), e))?;
+    fn verify(&self, vkey: &SnarkjsGroth16VerificationKey, output: &SnarkjsOutput) -> Result<bool> {
+        verify_snarkjs(vkey, &output.proof, &output.public_signals)
+    }
+}
+
+/// Proves a circuit with an existing witness and prover key over the given curve,
+/// where the R1CS is given by the contents of its file.
+fn prove_over_curve<E: CircomCurve>(
+    r1cs_bytes: &[u8],
+    wtns_path: &Path,
+    wtns_format: Option<FileFormat>,
+    pkey_path: &Path,
+    stopwatch: &mut Stopwatch,
+) -> Result<SnarkjsOutput>
+where
+    <E::ScalarField as FromStr>::Err: Debug,
+{
+    let wtns = load_witness_file::<E::ScalarField>(wtns_path, wtns_format)?;
+    let proving_key = MmapProvingKey::<E>::open(pkey_path)
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
 
     // load R1CS and disable the wire mapping, otherwise you may get out-of-index errors; this is how Arkworks does it
     // for witnesses generated via WASM runtime, see: https://github.com/arkworks-rs/circom-compat/blob/master/src/circom/builder.rs#L82
-    let mut r1cs = load_r1cs(r1cs_path)
-        .map_err(|e| eyre!("could not load R1CS {}: {}", wtns_path.display(), e))?;
+    let mut r1cs = read_r1cs(r1cs_bytes).map_err(|e| eyre!("could not load R1CS: {}", e))?;
     r1cs.wire_mapping = None;
 
     // construct the circuit with explicit witness
@@ -47,9 +348,25 @@ pub fn prove_with_existing_witness(
     let public_inputs = circom
         .get_public_inputs()
         .ok_or_eyre("could not get public inputs, is witness computed?")?;
-    let proof = prove_circuit(circom, &proving_key)?;
+    stopwatch.lap(STAGE_PARSE);
+
+    let config = crate::config::config();
+    let proof = crate::config::install(&config, || {
+        // entered within the closure, as it may run on a thread of another pool
+        let _span = tracing::info_span!("prove_circuit", backend = "arkworks").entered();
+        let (assignment, h) = proving_key.witness_map(circom)?;
+        stopwatch.lap(STAGE_WITNESS_MAP);
+        let proof = proving_key.prove_with_qap(
+            &assignment,
+            &h,
+            &config,
+            &mut ark_std::rand::thread_rng(),
+        )?;
+        stopwatch.lap(STAGE_MSM);
+        Ok(proof)
+    })?;
     debug_assert!(
-        verify(&proof, &public_inputs, &proving_key).is_ok_and(|b| b),
+        verify(&proof, &public_inputs, proving_key.verifying_key()).is_ok_and(|b| b),
         "proof is not accepted"
     );
 
@@ -59,10 +376,77 @@ pub fn prove_with_existing_witness(
     Ok(SnarkjsOutput {
         proof: snarkjs_proof,
         public_signals: snarkjs_public_inputs,
+        calldata: None,
+    })
+}
+
+/// Proves a witness with a prover key over the given curve, see [`prove_with_zkey`].
+fn prove_zkey_over_curve<E: CircomCurve>(
+    wtns_path: &Path,
+    wtns_format: Option<FileFormat>,
+    pkey_path: &Path,
+    stopwatch: &mut Stopwatch,
+) -> Result<SnarkjsOutput>
+where
+    <E::ScalarField as FromStr>::Err: Debug,
+{
+    let wtns = load_witness_file::<E::ScalarField>(wtns_path, wtns_format)?;
+    let proving_key = MmapProvingKey::<E>::open(pkey_path)
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
+
+    let n_public = proving_key.verifying_key().gamma_abc_g1.len() - 1;
+    let public_inputs = wtns
+        .get(1..=n_public)
+        .ok_or_eyre("witness does not have all public signals")?
+        .to_vec();
+    stopwatch.lap(STAGE_PARSE);
+
+    let config = crate::config::config();
+    let proof = crate::config::install(&config, || {
+        let _span = tracing::info_span!("prove_circuit", backend = "arkworks").entered();
+        let h = proving_key.witness_map_from_zkey(&wtns)?;
+        stopwatch.lap(STAGE_WITNESS_MAP);
+        let proof =
+            proving_key.prove_with_qap(&wtns, &h, &config, &mut ark_std::rand::thread_rng())?;
+        stopwatch.lap(STAGE_MSM);
+        Ok(proof)
+    })?;
+    debug_assert!(
+        verify(&proof, &public_inputs, proving_key.verifying_key()).is_ok_and(|b| b),
+        "proof is not accepted"
+    );
+
+    Ok(SnarkjsOutput {
+        proof: SnarkjsGroth16Proof::from(&proof),
+        public_signals: SnarkjsPublicInputs::from_arkworks(public_inputs),
+        calldata: None,
     })
 }
 
+/// Loads a witness over the given field from a binary or JSON file, as detected from its contents
+/// unless a format is given.
+pub(crate) fn load_witness_file<F: ark_ff::PrimeField>(
+    wtns_path: &Path,
+    wtns_format: Option<FileFormat>,
+) -> Result<Vec<F>> {
+    match FileFormat::resolve(wtns_path, wtns_format)? {
+        FileFormat::Wtns => load_witness::<F>(wtns_path),
+        FileFormat::Json => load_witness_json::<F>(wtns_path),
+        format => {
+            return Err(eyre!(
+                "witness {} is in {} format, expected a binary or JSON witness",
+                wtns_path.display(),
+                format
+            ))
+        }
+    }
+    .map_err(|e| eyre!("could not load witness {}: {}", wtns_path.display(), e))
+}
+
 /// Proves a circuit with an a runtime-computed witness (via WASM) and prover key.
+///
+/// This is only supported over BN254. The witness calculator of Arkworks needs a `tokio` context, so the
+/// shared runtime is entered for it, unless this is called from within a runtime already.
 pub fn prove_with_computed_witness(
     r1cs_path: impl AsRef<Path>,
     wasm_path: impl AsRef<Path>,
@@ -73,7 +457,7 @@ pub fn prove_with_computed_witness(
     let wasm_path = wasm_path.as_ref();
     let pkey_path = pkey_path.as_ref();
 
-    // if wtns path ends with JSON, use `load_witness_json`, otherwise, use `load_witness`
+    let runtime_guard = crate::config::enter_runtime();
     let config = CircomConfig::new(wasm_path, r1cs_path).map_err(|e| {
         eyre!(
             "could not load config from WASM {} and R1CS {}: {}",
@@ -85,15 +469,18 @@ pub fn prove_with_computed_witness(
 
     // construct the circuit with explicit witness
     let circom = compute_witness(config, inputs).wrap_err("could not compute witness")?;
-    let proving_key = load_proving_key(pkey_path)
+    drop(runtime_guard);
+    let proving_key = load_proving_key::<Bn254>(pkey_path)
         .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
 
     let public_inputs = circom
         .get_public_inputs()
         .ok_or_eyre("could not get public inputs, is witness computed?")?;
-    let proof = prove_circuit(circom, &proving_key).wrap_err("could not prove")?;
+    let proof = crate::config::install(&crate::config::config(), || {
+        prove_circuit(circom, &proving_key).wrap_err("could not prove")
+    })?;
     debug_assert!(
-        verify(&proof, &public_inputs, &proving_key).is_ok_and(|b| b),
+        verify(&proof, &public_inputs, &proving_key.vk).is_ok_and(|b| b),
         "proof is not accepted"
     );
 
@@ -103,6 +490,7 @@ pub fn prove_with_computed_witness(
     Ok(SnarkjsOutput {
         proof: snarkjs_proof,
         public_signals: snarkjs_public_inputs,
+        calldata: None,
     })
 }
 
@@ -111,6 +499,8 @@ mod tests {
     use super::*;
 
     const CIRCUIT: &str = "multiplier_30";
+    /// Same circuit, compiled with `circom --prime bls12381`.
+    const CIRCUIT_BLS12_381: &str = "multiplier_30_bls12381";
 
     #[tokio::test]
     async fn test_arkworks_with_computed_witness() -> eyre::Result<()> {
@@ -129,8 +519,118 @@ mod tests {
         check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "arkworks")
     }
 
-    #[tokio::test]
-    async fn test_arkworks_with_existing_witness() -> eyre::Result<()> {
+    #[test]
+    fn test_arkworks_r1cs_info() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let r1cs_path = dir.join(CIRCUIT).with_extension("r1cs");
+
+        let info = r1cs_info(&r1cs_path)?;
+        assert_eq!(info.curve, CircomPrime::Bn128);
+        assert_eq!(info.n_wires, 60);
+        assert_eq!(info.n_public_outputs, 1);
+        assert_eq!(info.n_private_inputs, 30);
+        assert_eq!(info.n_constraints, 29);
+        assert_eq!(
+            info.lc_histogram.values().sum::<usize>(),
+            3 * info.n_constraints as usize
+        );
+
+        // the histogram should match the constraints when read along with their terms
+        let r1cs = read_r1cs::<ark_bn254::Fr>(&std::fs::read(&r1cs_path)?)?;
+        let mut lc_histogram = std::collections::BTreeMap::new();
+        for (a, b, c) in &r1cs.constraints {
+            for lc in [a, b, c] {
+                *lc_histogram.entry(lc.len()).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(info.lc_histogram, lc_histogram);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arkworks_bls12_381() -> eyre::Result<()> {
+        use ark_bls12_381::Fr;
+        use ark_circom::{circom::R1CS, CircomReduction};
+        use ark_ff::One;
+        use ark_groth16::Groth16;
+        use ark_std::rand::thread_rng;
+
+        // a toy circuit for `c = a * b` with public `c`, over the wires `[1, c, a, b]`
+        let r1cs = R1CS::<Fr> {
+            num_inputs: 2,
+            num_aux: 2,
+            num_variables: 4,
+            constraints: vec![(
+                vec![(2, Fr::one())],
+                vec![(3, Fr::one())],
+                vec![(1, Fr::one())],
+            )],
+            wire_mapping: None,
+        };
+        let setup = CircomCircuit {
+            r1cs: r1cs.clone(),
+            witness: None,
+        };
+        let proving_key =
+            Groth16::<Bls12_381, CircomReduction>::generate_random_parameters_with_reduction(
+                setup,
+                &mut thread_rng(),
+            )?;
+
+        let circom = CircomCircuit {
+            r1cs,
+            witness: Some([1u64, 33, 3, 11].map(Fr::from).to_vec()),
+        };
+        let public_inputs = circom
+            .get_public_inputs()
+            .ok_or_eyre("could not get public inputs")?;
+        let proof = prove_circuit(circom, &proving_key)?;
+        assert!(verify(&proof, &public_inputs, &proving_key.vk)?);
+
+        let snarkjs_proof = SnarkjsGroth16Proof::from(&proof);
+        assert_eq!(snarkjs_proof.curve, "bls12381");
+        assert_eq!(snarkjs_proof.pi_b[0][1], proof.b.x.c1.to_string());
+
+        // should verify in SnarkJS format too, with the curve taken from the verification key
+        let snarkjs_vkey = SnarkjsGroth16VerificationKey::from(&proving_key.vk);
+        assert_eq!(snarkjs_vkey.curve, "bls12381");
+        let public_signals = SnarkjsPublicInputs::from_arkworks(public_inputs);
+        assert!(verify_snarkjs(
+            &snarkjs_vkey,
+            &snarkjs_proof,
+            &public_signals
+        )?);
+
+        // should not verify a proof that claims to be over another curve
+        let mut bn_proof = snarkjs_proof.clone();
+        bn_proof.curve = "bn128".to_string();
+        assert!(verify_snarkjs(&snarkjs_vkey, &bn_proof, &public_signals).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arkworks_bls12_381_snarkjs() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
+        let r1cs_path = dir.join(CIRCUIT_BLS12_381).with_extension("r1cs");
+        let wtns_path = dir.join("default").join("witness").with_extension("wtns");
+        let pkey_path = dir.join("groth16_pkey").with_extension("zkey");
+
+        // the curve is detected from the headers of the R1CS & the prover key
+        assert_eq!(load_r1cs_bytes(&r1cs_path, None)?.1, CircomPrime::Bls12381);
+        assert_eq!(load_zkey_prime(&pkey_path)?, CircomPrime::Bls12381);
+
+        let snarkjs_out = prove_with_existing_witness(&r1cs_path, &wtns_path, &pkey_path)?;
+        assert_eq!(snarkjs_out.proof.curve, "bls12381");
+        check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT_BLS12_381, "arkworks")?;
+
+        let snarkjs_out = prove_with_zkey(&wtns_path, &pkey_path)?;
+        check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT_BLS12_381, "arkworks_zkey")
+    }
+
+    #[test]
+    fn test_arkworks_with_existing_witness() -> eyre::Result<()> {
         let dir = Path::new("example/build").join(CIRCUIT);
         let r1cs_path = dir.join(CIRCUIT).with_extension("r1cs");
         let wtns_path = dir
@@ -142,4 +642,40 @@ mod tests {
         let snarkjs_out = prove_with_existing_witness(r1cs_path, wtns_path, pkey_path)?;
         check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "arkworks")
     }
+
+    #[test]
+    fn test_arkworks_rerandomize() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let vkey: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(dir.join("groth16_vkey").with_extension("json"))?;
+        let proof: SnarkjsGroth16Proof =
+            read_snarkjs_json(dir.join("default").join("groth16_proof.json"))?;
+        let public_signals: SnarkjsPublicInputs =
+            read_snarkjs_json(dir.join("default").join("public.json"))?;
+
+        let rerandomized = rerandomize_snarkjs(&vkey, &proof)?;
+        assert_ne!(rerandomized.pi_a, proof.pi_a);
+        assert_ne!(rerandomized.pi_c, proof.pi_c);
+        assert!(verify_snarkjs(&vkey, &rerandomized, &public_signals)?);
+
+        // a proof over another curve can not be re-randomized with a BN254 key
+        let mut bls_proof = proof.clone();
+        bls_proof.curve = "bls12381".to_string();
+        assert!(rerandomize_snarkjs(&vkey, &bls_proof).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arkworks_with_zkey_only() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let wtns_path = dir
+            .join("default") // input name
+            .join("witness")
+            .with_extension("wtns");
+        let pkey_path = dir.join("groth16_pkey").with_extension("zkey");
+
+        let snarkjs_out = prove_with_zkey(wtns_path, pkey_path)?;
+        check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "arkworks_zkey")
+    }
 }
@@ -0,0 +1,221 @@
+//! Reader for Circom `.r1cs` files over any prime field.
+//!
+//! Unlike the reader of `ark-circom`, this one is not restricted to BN254, so that the prime can
+//! be read from the header first and the circuit loaded over the respective field.
+
+use ark_circom::circom::R1CS;
+use ark_ff::PrimeField;
+use eyre::{eyre, Result};
+use num_bigint::BigUint;
+
+use crate::binfile::{BinFile, SectionReader};
+use crate::prime::CircomPrime;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+const WIRE_TO_LABEL_SECTION: u32 = 3;
+
+/// The header of an R1CS file.
+pub(super) struct R1CSHeader {
+    pub prime: CircomPrime,
+    pub field_size: usize,
+    pub n_wires: u32,
+    pub n_pub_out: u32,
+    pub n_pub_in: u32,
+    pub n_prv_in: u32,
+    pub n_labels: u64,
+    pub n_constraints: u32,
+}
+
+impl R1CSHeader {
+    /// Reads the header section of an R1CS file, failing for primes that are not known to Circom.
+    pub fn read(file: &BinFile) -> Result<Self> {
+        let mut header = file.section(HEADER_SECTION)?;
+        let field_size = header.read_u32()? as usize;
+        let prime =
+            CircomPrime::from_modulus(&BigUint::from_bytes_le(header.read_bytes(field_size)?))?;
+        if prime.n8() != field_size {
+            return Err(eyre!(
+                "invalid element size {} for prime {}",
+                field_size,
+                prime
+            ));
+        }
+
+        Ok(Self {
+            prime,
+            field_size,
+            n_wires: header.read_u32()?,
+            n_pub_out: header.read_u32()?,
+            n_pub_in: header.read_u32()?,
+            n_prv_in: header.read_u32()?,
+            n_labels: header.read_u64()?,
+            n_constraints: header.read_u32()?,
+        })
+    }
+}
+
+/// Reads the constraints section of an R1CS file, where each constraint is given as linear combinations
+/// `A`, `B` and `C`, with terms `(wire, coefficient)`.
+///
+/// Each linear combination is read by `read_lc`, given its number of terms and the section positioned
+/// at its first term, so that callers only interested in the sizes can skip the terms.
+pub(super) fn read_constraints<T>(
+    file: &BinFile,
+    header: &R1CSHeader,
+    mut read_lc: impl FnMut(&mut SectionReader, usize) -> Result<T>,
+) -> Result<Vec<(T, T, T)>> {
+    let mut section = file.section(CONSTRAINTS_SECTION)?;
+    let mut read_lc = |section: &mut SectionReader| {
+        let n_terms = section.read_u32()? as usize;
+        read_lc(section, n_terms)
+    };
+
+    (0..header.n_constraints)
+        .map(|_| {
+            Ok((
+                read_lc(&mut section)?,
+                read_lc(&mut section)?,
+                read_lc(&mut section)?,
+            ))
+        })
+        .collect()
+}
+
+/// Reads the prime of the scalar field from the header of an R1CS file.
+pub fn read_r1cs_prime(bytes: &[u8]) -> Result<CircomPrime> {
+    let file = BinFile::parse(bytes, b"r1cs")?;
+    Ok(R1CSHeader::read(&file)?.prime)
+}
+
+/// Reads an R1CS file over the given field, which must match the prime in its header.
+pub fn read_r1cs<F: PrimeField>(bytes: &[u8]) -> Result<R1CS<F>> {
+    let file = BinFile::parse(bytes, b"r1cs")?;
+
+    let header = R1CSHeader::read(&file)?;
+    if header.prime.modulus() != F::MODULUS.into() {
+        return Err(eyre!(
+            "unexpected prime {} in the R1CS header",
+            header.prime
+        ));
+    }
+    let field_size = header.field_size;
+    let n_wires = header.n_wires as usize;
+    let num_inputs = 1 + header.n_pub_in as usize + header.n_pub_out as usize;
+    if num_inputs > n_wires {
+        return Err(eyre!(
+            "invalid number of public signals {} for {} wires",
+            num_inputs,
+            n_wires
+        ));
+    }
+
+    let constraints = read_constraints(&file, &header, |section, n_terms| {
+        (0..n_terms)
+            .map(|_| {
+                let wire = section.read_u32()? as usize;
+                let coeff = F::from_le_bytes_mod_order(section.read_bytes(field_size)?);
+                Ok((wire, coeff))
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    let mut section = file.section(WIRE_TO_LABEL_SECTION)?;
+    let wire_mapping = (0..n_wires)
+        .map(|_| Ok(section.read_u64()? as usize))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(R1CS {
+        num_inputs,
+        num_aux: n_wires - num_inputs,
+        num_variables: n_wires,
+        constraints,
+        wire_mapping: Some(wire_mapping),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use std::{collections::BTreeMap, path::Path};
+
+    #[test]
+    fn test_r1cs_reader() -> eyre::Result<()> {
+        let dir = Path::new("example/build/multiplier_30");
+        let bytes = std::fs::read(dir.join("multiplier_30.r1cs"))?;
+        assert_eq!(read_r1cs_prime(&bytes)?, CircomPrime::Bn128);
+
+        let r1cs = read_r1cs::<Fr>(&bytes)?;
+        assert_eq!(r1cs.num_variables, 60);
+        assert_eq!(r1cs.num_inputs, 2);
+
+        // constraints should match the ones exported by `snarkjs r1cs export json`
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(dir.join("multiplier_30.r1cs.json"))?)?;
+        let expected = json["constraints"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                let [a, b, c] = [0, 1, 2].map(|i| {
+                    serde_json::from_value::<BTreeMap<usize, String>>(c[i].clone()).unwrap()
+                });
+                (a, b, c)
+            })
+            .collect::<Vec<_>>();
+        let lc_to_map = |lc: &Vec<(usize, Fr)>| {
+            lc.iter()
+                .map(|(w, c)| (*w, c.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let constraints = r1cs
+            .constraints
+            .iter()
+            .map(|(a, b, c)| (lc_to_map(a), lc_to_map(b), lc_to_map(c)))
+            .collect::<Vec<_>>();
+        assert_eq!(constraints, expected);
+
+        // should not read the circuit over another field
+        assert!(read_r1cs::<ark_bls12_381::Fr>(&bytes).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_r1cs_reader_other_primes() -> eyre::Result<()> {
+        // an R1CS with no constraints & a header over the given prime
+        let r1cs_with_prime = |prime: &BigUint, n8: usize| {
+            let mut header = (n8 as u32).to_le_bytes().to_vec();
+            let mut prime_bytes = prime.to_bytes_le();
+            prime_bytes.resize(n8, 0);
+            header.extend(prime_bytes);
+            for value in [2u32, 1, 0, 0] {
+                header.extend(value.to_le_bytes());
+            }
+            header.extend(2u64.to_le_bytes());
+            header.extend(0u32.to_le_bytes());
+
+            let mut bytes = b"r1cs".to_vec();
+            bytes.extend(1u32.to_le_bytes());
+            bytes.extend(1u32.to_le_bytes());
+            bytes.extend(HEADER_SECTION.to_le_bytes());
+            bytes.extend((header.len() as u64).to_le_bytes());
+            bytes.extend(header);
+            bytes
+        };
+
+        let goldilocks = r1cs_with_prime(&CircomPrime::Goldilocks.modulus(), 8);
+        assert_eq!(read_r1cs_prime(&goldilocks)?, CircomPrime::Goldilocks);
+        assert!(read_r1cs::<Fr>(&goldilocks).is_err());
+
+        let pallas = r1cs_with_prime(&CircomPrime::Pallas.modulus(), 32);
+        assert_eq!(read_r1cs_prime(&pallas)?, CircomPrime::Pallas);
+
+        // should reject unknown primes & mismatching element sizes
+        assert!(read_r1cs_prime(&r1cs_with_prime(&BigUint::from(97u32), 8)).is_err());
+        assert!(read_r1cs_prime(&r1cs_with_prime(&CircomPrime::Bn128.modulus(), 40)).is_err());
+
+        Ok(())
+    }
+}
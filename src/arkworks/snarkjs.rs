@@ -1,29 +1,135 @@
-use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsPublicInputs};
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{Field, One, PrimeField, Zero};
+use eyre::{eyre, OptionExt, Result};
+use num_bigint::BigUint;
+use std::str::FromStr;
 
-type ArkworksProof = ark_groth16::Proof<ark_bn254::Bn254>;
+use super::CircomCurve;
+use crate::prime::CircomPrime;
+use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsGroth16VerificationKey, SnarkjsPublicInputs};
+
+/// Parses the name of a curve as SnarkJS writes it, e.g. in the `curve` field of proofs & verification keys.
+///
+/// SnarkJS calls BN254 `bn128`, and accepts `bn254 | altbn128` as the same.
+pub(crate) fn parse_curve(name: &str) -> Result<CircomPrime> {
+    match name {
+        "bn254" | "altbn128" => Ok(CircomPrime::Bn128),
+        _ => CircomPrime::from_str(name).map_err(|_| eyre!("unknown curve {}", name)),
+    }
+}
+
+/// Makes sure that a curve given by SnarkJS is the curve `E`.
+fn expect_curve<E: CircomCurve>(name: &str, what: &str) -> Result<()> {
+    if parse_curve(name)?.name() != E::NAME {
+        return Err(eyre!("expected a {} over {}, got {}", what, E::NAME, name));
+    }
+
+    Ok(())
+}
+
+impl<E: CircomCurve> From<&ark_groth16::Proof<E>> for SnarkjsGroth16Proof {
+    fn from(proof: &ark_groth16::Proof<E>) -> Self {
+        let fq2_to_strings = |c: &E::Fq2| {
+            let mut elems = c.to_base_prime_field_elements().map(|e| e.to_string());
+            [(); 2].map(|_| elems.next().unwrap_or_default())
+        };
 
-impl From<&ArkworksProof> for SnarkjsGroth16Proof {
-    fn from(proof: &ArkworksProof) -> Self {
         Self {
             pi_a: [proof.a.x.to_string(), proof.a.y.to_string()],
-            pi_b: [
-                [proof.b.x.c0.to_string(), proof.b.x.c1.to_string()],
-                [proof.b.y.c0.to_string(), proof.b.y.c1.to_string()],
-            ],
+            pi_b: [fq2_to_strings(&proof.b.x), fq2_to_strings(&proof.b.y)],
             pi_c: [proof.c.x.to_string(), proof.c.y.to_string()],
             protocol: "groth16".to_string(),
-            // SnarkJS accepts `bn254 | bn128 | altbn128` as all the same
-            curve: "bn128".to_string(),
+            // SnarkJS calls BN254 `bn128`, and accepts `bn254 | altbn128` as the same
+            curve: E::NAME.to_string(),
         }
     }
 }
 
-impl From<ArkworksProof> for SnarkjsGroth16Proof {
-    fn from(proof: ArkworksProof) -> Self {
+impl<E: CircomCurve> From<ark_groth16::Proof<E>> for SnarkjsGroth16Proof {
+    fn from(proof: ark_groth16::Proof<E>) -> Self {
         Self::from(&proof)
     }
 }
 
+impl<E: CircomCurve> TryFrom<&SnarkjsGroth16Proof> for ark_groth16::Proof<E> {
+    type Error = eyre::Report;
+
+    fn try_from(proof: &SnarkjsGroth16Proof) -> Result<Self> {
+        expect_curve::<E>(&proof.curve, "proof")?;
+
+        Ok(Self {
+            a: parse_g1::<E>(&proof.pi_a)?,
+            b: parse_g2::<E>(&proof.pi_b)?,
+            c: parse_g1::<E>(&proof.pi_c)?,
+        })
+    }
+}
+
+impl<E: CircomCurve> From<&ark_groth16::VerifyingKey<E>> for SnarkjsGroth16VerificationKey {
+    fn from(vk: &ark_groth16::VerifyingKey<E>) -> Self {
+        // Fq12 is a quadratic extension of Fq6, which is a cubic extension of Fq2
+        let alphabeta = E::pairing(vk.alpha_g1, vk.beta_g2).0;
+        let mut elems = alphabeta
+            .to_base_prime_field_elements()
+            .map(|e| e.to_string());
+        let vk_alphabeta_12 =
+            [(); 2].map(|_| [(); 3].map(|_| [(); 2].map(|_| elems.next().unwrap_or_default())));
+
+        Self {
+            protocol: "groth16".to_string(),
+            curve: E::NAME.to_string(),
+            n_public: vk.gamma_abc_g1.len() - 1,
+            vk_alpha_1: g1_to_projective_strings(&vk.alpha_g1),
+            vk_beta_2: g2_to_projective_strings(&vk.beta_g2),
+            vk_gamma_2: g2_to_projective_strings(&vk.gamma_g2),
+            vk_delta_2: g2_to_projective_strings(&vk.delta_g2),
+            vk_alphabeta_12,
+            ic: vk
+                .gamma_abc_g1
+                .iter()
+                .map(g1_to_projective_strings)
+                .collect(),
+        }
+    }
+}
+
+impl<E: CircomCurve> From<ark_groth16::VerifyingKey<E>> for SnarkjsGroth16VerificationKey {
+    fn from(vk: ark_groth16::VerifyingKey<E>) -> Self {
+        Self::from(&vk)
+    }
+}
+
+impl<E: CircomCurve> TryFrom<&SnarkjsGroth16VerificationKey> for ark_groth16::VerifyingKey<E> {
+    type Error = eyre::Report;
+
+    fn try_from(vk: &SnarkjsGroth16VerificationKey) -> Result<Self> {
+        expect_curve::<E>(&vk.curve, "verification key")?;
+        if vk.ic.len() != vk.n_public + 1 {
+            return Err(eyre!(
+                "expected {} IC points for {} public signals, got {}",
+                vk.n_public + 1,
+                vk.n_public,
+                vk.ic.len()
+            ));
+        }
+
+        Ok(Self {
+            alpha_g1: parse_g1::<E>(&vk.vk_alpha_1)?,
+            beta_g2: parse_g2::<E>(&vk.vk_beta_2)?,
+            gamma_g2: parse_g2::<E>(&vk.vk_gamma_2)?,
+            delta_g2: parse_g2::<E>(&vk.vk_delta_2)?,
+            gamma_abc_g1: vk
+                .ic
+                .iter()
+                .map(|p| parse_g1::<E>(p))
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
 impl SnarkjsPublicInputs {
     #[inline]
     pub fn from_arkworks<F: ark_ff::PrimeField>(public_signals: impl AsRef<[F]>) -> Self {
@@ -35,4 +141,110 @@ impl SnarkjsPublicInputs {
                 .collect(),
         )
     }
+    /// Parses the public signals into field elements, making sure that they are smaller than the modulus.
+    #[inline]
+    pub fn to_arkworks<F: PrimeField>(&self) -> Result<Vec<F>> {
+        self.0.iter().map(|s| parse_field(s)).collect()
+    }
+}
+
+/// Parses a decimal string into a field element, making sure that it is smaller than the modulus.
+pub(crate) fn parse_field<F: PrimeField>(s: &str) -> Result<F> {
+    let value = s
+        .parse::<BigUint>()
+        .map_err(|e| eyre!("could not parse {} as a decimal: {}", s, e))?;
+    if value >= F::MODULUS.into() {
+        return Err(eyre!("{} is not smaller than the field modulus", s));
+    }
+
+    Ok(F::from(value))
+}
+
+/// Parses a G1 point given in affine (`[x, y]`) or projective (`[x, y, z]`) coordinates.
+///
+/// Projective coordinates are expected to be normalized, i.e. `z` must be `1`, or `0` for the identity.
+pub(crate) fn parse_g1<E: CircomCurve>(coords: &[String]) -> Result<E::G1Affine> {
+    let (xy, z) = match coords {
+        [x, y] => ([x, y], None),
+        [x, y, z] => ([x, y], Some(z.as_str())),
+        _ => {
+            return Err(eyre!(
+                "expected 2 or 3 coordinates for G1, got {}",
+                coords.len()
+            ))
+        }
+    };
+
+    match z {
+        Some("0") => return Ok(E::G1Affine::zero()),
+        Some("1") | None => {}
+        Some(z) => return Err(eyre!("expected z coordinate to be 1, got {}", z)),
+    }
+
+    let point = Affine::<E::G1Config>::new_unchecked(parse_field(xy[0])?, parse_field(xy[1])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(eyre!("point is not in G1"));
+    }
+
+    Ok(point)
+}
+
+/// Parses a G2 point given in affine (`[x, y]`) or projective (`[x, y, z]`) coordinates,
+/// where each coordinate is an `[c0, c1]` pair.
+///
+/// Projective coordinates are expected to be normalized, i.e. `z` must be `[1, 0]`, or `[0, 0]` for the identity.
+pub(crate) fn parse_g2<E: CircomCurve>(coords: &[[String; 2]]) -> Result<E::G2Affine> {
+    let parse_fq2 = |[c0, c1]: &[String; 2]| -> Result<E::Fq2> {
+        E::Fq2::from_base_prime_field_elems([parse_field(c0)?, parse_field(c1)?])
+            .ok_or_eyre("expected 2 coefficients for Fq2")
+    };
+
+    let (xy, z) = match coords {
+        [x, y] => ([x, y], None),
+        [x, y, z] => ([x, y], Some(parse_fq2(z)?)),
+        _ => {
+            return Err(eyre!(
+                "expected 2 or 3 coordinates for G2, got {}",
+                coords.len()
+            ))
+        }
+    };
+
+    match z {
+        Some(z) if z.is_zero() => return Ok(E::G2Affine::zero()),
+        Some(z) if !z.is_one() => return Err(eyre!("expected z coordinate to be [1, 0]")),
+        _ => {}
+    }
+
+    let point = Affine::<E::G2Config>::new_unchecked(parse_fq2(xy[0])?, parse_fq2(xy[1])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(eyre!("point is not in G2"));
+    }
+
+    Ok(point)
+}
+
+/// Returns the projective coordinates of a G1 point, as SnarkJS exports them.
+pub(crate) fn g1_to_projective_strings<P: SWCurveConfig>(p: &Affine<P>) -> [String; 3] {
+    match p.xy() {
+        Some((x, y)) => [x.to_string(), y.to_string(), "1".to_string()],
+        None => ["0", "1", "0"].map(String::from),
+    }
+}
+
+/// Returns the projective coordinates of a G2 point, as SnarkJS exports them.
+fn g2_to_projective_strings<P: SWCurveConfig>(p: &Affine<P>) -> [[String; 2]; 3] {
+    let fq2_to_strings = |c: &P::BaseField| {
+        let mut elems = c.to_base_prime_field_elements().map(|e| e.to_string());
+        [(); 2].map(|_| elems.next().unwrap_or_default())
+    };
+
+    match p.xy() {
+        Some((x, y)) => [
+            fq2_to_strings(&x),
+            fq2_to_strings(&y),
+            ["1".to_string(), "0".to_string()],
+        ],
+        None => [["0", "0"], ["1", "0"], ["0", "0"]].map(|c| c.map(String::from)),
+    }
 }
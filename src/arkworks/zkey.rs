@@ -0,0 +1,449 @@
+//! Reader for SnarkJS Groth16 `.zkey` files over any [`CircomCurve`].
+//!
+//! The sections are as follows, where field elements & curve points are little-endian in Montgomery form:
+//!
+//! 1. protocol id, which is `1` for Groth16
+//! 2. header: field sizes & moduli, `nVars`, `nPublic`, `domainSize`, and the points
+//!    `[α]₁`, `[β]₁`, `[β]₂`, `[γ]₂`, `[δ]₁`, `[δ]₂`
+//! 3. `IC`, i.e. the `nPublic + 1` points of the verifying key for the public signals
+//! 4. coefficients of the `A` & `B` constraint matrices, in double Montgomery form, see [`ZkeyHeader::read_matrices`]
+//! 5. to 7. `A` in G1, `B` in G1 and `B` in G2 queries for each signal
+//! 8. `C` query for each private signal
+//! 9. `H` query for each point of the domain
+
+use ark_ec::{
+    short_weierstrass::{Affine, SWCurveConfig},
+    AffineRepr,
+};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_relations::r1cs::ConstraintMatrices;
+use eyre::{eyre, OptionExt, Result};
+use num_bigint::BigUint;
+
+use super::{CircomCurve, MontgomeryField};
+use crate::binfile::{BinFile, SectionReader};
+use crate::prime::CircomPrime;
+
+const GROTH16_PROTOCOL_ID: u32 = 1;
+
+const HEADER_SECTION: u32 = 1;
+const GROTH16_HEADER_SECTION: u32 = 2;
+const IC_SECTION: u32 = 3;
+const COEFFS_SECTION: u32 = 4;
+pub(super) const A_SECTION: u32 = 5;
+pub(super) const B1_SECTION: u32 = 6;
+pub(super) const B2_SECTION: u32 = 7;
+pub(super) const C_SECTION: u32 = 8;
+pub(super) const H_SECTION: u32 = 9;
+
+/// Parses a Groth16 proving key from the contents of a `.zkey` file, over the given curve.
+pub fn read_proving_key<E: CircomCurve>(bytes: &[u8]) -> Result<ProvingKey<E>> {
+    let file = BinFile::parse(bytes, b"zkey")?;
+    let header = ZkeyHeader::<E>::read(&file)?;
+    let fq = &header.fq;
+
+    Ok(ProvingKey {
+        vk: header.read_verifying_key(&file)?,
+        beta_g1: header.beta_g1,
+        delta_g1: header.delta_g1,
+        a_query: fq.read_points(&file, A_SECTION, header.n_vars)?,
+        b_g1_query: fq.read_points(&file, B1_SECTION, header.n_vars)?,
+        b_g2_query: fq.read_points(&file, B2_SECTION, header.n_vars)?,
+        h_query: fq.read_points(&file, H_SECTION, header.domain_size)?,
+        l_query: fq.read_points(&file, C_SECTION, header.n_private())?,
+    })
+}
+
+/// Reads the prime of a Groth16 `.zkey` file, i.e. the scalar field of its curve, from its header.
+pub fn read_zkey_prime(bytes: &[u8]) -> Result<CircomPrime> {
+    let file = BinFile::parse(bytes, b"zkey")?;
+    let mut header = file.section(GROTH16_HEADER_SECTION)?;
+    let n8q = header.read_u32()? as usize;
+    header.read_bytes(n8q)?;
+    let n8r = header.read_u32()? as usize;
+    CircomPrime::from_modulus(&BigUint::from_bytes_le(header.read_bytes(n8r)?))
+}
+
+/// The Groth16 header of a `.zkey` file, i.e. its sizes and the points that are not part of a query.
+pub(super) struct ZkeyHeader<E: CircomCurve> {
+    /// Reader for the coordinates of the points.
+    pub fq: Montgomery<E::Fq>,
+    /// Reader for the coefficients of the constraints.
+    pub fr: Montgomery<E::ScalarField>,
+    pub n_vars: usize,
+    pub n_public: usize,
+    pub domain_size: usize,
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+    pub gamma_g2: E::G2Affine,
+    pub delta_g1: E::G1Affine,
+    pub delta_g2: E::G2Affine,
+}
+
+impl<E: CircomCurve> ZkeyHeader<E> {
+    /// Reads the header of a Groth16 `.zkey` file, checking its protocol & curve.
+    pub fn read(file: &BinFile) -> Result<Self> {
+        let protocol_id = file.section(HEADER_SECTION)?.read_u32()?;
+        if protocol_id != GROTH16_PROTOCOL_ID {
+            return Err(eyre!(
+                "expected a Groth16 zkey (protocol {}), got protocol {}",
+                GROTH16_PROTOCOL_ID,
+                protocol_id
+            ));
+        }
+
+        let mut header = file.section(GROTH16_HEADER_SECTION)?;
+        let fq = Montgomery::<E::Fq>::read_modulus(&mut header, E::NAME)?;
+        let fr = Montgomery::<E::ScalarField>::read_modulus(&mut header, E::NAME)?;
+        let n_vars = header.read_u32()? as usize;
+        let n_public = header.read_u32()? as usize;
+        let domain_size = header.read_u32()? as usize;
+        if n_public >= n_vars {
+            return Err(eyre!(
+                "invalid number of public signals {} for {} signals",
+                n_public,
+                n_vars
+            ));
+        }
+
+        Ok(Self {
+            alpha_g1: fq.read_point(&mut header)?,
+            beta_g1: fq.read_point(&mut header)?,
+            beta_g2: fq.read_point(&mut header)?,
+            gamma_g2: fq.read_point(&mut header)?,
+            delta_g1: fq.read_point(&mut header)?,
+            delta_g2: fq.read_point(&mut header)?,
+            fq,
+            fr,
+            n_vars,
+            n_public,
+            domain_size,
+        })
+    }
+
+    /// Number of private signals, i.e. the length of the `C` query.
+    #[inline]
+    pub fn n_private(&self) -> usize {
+        self.n_vars - self.n_public - 1
+    }
+
+    /// Reads the verifying key, whose `IC` points are in a section of their own.
+    pub fn read_verifying_key(&self, file: &BinFile) -> Result<VerifyingKey<E>> {
+        Ok(VerifyingKey {
+            alpha_g1: self.alpha_g1,
+            beta_g2: self.beta_g2,
+            gamma_g2: self.gamma_g2,
+            delta_g2: self.delta_g2,
+            gamma_abc_g1: self.fq.read_points(file, IC_SECTION, self.n_public + 1)?,
+        })
+    }
+
+    /// Reads the `A` & `B` constraint matrices, so that a witness can be proven without the R1CS.
+    ///
+    /// SnarkJS appends a constraint `a_i = signal_i` for each public signal to `A`, which are left out
+    /// here as [`CircomReduction`](ark_circom::CircomReduction) adds them on its own. The matrix `C` is empty,
+    /// as its evaluations are the products of those of `A` & `B` for a valid witness.
+    pub fn read_matrices(&self, file: &BinFile) -> Result<ConstraintMatrices<E::ScalarField>> {
+        let mut section = file.section(COEFFS_SECTION)?;
+        let n_coeffs = section.read_u32()? as usize;
+
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        for _ in 0..n_coeffs {
+            let matrix = section.read_u32()? as usize;
+            let constraint = section.read_u32()? as usize;
+            let signal = section.read_u32()? as usize;
+            let value = self.fr.read_coefficient(&mut section)?;
+
+            let rows = match matrix {
+                0 => &mut a,
+                1 => &mut b,
+                _ => return Err(eyre!("invalid matrix {} for a coefficient", matrix)),
+            };
+            if constraint >= self.domain_size || signal >= self.n_vars {
+                return Err(eyre!(
+                    "invalid coefficient of signal {} in constraint {}",
+                    signal,
+                    constraint
+                ));
+            }
+            if rows.len() <= constraint {
+                rows.resize(constraint + 1, Vec::new());
+            }
+            rows[constraint].push((value, signal));
+        }
+
+        let num_constraints = a
+            .len()
+            .checked_sub(self.n_public + 1)
+            .ok_or_eyre("missing the constraints of the public signals")?;
+        if b.len() > num_constraints {
+            return Err(eyre!(
+                "expected {} constraints in B, got {}",
+                num_constraints,
+                b.len()
+            ));
+        }
+        a.truncate(num_constraints);
+        b.resize(num_constraints, Vec::new());
+
+        Ok(ConstraintMatrices {
+            num_instance_variables: self.n_public + 1,
+            num_witness_variables: self.n_private(),
+            num_constraints,
+            a_num_non_zero: a.iter().map(Vec::len).sum(),
+            b_num_non_zero: b.iter().map(Vec::len).sum(),
+            c_num_non_zero: 0,
+            a,
+            b,
+            c: Vec::new(),
+        })
+    }
+}
+
+/// Reader for elements of a prime field in Montgomery form, i.e. multiplied by `R = 2^(8 * n8)`.
+///
+/// When `n8` matches the size of the field in Arkworks, so does `R`, and the elements are read without any arithmetic.
+pub(super) struct Montgomery<F> {
+    n8: usize,
+    r_inv: F,
+}
+
+impl<F: PrimeField> Montgomery<F> {
+    /// Reads the size & modulus of a field, and ensures that they match this field.
+    fn read_modulus(reader: &mut SectionReader, curve: &str) -> Result<Self> {
+        let n8 = reader.read_u32()? as usize;
+        let modulus = BigUint::from_bytes_le(reader.read_bytes(n8)?);
+        let expected: BigUint = F::MODULUS.into();
+        if modulus != expected {
+            return Err(eyre!(
+                "expected a field modulus of {}, got {}",
+                curve,
+                modulus
+            ));
+        }
+
+        let r_inv = F::from(2u64)
+            .pow([8 * n8 as u64])
+            .inverse()
+            .ok_or_eyre("could not invert the Montgomery factor")?;
+
+        Ok(Self { n8, r_inv })
+    }
+
+    /// Reads a coefficient of a constraint, which SnarkJS multiplies by `R` twice.
+    fn read_coefficient(&self, reader: &mut SectionReader) -> Result<F> {
+        let bytes = reader.read_bytes(self.n8)?;
+        Ok(F::from_le_bytes_mod_order(bytes) * self.r_inv * self.r_inv)
+    }
+}
+
+impl<F: MontgomeryField> Montgomery<F> {
+    /// Reads an element, which is moved out of Montgomery form unless it has the same form as in Arkworks.
+    fn read(&self, reader: &mut SectionReader) -> Result<F> {
+        let bytes = reader.read_bytes(self.n8)?;
+        if self.n8 == F::N8 {
+            return F::from_montgomery_le(bytes).ok_or_eyre("field element is not in the field");
+        }
+
+        Ok(F::from_le_bytes_mod_order(bytes) * self.r_inv)
+    }
+
+    /// Number of bytes of an affine point, whose coordinates are in an extension of this field of the given degree.
+    #[inline]
+    pub fn point_size(&self, degree: usize) -> usize {
+        2 * degree * self.n8
+    }
+
+    /// Reads an affine point, whose coordinates are in an extension of this field (e.g. `Fq2` for G2).
+    ///
+    /// The point at infinity is given as `(0, 0)`. Points are only checked to be on the curve,
+    /// as checking the subgroup for all points of a zkey is rather costly.
+    pub fn read_point<P>(&self, reader: &mut SectionReader) -> Result<Affine<P>>
+    where
+        P: SWCurveConfig,
+        P::BaseField: Field<BasePrimeField = F>,
+    {
+        let degree = P::BaseField::extension_degree() as usize;
+        let mut read_coordinate = || {
+            let elems = (0..degree)
+                .map(|_| self.read(reader))
+                .collect::<Result<Vec<_>>>()?;
+            P::BaseField::from_base_prime_field_elems(elems).ok_or_eyre("invalid coordinate")
+        };
+        let x = read_coordinate()?;
+        let y = read_coordinate()?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(Affine::zero());
+        }
+
+        let p = Affine::new_unchecked(x, y);
+        if !p.is_on_curve() {
+            return Err(eyre!("point is not on the curve"));
+        }
+
+        Ok(p)
+    }
+
+    /// Reads `n` points from the section with the given type.
+    fn read_points<P>(&self, file: &BinFile, section_type: u32, n: usize) -> Result<Vec<Affine<P>>>
+    where
+        P: SWCurveConfig,
+        P::BaseField: Field<BasePrimeField = F>,
+    {
+        let mut section = file.section(section_type)?;
+        (0..n).map(|_| self.read_point(&mut section)).collect()
+    }
+}
+
+/// Writes a proving key as a `.zkey` in the layout of SnarkJS, for the fixtures of the tests.
+///
+/// The coefficients `(matrix, constraint, signal, value)` of `A` & `B` are only read when proving without
+/// the R1CS, and must then include the constraints of the public signals, see [`ZkeyHeader::read_matrices`].
+#[cfg(test)]
+pub(crate) fn write_proving_key<E: CircomCurve>(
+    pk: &ProvingKey<E>,
+    coefficients: &[(u32, u32, u32, E::ScalarField)],
+) -> Vec<u8> {
+    use crate::binfile::zkey_writer::{points, write_field, write_moduli, write_point, ZkeyWriter};
+    use ark_ff::BigInteger;
+
+    let n_public = pk.vk.gamma_abc_g1.len() - 1;
+    let mut header = Vec::new();
+    write_moduli::<E::Fq, E::ScalarField>(&mut header);
+    for value in [pk.a_query.len(), n_public, pk.h_query.len()] {
+        header.extend((value as u32).to_le_bytes());
+    }
+    write_point(&mut header, &pk.vk.alpha_g1);
+    write_point(&mut header, &pk.beta_g1);
+    write_point(&mut header, &pk.vk.beta_g2);
+    write_point(&mut header, &pk.vk.gamma_g2);
+    write_point(&mut header, &pk.delta_g1);
+    write_point(&mut header, &pk.vk.delta_g2);
+
+    // coefficients are multiplied by `R` once more than other elements
+    let n8 = E::ScalarField::MODULUS.to_bytes_le().len();
+    let r = E::ScalarField::from(2u64).pow([8 * n8 as u64]);
+    let mut coeffs = (coefficients.len() as u32).to_le_bytes().to_vec();
+    for (matrix, constraint, signal, value) in coefficients {
+        for index in [matrix, constraint, signal] {
+            coeffs.extend(index.to_le_bytes());
+        }
+        write_field(&mut coeffs, &(*value * r));
+    }
+
+    ZkeyWriter::new(GROTH16_PROTOCOL_ID)
+        .section(GROTH16_HEADER_SECTION, header)
+        .section(IC_SECTION, points(&pk.vk.gamma_abc_g1))
+        .section(COEFFS_SECTION, coeffs)
+        .section(A_SECTION, points(&pk.a_query))
+        .section(B1_SECTION, points(&pk.b_g1_query))
+        .section(B2_SECTION, points(&pk.b_g2_query))
+        .section(C_SECTION, points(&pk.l_query))
+        .section(H_SECTION, points(&pk.h_query))
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::{read_snarkjs_json, SnarkjsGroth16VerificationKey};
+    use ark_bls12_381::Bls12_381;
+    use ark_bn254::Bn254;
+    use ark_ff::{BigInteger, UniformRand};
+    use ark_std::rand::thread_rng;
+    use std::path::Path;
+
+    /// Writes a proving key in the layout of SnarkJS, with random points & `nPublic = 1`.
+    fn random_zkey<E: CircomCurve>(n_vars: usize, domain_size: usize) -> (Vec<u8>, ProvingKey<E>) {
+        let mut rng = thread_rng();
+        let pk = ProvingKey::<E> {
+            vk: VerifyingKey {
+                alpha_g1: E::G1Affine::rand(&mut rng),
+                beta_g2: E::G2Affine::rand(&mut rng),
+                gamma_g2: E::G2Affine::rand(&mut rng),
+                delta_g2: E::G2Affine::rand(&mut rng),
+                gamma_abc_g1: (0..2).map(|_| E::G1Affine::rand(&mut rng)).collect(),
+            },
+            beta_g1: E::G1Affine::rand(&mut rng),
+            delta_g1: E::G1Affine::rand(&mut rng),
+            // the identity is included to check its encoding
+            a_query: (0..n_vars)
+                .map(|i| {
+                    if i == 0 {
+                        E::G1Affine::zero()
+                    } else {
+                        E::G1Affine::rand(&mut rng)
+                    }
+                })
+                .collect(),
+            b_g1_query: (0..n_vars).map(|_| E::G1Affine::rand(&mut rng)).collect(),
+            b_g2_query: (0..n_vars).map(|_| E::G2Affine::rand(&mut rng)).collect(),
+            h_query: (0..domain_size)
+                .map(|_| E::G1Affine::rand(&mut rng))
+                .collect(),
+            l_query: (0..n_vars - 2)
+                .map(|_| E::G1Affine::rand(&mut rng))
+                .collect(),
+        };
+
+        let bytes = write_proving_key(&pk, &[]);
+        (bytes, pk)
+    }
+
+    #[test]
+    fn test_zkey_reader_roundtrip() -> eyre::Result<()> {
+        let (bytes, pk) = random_zkey::<Bn254>(5, 4);
+        assert_eq!(read_proving_key::<Bn254>(&bytes)?, pk);
+
+        let (bytes, pk) = random_zkey::<Bls12_381>(5, 4);
+        assert_eq!(read_proving_key::<Bls12_381>(&bytes)?, pk);
+
+        // should not read a zkey over another curve
+        assert!(read_proving_key::<Bn254>(&bytes).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_montgomery_fast_path() -> eyre::Result<()> {
+        use ark_bn254::Fq;
+
+        let mut rng = thread_rng();
+        let fq = Montgomery::<Fq> {
+            n8: 32,
+            r_inv: Fq::from(2u64).pow([256]).inverse().unwrap(),
+        };
+        for _ in 0..10 {
+            let f = Fq::rand(&mut rng);
+            let mont = (f * Fq::from(2u64).pow([256])).into_bigint().to_bytes_le();
+            assert_eq!(fq.read(&mut SectionReader::new(&mont))?, f);
+
+            // should agree with the conversion out of Montgomery form
+            assert_eq!(
+                Fq::from_le_bytes_mod_order(&mont) * fq.r_inv,
+                Fq::from_montgomery_le(&mont).unwrap()
+            );
+        }
+
+        // should not read elements that are not reduced
+        let modulus = Fq::MODULUS.to_bytes_le();
+        assert!(fq.read(&mut SectionReader::new(&modulus)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_zkey_reader_snarkjs() -> eyre::Result<()> {
+        let dir = Path::new("example/build/multiplier_30");
+        let bytes = std::fs::read(dir.join("groth16_pkey.zkey"))?;
+        assert_eq!(read_zkey_prime(&bytes)?, CircomPrime::Bn128);
+        let pk = read_proving_key::<Bn254>(&bytes)?;
+
+        let vkey: SnarkjsGroth16VerificationKey = read_snarkjs_json(dir.join("groth16_vkey.json"))?;
+        assert_eq!(pk.vk, VerifyingKey::try_from(&vkey)?);
+
+        Ok(())
+    }
+}
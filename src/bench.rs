@@ -0,0 +1,203 @@
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::prover::{ProveRequest, ProverRegistry};
+
+/// Stage for loading the circuit, keys & witness from their files.
+pub const STAGE_PARSE: &str = "parse";
+/// Stage for computing the coefficients of `h` from the witness.
+pub const STAGE_WITNESS_MAP: &str = "witness_map";
+/// Stage for the multi-scalar multiplications of the proof.
+pub const STAGE_MSM: &str = "msm";
+/// Stage for the whole proof, for backends that don't tell the witness map & MSMs apart.
+pub const STAGE_PROVE: &str = "prove";
+/// Stage for serializing the proof to SnarkJS JSON.
+pub const STAGE_SERIALIZE: &str = "serialize";
+
+/// Records the time spent in each stage of a proof.
+///
+/// Each lap is attributed to a stage, and laps of the same stage add up.
+#[derive(Debug, Clone)]
+pub struct Stopwatch {
+    laps: Vec<(&'static str, Duration)>,
+    last: Instant,
+}
+
+impl Stopwatch {
+    /// Starts the first lap.
+    pub fn start() -> Self {
+        Self {
+            laps: Vec::new(),
+            last: Instant::now(),
+        }
+    }
+
+    /// Ends the current lap & attributes it to the given stage, and starts the next lap.
+    pub fn lap(&mut self, stage: &'static str) {
+        let now = Instant::now();
+        let elapsed = now - self.last;
+        self.last = now;
+
+        match self.laps.iter_mut().find(|(name, _)| *name == stage) {
+            Some((_, total)) => *total += elapsed,
+            None => self.laps.push((stage, elapsed)),
+        }
+    }
+
+    /// Time spent in each stage, in the order that the stages were first recorded.
+    pub fn laps(&self) -> &[(&'static str, Duration)] {
+        &self.laps
+    }
+}
+
+/// Timings of a stage over all iterations of a benchmark, in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageTiming {
+    pub stage: String,
+    pub mean_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+}
+
+impl StageTiming {
+    fn new(stage: &str, durations: &[Duration]) -> Self {
+        let millis = durations
+            .iter()
+            .map(|d| d.as_secs_f64() * 1000.0)
+            .collect::<Vec<_>>();
+
+        Self {
+            stage: stage.to_string(),
+            mean_ms: millis.iter().sum::<f64>() / millis.len() as f64,
+            min_ms: millis.iter().copied().fold(f64::INFINITY, f64::min),
+            max_ms: millis.iter().copied().fold(0.0, f64::max),
+        }
+    }
+}
+
+/// Result of benchmarking a backend on a circuit, which is what `bench/plot.py` reads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    pub backend: String,
+    /// Name of the circuit, i.e. the name of its R1CS or prover key file without extensions.
+    pub circuit: String,
+    pub iterations: usize,
+    /// Timings of each stage, in the order that the backend goes through them.
+    pub stages: Vec<StageTiming>,
+    /// Timings of all stages together.
+    pub total: StageTiming,
+}
+
+/// Proves the witness of a request with a backend of the default registry for a number of iterations,
+/// and reports the time spent in each stage.
+///
+/// Each iteration loads the keys & circuit anew, so that parsing the files is timed as well.
+pub fn benchmark(
+    backend: &str,
+    request: &ProveRequest,
+    iterations: usize,
+) -> Result<BenchmarkReport> {
+    if iterations == 0 {
+        return Err(eyre!("number of iterations must be positive"));
+    }
+
+    let registry = ProverRegistry::default();
+    let mut runs = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let mut prover = registry.get(backend)?;
+
+        let mut stopwatch = Stopwatch::start();
+        prover.load(request)?;
+        stopwatch.lap(STAGE_PARSE);
+        let out = prover.prove_timed(request, &mut stopwatch)?;
+        let out = if request.calldata {
+            out.with_calldata()?
+        } else {
+            out
+        };
+        serde_json::to_string_pretty(&out)?;
+        stopwatch.lap(STAGE_SERIALIZE);
+
+        runs.push(stopwatch.laps().to_vec());
+    }
+
+    let stages = runs[0]
+        .iter()
+        .map(|(stage, _)| {
+            let durations = runs
+                .iter()
+                .map(|laps| {
+                    laps.iter()
+                        .find(|(name, _)| name == stage)
+                        .map(|(_, d)| *d)
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+            StageTiming::new(stage, &durations)
+        })
+        .collect();
+    let totals = runs
+        .iter()
+        .map(|laps| laps.iter().map(|(_, d)| *d).sum())
+        .collect::<Vec<Duration>>();
+
+    Ok(BenchmarkReport {
+        backend: backend.to_string(),
+        circuit: circuit_name(request),
+        iterations,
+        stages,
+        total: StageTiming::new("total", &totals),
+    })
+}
+
+/// Name of the circuit of a request, from its R1CS or prover key path.
+fn circuit_name(request: &ProveRequest) -> String {
+    request
+        .r1cs_path
+        .as_ref()
+        .or(request.pkey_path.as_ref())
+        .and_then(|path| path.file_name())
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.split('.').next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_benchmark_arkworks() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let request = ProveRequest {
+            wtns_path: dir.join("default").join("witness").with_extension("wtns"),
+            r1cs_path: Some(dir.join(CIRCUIT).with_extension("r1cs")),
+            pkey_path: Some(dir.join("groth16_pkey").with_extension("zkey")),
+            ..Default::default()
+        };
+
+        let report = benchmark("arkworks", &request, 2)?;
+        assert_eq!(report.circuit, CIRCUIT);
+        assert_eq!(
+            report
+                .stages
+                .iter()
+                .map(|s| s.stage.as_str())
+                .collect::<Vec<_>>(),
+            [STAGE_PARSE, STAGE_WITNESS_MAP, STAGE_MSM, STAGE_SERIALIZE]
+        );
+        for timing in report.stages.iter().chain([&report.total]) {
+            assert!(timing.min_ms <= timing.mean_ms && timing.mean_ms <= timing.max_ms);
+        }
+
+        assert!(benchmark("arkworks", &request, 0).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+//! Reader for the binary file format of Circom & SnarkJS, used by `.zkey`, `.wtns` and `.r1cs` files.
+//!
+//! A file starts with a 4-byte magic string, followed by a `u32` version and a `u32` number of sections.
+//! Each section is prefixed with its `u32` type and `u64` length; all integers are little-endian.
+
+use eyre::{eyre, Result};
+
+/// A binary file split into its sections, which refer to the underlying bytes.
+pub(crate) struct BinFile<'a> {
+    sections: Vec<(u32, &'a [u8])>,
+}
+
+impl<'a> BinFile<'a> {
+    /// Parses the sections of a binary file, checking its magic string.
+    pub fn parse(bytes: &'a [u8], magic: &[u8; 4]) -> Result<Self> {
+        let mut reader = SectionReader::new(bytes);
+        if reader.read_bytes(4)? != magic {
+            return Err(eyre!(
+                "invalid file format, expected {}",
+                String::from_utf8_lossy(magic)
+            ));
+        }
+
+        let _version = reader.read_u32()?;
+        let n_sections = reader.read_u32()?;
+        let sections = (0..n_sections)
+            .map(|_| {
+                let section_type = reader.read_u32()?;
+                let section_length = usize::try_from(reader.read_u64()?)?;
+                Ok((section_type, reader.read_bytes(section_length)?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { sections })
+    }
+
+    /// Returns a reader for the first section with the given type.
+    pub fn section(&self, section_type: u32) -> Result<SectionReader<'a>> {
+        self.sections
+            .iter()
+            .find(|(t, _)| *t == section_type)
+            .map(|(_, data)| SectionReader::new(data))
+            .ok_or_else(|| eyre!("section {} is missing", section_type))
+    }
+}
+
+/// Writes a binary file with the given magic string & version, along with its sections as `(type, data)` pairs.
+pub(crate) fn write_bin_file(
+    magic: &[u8; 4],
+    version: u32,
+    sections: &[(u32, Vec<u8>)],
+) -> Vec<u8> {
+    let mut bytes = magic.to_vec();
+    bytes.extend(version.to_le_bytes());
+    bytes.extend((sections.len() as u32).to_le_bytes());
+    for (section_type, data) in sections {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((data.len() as u64).to_le_bytes());
+        bytes.extend(data);
+    }
+
+    bytes
+}
+
+/// A cursor over the bytes of a section.
+pub(crate) struct SectionReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SectionReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Reads the next `n` bytes.
+    pub fn read_bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let remaining = self.data.len() - self.pos;
+        if n > remaining {
+            return Err(eyre!(
+                "unexpected end of data, requested {} bytes but {} remain",
+                n,
+                remaining
+            ));
+        }
+
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    #[inline]
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into()?))
+    }
+
+    #[inline]
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into()?))
+    }
+
+    /// Returns the number of bytes left to read.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+}
+
+/// Writer of `.zkey` files in the layout of SnarkJS, for the fixtures of the tests.
+#[cfg(test)]
+pub(crate) mod zkey_writer {
+    use super::write_bin_file;
+    use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, Field, PrimeField, Zero};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use std::collections::BTreeMap;
+
+    /// The sections of a `.zkey` file, starting with the section of its protocol.
+    pub(crate) struct ZkeyWriter {
+        sections: Vec<(u32, Vec<u8>)>,
+    }
+
+    impl ZkeyWriter {
+        /// Starts a zkey with the given protocol id, e.g. `1` for Groth16, `2` for PLONK or `10` for FFLONK.
+        pub fn new(protocol_id: u32) -> Self {
+            Self {
+                sections: vec![(1, protocol_id.to_le_bytes().to_vec())],
+            }
+        }
+
+        /// Appends a section with the given type.
+        pub fn section(&mut self, section_type: u32, data: Vec<u8>) -> &mut Self {
+            self.sections.push((section_type, data));
+            self
+        }
+
+        /// Writes the zkey, see [`write_bin_file`].
+        pub fn finish(&self) -> Vec<u8> {
+            write_bin_file(b"zkey", 1, &self.sections)
+        }
+    }
+
+    /// Writes a field element in Montgomery form.
+    pub(crate) fn write_field<F: PrimeField>(bytes: &mut Vec<u8>, f: &F) {
+        let n8 = F::MODULUS.to_bytes_le().len();
+        let mont = *f * F::from(2u64).pow([8 * n8 as u64]);
+        bytes.extend(mont.into_bigint().to_bytes_le());
+    }
+
+    /// Writes the size & the modulus of the base and the scalar field, which start every header.
+    pub(crate) fn write_moduli<Fq: PrimeField, Fr: PrimeField>(bytes: &mut Vec<u8>) {
+        for modulus in [Fq::MODULUS.to_bytes_le(), Fr::MODULUS.to_bytes_le()] {
+            bytes.extend((modulus.len() as u32).to_le_bytes());
+            bytes.extend(modulus);
+        }
+    }
+
+    /// Writes the coordinates of a point, where the identity is written as zeros.
+    pub(crate) fn write_point<P: SWCurveConfig>(bytes: &mut Vec<u8>, p: &Affine<P>) {
+        let (x, y) = p.xy().unwrap_or_default();
+        for c in x
+            .to_base_prime_field_elements()
+            .chain(y.to_base_prime_field_elements())
+        {
+            write_field(bytes, &c);
+        }
+    }
+
+    /// Writes a section of points.
+    pub(crate) fn points<P: SWCurveConfig>(points: &[Affine<P>]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        points.iter().for_each(|p| write_point(&mut bytes, p));
+        bytes
+    }
+
+    /// Writes the additions of PLONK & FFLONK, i.e. `(signal, signal, factor, factor)` each.
+    pub(crate) fn additions<F: PrimeField>(additions: &[(u32, u32, F, F)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for (s1, s2, f1, f2) in additions {
+            bytes.extend(s1.to_le_bytes());
+            bytes.extend(s2.to_le_bytes());
+            write_field(&mut bytes, f1);
+            write_field(&mut bytes, f2);
+        }
+        bytes
+    }
+
+    /// Writes the signals of a wire for each row.
+    pub(crate) fn map(map: &[u32]) -> Vec<u8> {
+        map.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    /// Writes polynomials of PLONK & FFLONK, i.e. the coefficients followed by the evaluations over
+    /// the domain that is 4 times larger.
+    pub(crate) fn polynomials<F: PrimeField>(polynomials: &[Vec<F>]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for coeffs in polynomials {
+            let extended = Radix2EvaluationDomain::<F>::new(4 * coeffs.len()).unwrap();
+            for c in coeffs.iter().chain(&extended.fft(coeffs)) {
+                write_field(&mut bytes, c);
+            }
+        }
+        bytes
+    }
+
+    /// Returns the evaluations of the permutation polynomials of PLONK & FFLONK, which are the identity
+    /// for unused rows, and a cycle over the wires of each signal otherwise.
+    pub(crate) fn permutation<F: PrimeField>(
+        domain: &Radix2EvaluationDomain<F>,
+        (k1, k2): (F, F),
+        maps: &[&[u32]; 3],
+    ) -> Vec<F> {
+        let n = domain.size();
+        let ks = [F::one(), k1, k2];
+        let id = |p: usize| ks[p / n] * domain.element(p % n);
+        let mut sigma = (0..3 * n).map(id).collect::<Vec<_>>();
+        let mut cycles = BTreeMap::<u32, Vec<usize>>::new();
+        for (col, map) in maps.iter().enumerate() {
+            for (row, signal) in map.iter().enumerate() {
+                cycles.entry(*signal).or_default().push(col * n + row);
+            }
+        }
+        for cycle in cycles.values() {
+            for (j, p) in cycle.iter().enumerate() {
+                sigma[*p] = id(cycle[(j + 1) % cycle.len()]);
+            }
+        }
+
+        sigma
+    }
+    /// A toy circuit in the layout of the PLONK & FFLONK zkeys of SnarkJS, where `c = a * b` is public and
+    /// the internal signal `d = a + b` is checked with `d - a - b = 0`.
+    ///
+    /// The signals are `[1, c, a, b]` with `d` as an addition, over 3 rows of a domain of size 4.
+    pub(crate) struct ToyCircuit<F: PrimeField> {
+        pub domain: Radix2EvaluationDomain<F>,
+        pub k1: F,
+        pub k2: F,
+        pub additions: Vec<(u32, u32, F, F)>,
+        /// Signals of the wires `A`, `B` & `C` for each row.
+        pub maps: [Vec<u32>; 3],
+        /// Coefficients of the selectors.
+        pub qm: Vec<F>,
+        pub ql: Vec<F>,
+        pub qr: Vec<F>,
+        pub qo: Vec<F>,
+        pub qc: Vec<F>,
+        /// Coefficients of the permutation polynomials `σ1`, `σ2` & `σ3`.
+        pub sigma: Vec<Vec<F>>,
+    }
+
+    impl<F: PrimeField> ToyCircuit<F> {
+        pub fn new() -> Self {
+            let domain = Radix2EvaluationDomain::<F>::new(4).unwrap();
+            let (k1, k2) = (F::from(2u64), F::from(3u64));
+
+            // rows are `[A, B, C, qm, ql, qr, qo]`
+            let rows = [
+                (1, 0, 0, F::zero(), F::one(), F::zero(), F::zero()),
+                (2, 3, 1, F::one(), F::zero(), F::zero(), -F::one()),
+                (4, 2, 3, F::zero(), F::one(), -F::one(), -F::one()),
+            ];
+            let maps = [
+                rows.map(|r| r.0).to_vec(),
+                rows.map(|r| r.1).to_vec(),
+                rows.map(|r| r.2).to_vec(),
+            ];
+            let sigma = permutation(&domain, (k1, k2), &maps.each_ref().map(|m| &m[..]));
+
+            let coeffs = |evals: &[F]| interpolate(&domain, evals);
+            Self {
+                k1,
+                k2,
+                additions: vec![(2, 3, F::one(), F::one())],
+                maps,
+                qm: coeffs(&rows.map(|r| r.3)),
+                ql: coeffs(&rows.map(|r| r.4)),
+                qr: coeffs(&rows.map(|r| r.5)),
+                qo: coeffs(&rows.map(|r| r.6)),
+                qc: coeffs(&[]),
+                sigma: sigma.chunks(domain.size()).map(coeffs).collect(),
+                domain,
+            }
+        }
+
+        /// Returns the coefficients of the polynomial with the given evaluations over the first rows.
+        pub fn coeffs(&self, evals: &[F]) -> Vec<F> {
+            interpolate(&self.domain, evals)
+        }
+
+        /// Writes the moduli & the sizes that start the headers of PLONK & FFLONK, i.e. the number of
+        /// signals, public signals, rows of the domain, additions and constraints.
+        pub fn write_sizes<Fq: PrimeField>(&self, header: &mut Vec<u8>) {
+            write_moduli::<Fq, F>(header);
+            for value in [5, 1, self.domain.size(), self.additions.len(), 3] {
+                header.extend((value as u32).to_le_bytes());
+            }
+        }
+
+        /// Starts a zkey with the given protocol id & header, followed by the additions and the maps.
+        pub fn zkey(&self, protocol_id: u32, header: Vec<u8>) -> ZkeyWriter {
+            let mut zkey = ZkeyWriter::new(protocol_id);
+            zkey.section(2, header)
+                .section(3, additions(&self.additions));
+            for (id, signals) in (4..).zip(&self.maps) {
+                zkey.section(id, map(signals));
+            }
+            zkey
+        }
+    }
+
+    /// Interpolates the evaluations over the first points of the domain, and zero for the others.
+    fn interpolate<F: PrimeField>(domain: &Radix2EvaluationDomain<F>, evals: &[F]) -> Vec<F> {
+        let mut evals = evals.to_vec();
+        evals.resize(domain.size(), F::zero());
+        domain.ifft(&evals)
+    }
+
+    /// Commits to a polynomial with the secret `tau`, i.e. `[p(tau)]₁` for G1.
+    pub(crate) fn commit<G: AffineRepr>(coeffs: &[G::ScalarField], tau: G::ScalarField) -> G {
+        let value = coeffs
+            .iter()
+            .rev()
+            .fold(G::ScalarField::zero(), |acc, c| acc * tau + c);
+        (G::generator().into_group() * value).into_affine()
+    }
+}
@@ -0,0 +1,174 @@
+use eyre::{eyre, Result};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+/// Resource limits of the provers, shared by all proofs within the process.
+///
+/// These only apply to the CPU work of the Arkworks backend, which is parallelized with Rayon;
+/// the other backends are either single-threaded or run on a device of their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProverConfig {
+    /// Maximum number of threads per proof, defaults to the number of CPUs.
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+    /// Whether each proof gets a thread pool of its own, instead of sharing one pool with the other proofs.
+    ///
+    /// With a pool per proof, concurrent proofs don't wait for each other, but can use up to
+    /// `maxThreads` threads each.
+    #[serde(default)]
+    pub per_proof_pool: bool,
+    /// Memory budget in bytes for the data that a proof decodes at once, e.g. the points of an MSM.
+    ///
+    /// This is a hint, the memory used by the witness & the circuit itself is not bounded by it.
+    #[serde(default)]
+    pub memory_budget: Option<usize>,
+}
+
+static CONFIG: RwLock<ProverConfig> = RwLock::new(ProverConfig {
+    max_threads: None,
+    per_proof_pool: false,
+    memory_budget: None,
+});
+
+/// Pool shared by the proofs when a thread limit is set without [`ProverConfig::per_proof_pool`],
+/// built on first use & rebuilt whenever the thread limit changes.
+static SHARED_POOL: Mutex<Option<Arc<ThreadPool>>> = Mutex::new(None);
+
+/// Returns the current configuration.
+pub fn config() -> ProverConfig {
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Replaces the configuration, which applies to the proofs that start afterwards.
+pub fn set_config(config: ProverConfig) -> Result<()> {
+    if config.max_threads == Some(0) {
+        return Err(eyre!("maximum number of threads must be positive"));
+    }
+    if config.memory_budget == Some(0) {
+        return Err(eyre!("memory budget must be positive"));
+    }
+
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = config;
+    // proofs in progress keep their own reference to the old pool
+    SHARED_POOL.lock().unwrap_or_else(|e| e.into_inner()).take();
+    Ok(())
+}
+
+/// Runs an operation within the thread pool of the given configuration, so that the parallel
+/// iterators of Arkworks & Rayon within it are bounded by [`ProverConfig::max_threads`].
+///
+/// A proof should take the [`config`] once when it starts, and use that for all of its stages.
+/// Without any limits, the operation runs on the global pool of Rayon.
+pub fn install<T: Send>(config: &ProverConfig, op: impl FnOnce() -> Result<T> + Send) -> Result<T> {
+    let pool = if config.per_proof_pool {
+        Arc::new(build_pool(config)?)
+    } else if let Some(max_threads) = config.max_threads {
+        let mut shared_pool = SHARED_POOL.lock().unwrap_or_else(|e| e.into_inner());
+        match shared_pool.as_ref() {
+            Some(pool) if pool.current_num_threads() == max_threads => pool.clone(),
+            _ => shared_pool.insert(Arc::new(build_pool(config)?)).clone(),
+        }
+    } else {
+        return op();
+    };
+
+    pool.install(op)
+}
+
+/// Returns the `tokio` runtime that is shared by all calls, which is created on first use.
+///
+/// Only the WASM witness calculator of Arkworks needs a runtime, see [`enter_runtime`].
+pub fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+
+    // futures run on the threads that block on them, so a single worker is enough for the drivers
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .expect("could not build tokio runtime")
+    })
+}
+
+/// Enters the shared runtime, unless the current thread is within a runtime already.
+///
+/// This is for code that needs a `tokio` context without being async itself. Unlike blocking on the
+/// shared runtime, it does not panic when the library is called from within another runtime.
+pub fn enter_runtime() -> Option<tokio::runtime::EnterGuard<'static>> {
+    tokio::runtime::Handle::try_current()
+        .is_err()
+        .then(|| runtime().enter())
+}
+
+/// Builds a thread pool with the thread limit of the configuration.
+fn build_pool(config: &ProverConfig) -> Result<ThreadPool> {
+    ThreadPoolBuilder::new()
+        .num_threads(config.max_threads.unwrap_or_default())
+        .thread_name(|i| format!("circomkit-prover-{}", i))
+        .build()
+        .map_err(|e| eyre!("could not build thread pool: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prover_config() -> eyre::Result<()> {
+        // the configuration is given to `install` directly, as the global one is used by the proofs of other tests
+        let shared = ProverConfig {
+            max_threads: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(install(&shared, || Ok(rayon::current_num_threads()))?, 2);
+
+        // the shared pool should follow the thread limit
+        let shared = ProverConfig {
+            max_threads: Some(4),
+            ..Default::default()
+        };
+        assert_eq!(install(&shared, || Ok(rayon::current_num_threads()))?, 4);
+
+        let per_proof = ProverConfig {
+            max_threads: Some(3),
+            per_proof_pool: true,
+            memory_budget: Some(1 << 20),
+        };
+        assert_eq!(install(&per_proof, || Ok(rayon::current_num_threads()))?, 3);
+
+        assert_eq!(
+            install(
+                &ProverConfig::default(),
+                || Ok(rayon::current_num_threads())
+            )?,
+            rayon::current_num_threads()
+        );
+
+        // invalid limits are rejected before the global configuration is replaced
+        assert!(set_config(ProverConfig {
+            max_threads: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(set_config(ProverConfig {
+            memory_budget: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_enter_runtime() {
+        let guard = enter_runtime();
+        assert!(guard.is_some());
+
+        // should neither enter again nor panic within a runtime
+        assert!(enter_runtime().is_none());
+        drop(guard);
+    }
+}
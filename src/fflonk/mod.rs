@@ -0,0 +1,221 @@
+use ark_bn254::{Fr, G1Affine};
+use eyre::{eyre, Result};
+use std::path::Path;
+
+use crate::arkworks::load_witness_file;
+use crate::snarkjs::{SnarkjsFflonkOutput, SnarkjsFflonkProof, SnarkjsPublicInputs};
+
+mod snarkjs;
+
+mod roots;
+
+mod zkey;
+pub use zkey::{FflonkProvingKey, FflonkVerifyingKey};
+
+mod prover;
+pub use prover::prove;
+
+mod verifier;
+pub use verifier::verify;
+
+/// A FFLONK proof over BN254, with the same components as a SnarkJS FFLONK proof.
+///
+/// Evaluations are at the challenge `xi`, unless stated otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FflonkProof {
+    /// Commitment to the wires & the gate quotient.
+    pub c1: G1Affine,
+    /// Commitment to the permutation polynomial & its quotients.
+    pub c2: G1Affine,
+    /// Opening proof at the roots of `xi` & `xi * w`.
+    pub w1: G1Affine,
+    /// Opening proof at the challenge `y`.
+    pub w2: G1Affine,
+    /// Evaluation of the left selector.
+    pub eval_ql: Fr,
+    /// Evaluation of the right selector.
+    pub eval_qr: Fr,
+    /// Evaluation of the multiplication selector.
+    pub eval_qm: Fr,
+    /// Evaluation of the output selector.
+    pub eval_qo: Fr,
+    /// Evaluation of the constant selector.
+    pub eval_qc: Fr,
+    /// Evaluation of `S1`.
+    pub eval_s1: Fr,
+    /// Evaluation of `S2`.
+    pub eval_s2: Fr,
+    /// Evaluation of `S3`.
+    pub eval_s3: Fr,
+    /// Evaluation of `A`.
+    pub eval_a: Fr,
+    /// Evaluation of `B`.
+    pub eval_b: Fr,
+    /// Evaluation of `C`.
+    pub eval_c: Fr,
+    /// Evaluation of `Z`.
+    pub eval_z: Fr,
+    /// Evaluation of `Z` at `xi * w`.
+    pub eval_zw: Fr,
+    /// Evaluation of `T1` at `xi * w`.
+    pub eval_t1w: Fr,
+    /// Evaluation of `T2` at `xi * w`.
+    pub eval_t2w: Fr,
+    /// Inverse of the product of the denominators of the verifier.
+    pub inv: Fr,
+}
+
+/// Proves a circuit with an existing witness and a SnarkJS FFLONK prover key.
+///
+/// The witness can be either a JSON or binary file, which is detected from its contents.
+pub fn prove_with_existing_witness(
+    wtns_path: impl AsRef<Path>,
+    pkey_path: impl AsRef<Path>,
+) -> Result<SnarkjsFflonkOutput> {
+    let wtns_path = wtns_path.as_ref();
+    let pkey_path = pkey_path.as_ref();
+
+    let wtns = load_witness_file::<Fr>(wtns_path, None)?;
+
+    let proving_key = FflonkProvingKey::read(pkey_path)
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
+
+    let (proof, public_signals) = prove(&proving_key, &wtns)?;
+    debug_assert!(
+        verify(&proving_key.vk, &proof, &public_signals).is_ok_and(|b| b),
+        "proof is not accepted"
+    );
+
+    Ok(SnarkjsFflonkOutput {
+        proof: SnarkjsFflonkProof::from(&proof),
+        public_signals: SnarkjsPublicInputs::from_arkworks(public_signals),
+        calldata: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binfile::zkey_writer::{self, commit, points, write_field, write_point, ToyCircuit};
+    use crate::snarkjs::check_snarkjs_output;
+    use ark_bn254::{g2, Fq, G2Affine};
+    use ark_ec::AffineRepr;
+    use ark_ff::{FftField, Field, One, PrimeField, UniformRand, Zero};
+    use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+    use ark_std::rand::thread_rng;
+    use num_bigint::BigUint;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    /// Builds the zkey of the toy circuit of [`ToyCircuit`], with the given secret `tau`.
+    fn toy_zkey(tau: Fr) -> Vec<u8> {
+        let circuit = ToyCircuit::<Fr>::new();
+        let n = circuit.domain.size();
+
+        // roots of unity, where `wr^3` is the generator of the domain
+        let root = |order: u32| {
+            let exponent = (BigUint::from(Fr::MODULUS) - 1u32) / order;
+            Fr::GENERATOR.pow(exponent.to_u64_digits())
+        };
+        let w8 = Radix2EvaluationDomain::<Fr>::new(8).unwrap().group_gen;
+        let (w3, w4, wr) = (root(3), w8.square(), root(3 * n as u32));
+
+        // `C0` interleaves the selectors & permutations, so `C0(tau) = sum_j tau^j P_j(tau^8)`
+        let polynomials = [
+            &circuit.ql,
+            &circuit.qr,
+            &circuit.qm,
+            &circuit.qo,
+            &circuit.qc,
+        ]
+        .into_iter()
+        .chain(&circuit.sigma)
+        .collect::<Vec<_>>();
+        let c0_value = polynomials
+            .iter()
+            .enumerate()
+            .map(|(j, p)| {
+                let value = p
+                    .iter()
+                    .rev()
+                    .fold(Fr::zero(), |acc, c| acc * tau.pow([8]) + c);
+                tau.pow([j as u64]) * value
+            })
+            .sum::<Fr>();
+        let c0: G1Affine = (G1Affine::generator() * c0_value).into();
+
+        let mut header = Vec::new();
+        circuit.write_sizes::<Fq>(&mut header);
+        for f in [circuit.k1, circuit.k2, w3, w4, w8, wr] {
+            write_field(&mut header, &f);
+        }
+        write_point::<g2::Config>(&mut header, &(G2Affine::generator() * tau).into());
+        write_point(&mut header, &c0);
+
+        let ptau = (0..9 * n + 18)
+            .map(|i| commit::<G1Affine>(&[vec![Fr::zero(); i], vec![Fr::one()]].concat(), tau))
+            .collect::<Vec<_>>();
+
+        let mut zkey = circuit.zkey(10, header);
+        for (id, p) in (7..).zip(polynomials) {
+            zkey.section(id, zkey_writer::polynomials(std::slice::from_ref(p)));
+        }
+        zkey.section(
+            15,
+            zkey_writer::polynomials(&[circuit.coeffs(&[Fr::one()])]),
+        )
+        .section(16, points(&ptau));
+
+        zkey.finish()
+    }
+
+    #[test]
+    fn test_fflonk_prove() -> eyre::Result<()> {
+        let mut rng = thread_rng();
+        let pk = FflonkProvingKey::from_bytes(&toy_zkey(Fr::rand(&mut rng)))?;
+        assert_eq!(pk.vk.n_public, 1);
+        assert_eq!(pk.additions.len(), 1);
+        assert_eq!(pk.a_map, vec![1, 2, 4]);
+
+        let (a, b) = (Fr::from(3), Fr::from(11));
+        let (proof, public_signals) = prove(&pk, &[Fr::one(), a * b, a, b])?;
+        assert_eq!(public_signals, vec![Fr::from(33)]);
+        assert!(verify(&pk.vk, &proof, &public_signals)?);
+        assert!(!verify(&pk.vk, &proof, &[Fr::from(34)])?);
+
+        // a tampered inverse is rejected, as the verifier relies on it
+        let mut tampered = proof.clone();
+        tampered.inv += Fr::one();
+        assert!(!verify(&pk.vk, &tampered, &public_signals)?);
+
+        // should not prove an unsatisfied witness
+        assert!(prove(&pk, &[Fr::one(), Fr::from(34), a, b]).is_err());
+
+        let snarkjs_proof = SnarkjsFflonkProof::from(&proof);
+        assert_eq!(snarkjs_proof.protocol, "fflonk");
+        assert_eq!(snarkjs_proof.polynomials.c1[2], "1");
+        assert_eq!(snarkjs_proof.evaluations.inv, proof.inv.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fflonk_rejects_plonk_zkey() {
+        let mut zkey = toy_zkey(Fr::one());
+        // protocol id of the first section
+        zkey[24..28].copy_from_slice(&2u32.to_le_bytes());
+        assert!(FflonkProvingKey::from_bytes(&zkey).is_err());
+    }
+
+    #[test]
+    fn test_fflonk_snarkjs() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let snarkjs_out = prove_with_existing_witness(
+            dir.join("default").join("witness.wtns"),
+            dir.join("fflonk_pkey.zkey"),
+        )?;
+        assert_eq!(snarkjs_out.proof.protocol, "fflonk");
+
+        check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "fflonk")
+    }
+}
@@ -0,0 +1,251 @@
+use ark_bn254::Fr;
+use ark_ff::{FftField, Field, One, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::rand::thread_rng;
+use eyre::{eyre, OptionExt, Result};
+
+use super::{roots::Roots, FflonkProof, FflonkProvingKey};
+use crate::plonk::polynomial::{
+    add_scaled, blind, commit, divide_by_linear, divide_by_vanishing, evaluate, omega_powers,
+};
+use crate::plonk::prover::{compute_signals, permutation_evaluations, wire_evaluations};
+use crate::plonk::transcript::Keccak256Transcript;
+
+/// Creates a FFLONK proof for the given witness, following the rounds of the SnarkJS prover so that
+/// the proof is accepted by `snarkjs fflonk verify`.
+///
+/// The witness is the one computed by Circom, i.e. without the internal signals of the additions.
+/// Returns the proof along with the public signals.
+pub fn prove(pk: &FflonkProvingKey, witness: &[Fr]) -> Result<(FflonkProof, Vec<Fr>)> {
+    let vk = &pk.vk;
+    let n = vk.domain_size;
+    let domain = vk.domain()?;
+    let omega = domain.group_gen;
+    if pk.ptau.len() < 9 * n + 18 {
+        return Err(eyre!(
+            "expected at least {} powers of tau, got {}",
+            9 * n + 18,
+            pk.ptau.len()
+        ));
+    }
+
+    let signals = compute_signals(pk.n_vars, &pk.additions, witness)?;
+    let public_signals = signals[1..=vk.n_public].to_vec();
+
+    let mut rng = thread_rng();
+    let blinders: [Fr; 9] = std::array::from_fn(|_| Fr::rand(&mut rng));
+    let mut transcript = Keccak256Transcript::default();
+
+    // all quotients by `Z_H` are computed over a coset large enough for their numerators
+    let coset = Radix2EvaluationDomain::<Fr>::new(8 * n)
+        .and_then(|d| d.get_coset(Fr::GENERATOR))
+        .ok_or_eyre("could not create the quotient domain")?;
+    let mut zh_inv_ext = coset
+        .elements()
+        .map(|x| domain.evaluate_vanishing_polynomial(x))
+        .collect::<Vec<_>>();
+    ark_ff::batch_inversion(&mut zh_inv_ext);
+    let quotient = |numerator: Vec<Fr>, len: usize| {
+        let evals = numerator
+            .iter()
+            .zip(&zh_inv_ext)
+            .map(|(num, zh_inv)| *num * zh_inv)
+            .collect::<Vec<_>>();
+        let mut coeffs = coset.ifft(&evals);
+        if coeffs.iter().skip(len).any(|c| !c.is_zero()) {
+            return Err(eyre!("constraints are not satisfied by the witness"));
+        }
+        coeffs.resize(len, Fr::zero());
+        Ok(coeffs)
+    };
+
+    // round 1: wire polynomials & the gate quotient `T0`, committed together as `C1`
+    let [a_evals, b_evals, c_evals] =
+        [&pk.a_map, &pk.b_map, &pk.c_map].map(|map| wire_evaluations(map, &signals, n));
+    let a = blind(&domain, &a_evals, &blinders[0..2]);
+    let b = blind(&domain, &b_evals, &blinders[2..4]);
+    let c = blind(&domain, &c_evals, &blinders[4..6]);
+
+    let mut pi_evals = public_signals.iter().map(|s| -*s).collect::<Vec<_>>();
+    pi_evals.resize(n, Fr::zero());
+    let mut l1_evals = vec![Fr::zero(); n];
+    l1_evals[0] = Fr::one();
+    let [pi_ext, l1_ext] = [pi_evals, l1_evals].map(|evals| coset.fft(&domain.ifft(&evals)));
+    let [a_ext, b_ext, c_ext] = [&a, &b, &c].map(|p| coset.fft(p));
+    let [ql_ext, qr_ext, qm_ext, qo_ext, qc_ext, s1_ext, s2_ext, s3_ext] = [
+        &pk.ql, &pk.qr, &pk.qm, &pk.qo, &pk.qc, &pk.s1, &pk.s2, &pk.s3,
+    ]
+    .map(|p| coset.fft(p));
+
+    let t0 = quotient(
+        (0..coset.size())
+            .map(|i| {
+                let (a, b, c) = (a_ext[i], b_ext[i], c_ext[i]);
+                ql_ext[i] * a
+                    + qr_ext[i] * b
+                    + qm_ext[i] * a * b
+                    + qo_ext[i] * c
+                    + qc_ext[i]
+                    + pi_ext[i]
+            })
+            .collect(),
+        2 * n + 2,
+    )?;
+    let c0 = interleave(&[
+        &pk.ql, &pk.qr, &pk.qm, &pk.qo, &pk.qc, &pk.s1, &pk.s2, &pk.s3,
+    ]);
+    let c1 = interleave(&[&a, &b, &c, &t0]);
+    let commit_c1 = commit(&pk.ptau, &c1);
+
+    // round 2: permutation polynomial & its quotients `T1`, `T2`, committed together as `C2`
+    transcript.add_point(&vk.c0);
+    for s in &public_signals {
+        transcript.add_scalar(s);
+    }
+    transcript.add_point(&commit_c1);
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+
+    let z_evals = permutation_evaluations(
+        &domain,
+        [&a_evals, &b_evals, &c_evals],
+        [&pk.s1, &pk.s2, &pk.s3],
+        [vk.k1, vk.k2],
+        beta,
+        gamma,
+    )?;
+    let z = blind(&domain, &z_evals, &blinders[6..9]);
+    let z_shifted = z
+        .iter()
+        .zip(omega_powers(omega))
+        .map(|(c, w)| *c * w)
+        .collect::<Vec<_>>();
+    let [z_ext, zw_ext] = [&z, &z_shifted].map(|p| coset.fft(p));
+
+    let t1 = quotient(
+        (0..coset.size())
+            .map(|i| (z_ext[i] - Fr::one()) * l1_ext[i])
+            .collect(),
+        n + 2,
+    )?;
+    let t2 = quotient(
+        coset
+            .elements()
+            .enumerate()
+            .map(|(i, x)| {
+                let (a, b, c) = (a_ext[i], b_ext[i], c_ext[i]);
+                (a + beta * x + gamma)
+                    * (b + beta * vk.k1 * x + gamma)
+                    * (c + beta * vk.k2 * x + gamma)
+                    * z_ext[i]
+                    - (a + beta * s1_ext[i] + gamma)
+                        * (b + beta * s2_ext[i] + gamma)
+                        * (c + beta * s3_ext[i] + gamma)
+                        * zw_ext[i]
+            })
+            .collect(),
+        3 * n + 6,
+    )?;
+    let c2 = interleave(&[&z, &t1, &t2]);
+    let commit_c2 = commit(&pk.ptau, &c2);
+
+    // round 3: evaluations at `xi` & `xi * w`
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&commit_c2);
+    let xi_seed = transcript.challenge();
+    let roots = Roots::new(vk, xi_seed);
+    let xi = roots.xi;
+    let xiw = xi * omega;
+
+    let [eval_ql, eval_qr, eval_qm, eval_qo, eval_qc, eval_s1, eval_s2, eval_s3] = [
+        &pk.ql, &pk.qr, &pk.qm, &pk.qo, &pk.qc, &pk.s1, &pk.s2, &pk.s3,
+    ]
+    .map(|p| evaluate(p, xi));
+    let [eval_a, eval_b, eval_c, eval_z] = [&a, &b, &c, &z].map(|p| evaluate(p, xi));
+    let [eval_zw, eval_t1w, eval_t2w] = [&z, &t1, &t2].map(|p| evaluate(p, xiw));
+
+    // round 4: first opening proof, for all the opening points of each polynomial
+    transcript.add_scalar(&xi_seed);
+    for s in [
+        eval_ql, eval_qr, eval_qm, eval_qo, eval_qc, eval_s1, eval_s2, eval_s3, eval_a, eval_b,
+        eval_c, eval_z, eval_zw, eval_t1w, eval_t2w,
+    ] {
+        transcript.add_scalar(&s);
+    }
+    let alpha = transcript.challenge();
+    let alpha2 = alpha.square();
+
+    // the remainders of these divisions are the polynomials `r_i` that interpolate the evaluations
+    let mut f = divide_by_vanishing(&c0, 8, xi);
+    add_scaled(&mut f, &divide_by_vanishing(&c1, 4, xi), alpha);
+    add_scaled(
+        &mut f,
+        &divide_by_vanishing(&divide_by_vanishing(&c2, 3, xi), 3, xiw),
+        alpha2,
+    );
+    let commit_w1 = commit(&pk.ptau, &f);
+
+    // round 5: second opening proof, at a single point `y`
+    transcript.add_scalar(&alpha);
+    transcript.add_point(&commit_w1);
+    let y = transcript.challenge();
+
+    let zs0 = y.pow([8]) - xi;
+    let zs1 = y.pow([4]) - xi;
+    let zs2 = (y.pow([3]) - xi) * (y.pow([3]) - xiw);
+    let [zs1_inv, zs2_inv] = [zs1, zs2].map(|z| z.inverse().ok_or_eyre("challenge y is a root"));
+    let q1 = alpha * zs0 * zs1_inv?;
+    let q2 = alpha2 * zs0 * zs2_inv?;
+
+    // constant terms only affect the remainder of the division, so the `r_i(y)` are not subtracted
+    let mut l = c0;
+    add_scaled(&mut l, &c1, q1);
+    add_scaled(&mut l, &c2, q2);
+    add_scaled(&mut l, &f, -zs0);
+    let commit_w2 = commit(&pk.ptau, &divide_by_linear(&l, y));
+
+    let inv = roots
+        .denominators(vk, omega, y)
+        .inverse()
+        .ok_or_eyre("a denominator of the verifier is zero")?;
+
+    let proof = FflonkProof {
+        c1: commit_c1,
+        c2: commit_c2,
+        w1: commit_w1,
+        w2: commit_w2,
+        eval_ql,
+        eval_qr,
+        eval_qm,
+        eval_qo,
+        eval_qc,
+        eval_s1,
+        eval_s2,
+        eval_s3,
+        eval_a,
+        eval_b,
+        eval_c,
+        eval_z,
+        eval_zw,
+        eval_t1w,
+        eval_t2w,
+        inv,
+    };
+
+    Ok((proof, public_signals))
+}
+
+/// Combines the polynomials `p_0, ..., p_{k-1}` as `p_0(X^k) + X p_1(X^k) + ... + X^{k-1} p_{k-1}(X^k)`.
+fn interleave(polys: &[&[Fr]]) -> Vec<Fr> {
+    let k = polys.len();
+    let len = polys.iter().map(|p| p.len()).max().unwrap_or_default();
+    let mut coeffs = vec![Fr::zero(); k * len];
+    for (j, p) in polys.iter().enumerate() {
+        for (i, c) in p.iter().enumerate() {
+            coeffs[k * i + j] = *c;
+        }
+    }
+
+    coeffs
+}
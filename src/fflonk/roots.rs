@@ -0,0 +1,72 @@
+use ark_bn254::Fr;
+use ark_ff::{Field, One};
+
+use super::FflonkVerifyingKey;
+use crate::plonk::polynomial::omega_powers;
+
+/// The opening points of the committed polynomials, derived from the challenge `xiSeed`.
+///
+/// `C0` is opened at the 8th roots of `xi`, `C1` at the 4th roots of `xi`, and `C2` at the cube
+/// roots of both `xi` and `xi * w`, where `xi = xiSeed^24`.
+#[derive(Debug, Clone)]
+pub(crate) struct Roots {
+    pub xi: Fr,
+    pub h0w8: [Fr; 8],
+    pub h1w4: [Fr; 4],
+    pub h2w3: [Fr; 3],
+    pub h3w3: [Fr; 3],
+}
+
+impl Roots {
+    pub fn new(vk: &FflonkVerifyingKey, xi_seed: Fr) -> Self {
+        let h0 = xi_seed.pow([3]);
+        let h1 = h0.square();
+        let h2 = h1 * xi_seed.square();
+        let h3 = h2 * vk.wr;
+
+        Self {
+            xi: h2.pow([3]),
+            h0w8: std::array::from_fn(|i| h0 * vk.w8.pow([i as u64])),
+            h1w4: std::array::from_fn(|i| h1 * vk.w4.pow([i as u64])),
+            h2w3: std::array::from_fn(|i| h2 * vk.w3.pow([i as u64])),
+            h3w3: std::array::from_fn(|i| h3 * vk.w3.pow([i as u64])),
+        }
+    }
+
+    /// Returns the product of all denominators of the verifier, whose inverse is the `inv` evaluation
+    /// of the proof, so that the verifier can check it instead of computing the inverses.
+    ///
+    /// These are `Z_H(xi)`, the vanishing polynomials of the openings of `C1` & `C2` at `y`, the
+    /// denominators of the Lagrange polynomials over each opening set evaluated at `y`, and those of
+    /// the Lagrange polynomials `L_i(xi)` for the public signals.
+    pub fn denominators(&self, vk: &FflonkVerifyingKey, omega: Fr, y: Fr) -> Fr {
+        let n = Fr::from(vk.domain_size as u64);
+        let xiw = self.xi * omega;
+
+        let mut acc = self.xi.pow([vk.domain_size as u64]) - Fr::one();
+        acc *= self.h1w4.iter().map(|h| y - h).product::<Fr>();
+        acc *= self
+            .h2w3
+            .iter()
+            .chain(&self.h3w3)
+            .map(|h| y - h)
+            .product::<Fr>();
+        for h in self.h0w8 {
+            acc *= Fr::from(8) * h.pow([7]) * (y - h);
+        }
+        for h in self.h1w4 {
+            acc *= Fr::from(4) * h.pow([3]) * (y - h);
+        }
+        for h in self.h2w3 {
+            acc *= Fr::from(3) * h.square() * (self.xi - xiw) * (y - h);
+        }
+        for h in self.h3w3 {
+            acc *= Fr::from(3) * h.square() * (xiw - self.xi) * (y - h);
+        }
+        for w in omega_powers(omega).take(vk.n_public.max(1)) {
+            acc *= n * (self.xi - w);
+        }
+
+        acc
+    }
+}
@@ -0,0 +1,43 @@
+use crate::arkworks::g1_to_projective_strings;
+use crate::snarkjs::{SnarkjsFflonkEvaluations, SnarkjsFflonkPolynomials, SnarkjsFflonkProof};
+
+use super::FflonkProof;
+
+impl From<&FflonkProof> for SnarkjsFflonkProof {
+    fn from(proof: &FflonkProof) -> Self {
+        Self {
+            polynomials: SnarkjsFflonkPolynomials {
+                c1: g1_to_projective_strings(&proof.c1),
+                c2: g1_to_projective_strings(&proof.c2),
+                w1: g1_to_projective_strings(&proof.w1),
+                w2: g1_to_projective_strings(&proof.w2),
+            },
+            evaluations: SnarkjsFflonkEvaluations {
+                ql: proof.eval_ql.to_string(),
+                qr: proof.eval_qr.to_string(),
+                qm: proof.eval_qm.to_string(),
+                qo: proof.eval_qo.to_string(),
+                qc: proof.eval_qc.to_string(),
+                s1: proof.eval_s1.to_string(),
+                s2: proof.eval_s2.to_string(),
+                s3: proof.eval_s3.to_string(),
+                a: proof.eval_a.to_string(),
+                b: proof.eval_b.to_string(),
+                c: proof.eval_c.to_string(),
+                z: proof.eval_z.to_string(),
+                zw: proof.eval_zw.to_string(),
+                t1w: proof.eval_t1w.to_string(),
+                t2w: proof.eval_t2w.to_string(),
+                inv: proof.inv.to_string(),
+            },
+            protocol: "fflonk".to_string(),
+            curve: "bn128".to_string(),
+        }
+    }
+}
+
+impl From<FflonkProof> for SnarkjsFflonkProof {
+    fn from(proof: FflonkProof) -> Self {
+        Self::from(&proof)
+    }
+}
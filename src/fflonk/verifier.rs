@@ -0,0 +1,149 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{Field, One};
+use eyre::{eyre, Result};
+
+use super::{roots::Roots, FflonkProof, FflonkVerifyingKey};
+use crate::plonk::polynomial::{evaluate, lagrange_evaluations};
+use crate::plonk::transcript::Keccak256Transcript;
+
+/// Verifies a FFLONK proof with the given public signals, as done by `snarkjs fflonk verify`.
+pub fn verify(vk: &FflonkVerifyingKey, proof: &FflonkProof, public_signals: &[Fr]) -> Result<bool> {
+    if public_signals.len() != vk.n_public {
+        return Err(eyre!(
+            "expected {} public signals, got {}",
+            vk.n_public,
+            public_signals.len()
+        ));
+    }
+
+    let domain = vk.domain()?;
+    let omega = domain.group_gen;
+
+    // challenges
+    let mut transcript = Keccak256Transcript::default();
+    transcript.add_point(&vk.c0);
+    for s in public_signals {
+        transcript.add_scalar(s);
+    }
+    transcript.add_point(&proof.c1);
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&proof.c2);
+    let xi_seed = transcript.challenge();
+    transcript.add_scalar(&xi_seed);
+    for s in [
+        proof.eval_ql,
+        proof.eval_qr,
+        proof.eval_qm,
+        proof.eval_qo,
+        proof.eval_qc,
+        proof.eval_s1,
+        proof.eval_s2,
+        proof.eval_s3,
+        proof.eval_a,
+        proof.eval_b,
+        proof.eval_c,
+        proof.eval_z,
+        proof.eval_zw,
+        proof.eval_t1w,
+        proof.eval_t2w,
+    ] {
+        transcript.add_scalar(&s);
+    }
+    let alpha = transcript.challenge();
+    transcript.add_scalar(&alpha);
+    transcript.add_point(&proof.w1);
+    let y = transcript.challenge();
+
+    let roots = Roots::new(vk, xi_seed);
+    let xi = roots.xi;
+    let xiw = xi * omega;
+
+    // the proof provides the inverse of all denominators, so none of them is zero if it is correct
+    if !(proof.inv * roots.denominators(vk, omega, y)).is_one() {
+        return Ok(false);
+    }
+    let inverse = |x: Fr| x.inverse().unwrap_or_default();
+
+    // quotient evaluations at `xi`, from the evaluations of the proof
+    let zh_inv = inverse(xi.pow([vk.domain_size as u64]) - Fr::one());
+    let (l1, pi) = lagrange_evaluations(&domain, xi, public_signals);
+    let (a, b, c, z) = (proof.eval_a, proof.eval_b, proof.eval_c, proof.eval_z);
+    let t0 = (proof.eval_ql * a
+        + proof.eval_qr * b
+        + proof.eval_qm * a * b
+        + proof.eval_qo * c
+        + proof.eval_qc
+        + pi)
+        * zh_inv;
+    let t1 = (z - Fr::one()) * l1 * zh_inv;
+    let betaxi = beta * xi;
+    let t2 =
+        ((a + betaxi + gamma) * (b + betaxi * vk.k1 + gamma) * (c + betaxi * vk.k2 + gamma) * z
+            - (a + beta * proof.eval_s1 + gamma)
+                * (b + beta * proof.eval_s2 + gamma)
+                * (c + beta * proof.eval_s3 + gamma)
+                * proof.eval_zw)
+            * zh_inv;
+
+    // evaluations at `y` of the polynomials `r_i` that interpolate the openings of each `C_i`
+    let r0 = evaluate(
+        &[
+            proof.eval_ql,
+            proof.eval_qr,
+            proof.eval_qm,
+            proof.eval_qo,
+            proof.eval_qc,
+            proof.eval_s1,
+            proof.eval_s2,
+            proof.eval_s3,
+        ],
+        y,
+    );
+    let r1 = evaluate(&[a, b, c, t0], y);
+    let points = roots
+        .h2w3
+        .iter()
+        .map(|h| (*h, evaluate(&[z, t1, t2], *h)))
+        .chain(roots.h3w3.iter().map(|h| {
+            (
+                *h,
+                evaluate(&[proof.eval_zw, proof.eval_t1w, proof.eval_t2w], *h),
+            )
+        }))
+        .collect::<Vec<_>>();
+    let r2 = points
+        .iter()
+        .map(|(h_i, value)| {
+            let (num, den) = points
+                .iter()
+                .filter(|(h_k, _)| h_k != h_i)
+                .fold((Fr::one(), Fr::one()), |(num, den), (h_k, _)| {
+                    (num * (y - h_k), den * (*h_i - h_k))
+                });
+            *value * num * inverse(den)
+        })
+        .sum::<Fr>();
+
+    // batched commitment `[F]`, evaluation `[E]` & opening `[J]`
+    let zs0 = y.pow([8]) - xi;
+    let zs1 = y.pow([4]) - xi;
+    let zs2 = (y.pow([3]) - xi) * (y.pow([3]) - xiw);
+    let q1 = alpha * zs0 * inverse(zs1);
+    let q2 = alpha.square() * zs0 * inverse(zs2);
+    let f = vk.c0 + proof.c1 * q1 + proof.c2 * q2;
+    let e = G1Affine::generator() * (r0 + q1 * r1 + q2 * r2);
+    let j = proof.w1 * zs0;
+
+    // e(-([F] - [E] - [J] + y[W2]), [1]_2) * e([W2], [x]_2) == 1
+    let a1 = f - e - j + proof.w2 * y;
+    let pairing = Bn254::multi_pairing(
+        [-a1, proof.w2.into_group()],
+        [G2Affine::generator(), vk.x_2],
+    );
+
+    Ok(pairing.0.is_one())
+}
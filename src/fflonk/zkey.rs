@@ -0,0 +1,238 @@
+//! Reader for SnarkJS FFLONK `.zkey` files over BN254.
+//!
+//! The sections are as follows, where field elements & curve points are little-endian in Montgomery form:
+//!
+//! 1. protocol id, which is `10` for FFLONK
+//! 2. header: field sizes & moduli, `nVars`, `nPublic`, `domainSize`, `nAdditions`, `nConstraints`,
+//!    `k1`, `k2`, the roots of unity `w3`, `w4`, `w8`, `wr`, then `[τ]₂` and the commitment to `C0`
+//! 3. additions, i.e. internal signals `factor1 * signal1 + factor2 * signal2`
+//! 4. to 6. signal ids of `A`, `B` and `C` wires for each constraint
+//! 7. to 11. `Ql, Qr, Qm, Qo, Qc` polynomials, with `n` coefficients followed by `4n` evaluations
+//! 12. to 14. `S1, S2, S3` polynomials, with `n` coefficients followed by `4n` evaluations
+//! 15. Lagrange polynomials for the public signals
+//! 16. powers of tau in G1
+//! 17. the `C0` polynomial, which is rebuilt from the selectors instead
+
+use ark_bn254::{Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{Field, One};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use eyre::{eyre, OptionExt, Result};
+use std::path::Path;
+
+use crate::binfile::BinFile;
+use crate::plonk::zkey::{read_field, read_g1, read_g2, read_modulus, read_polynomial, N8};
+
+const FFLONK_PROTOCOL_ID: u32 = 10;
+
+const HEADER_SECTION: u32 = 1;
+const FFLONK_HEADER_SECTION: u32 = 2;
+const ADDITIONS_SECTION: u32 = 3;
+const A_MAP_SECTION: u32 = 4;
+const B_MAP_SECTION: u32 = 5;
+const C_MAP_SECTION: u32 = 6;
+const QL_SECTION: u32 = 7;
+const QR_SECTION: u32 = 8;
+const QM_SECTION: u32 = 9;
+const QO_SECTION: u32 = 10;
+const QC_SECTION: u32 = 11;
+const SIGMA1_SECTION: u32 = 12;
+const SIGMA2_SECTION: u32 = 13;
+const SIGMA3_SECTION: u32 = 14;
+const PTAU_SECTION: u32 = 16;
+
+/// The verifying key of a FFLONK circuit, i.e. what SnarkJS exports as `verification_key.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FflonkVerifyingKey {
+    /// Number of public signals.
+    pub n_public: usize,
+    /// Size of the evaluation domain, a power of two.
+    pub domain_size: usize,
+    /// Coset shift for the `B` wires.
+    pub k1: Fr,
+    /// Coset shift for the `C` wires.
+    pub k2: Fr,
+    /// A primitive 3rd root of unity.
+    pub w3: Fr,
+    /// A primitive 4th root of unity.
+    pub w4: Fr,
+    /// A primitive 8th root of unity.
+    pub w8: Fr,
+    /// A cube root of the generator of the evaluation domain.
+    pub wr: Fr,
+    /// The element `τ * H` in G2.
+    pub x_2: G2Affine,
+    /// Commitment to the `C0` polynomial, which combines the selectors & permutations.
+    pub c0: G1Affine,
+}
+
+/// The proving key of a FFLONK circuit, as read from a SnarkJS `.zkey` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FflonkProvingKey {
+    /// The underlying verifying key.
+    pub vk: FflonkVerifyingKey,
+    /// Number of signals, including the internal ones from additions.
+    pub n_vars: usize,
+    /// Internal signals as `(signal1, signal2, factor1, factor2)`, computed as `factor1 * signal1 + factor2 * signal2`.
+    pub additions: Vec<(usize, usize, Fr, Fr)>,
+    /// Signal ids of `A` wires, one for each constraint.
+    pub a_map: Vec<usize>,
+    /// Signal ids of `B` wires, one for each constraint.
+    pub b_map: Vec<usize>,
+    /// Signal ids of `C` wires, one for each constraint.
+    pub c_map: Vec<usize>,
+    /// The left selector polynomial.
+    pub ql: DensePolynomial<Fr>,
+    /// The right selector polynomial.
+    pub qr: DensePolynomial<Fr>,
+    /// The multiplication selector polynomial.
+    pub qm: DensePolynomial<Fr>,
+    /// The output selector polynomial.
+    pub qo: DensePolynomial<Fr>,
+    /// The constant selector polynomial.
+    pub qc: DensePolynomial<Fr>,
+    /// The permutation polynomial of `A` wires.
+    pub s1: DensePolynomial<Fr>,
+    /// The permutation polynomial of `B` wires.
+    pub s2: DensePolynomial<Fr>,
+    /// The permutation polynomial of `C` wires.
+    pub s3: DensePolynomial<Fr>,
+    /// The elements `τ^i * G` in G1.
+    pub ptau: Vec<G1Affine>,
+}
+
+impl FflonkProvingKey {
+    /// Reads a FFLONK proving key from a `.zkey` file.
+    pub fn read(zkey_path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(zkey_path)?)
+    }
+
+    /// Parses a FFLONK proving key from the contents of a `.zkey` file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let file = BinFile::parse(bytes, b"zkey")?;
+
+        let protocol_id = file.section(HEADER_SECTION)?.read_u32()?;
+        if protocol_id != FFLONK_PROTOCOL_ID {
+            return Err(eyre!(
+                "expected a FFLONK zkey (protocol {}), got protocol {}",
+                FFLONK_PROTOCOL_ID,
+                protocol_id
+            ));
+        }
+
+        // header
+        let mut header = file.section(FFLONK_HEADER_SECTION)?;
+        read_modulus::<Fq>(&mut header, "base")?;
+        read_modulus::<Fr>(&mut header, "scalar")?;
+        let n_vars = header.read_u32()? as usize;
+        let n_public = header.read_u32()? as usize;
+        let domain_size = header.read_u32()? as usize;
+        let n_additions = header.read_u32()? as usize;
+        let n_constraints = header.read_u32()? as usize;
+        if !domain_size.is_power_of_two() || n_constraints > domain_size {
+            return Err(eyre!(
+                "invalid domain size {} for {} constraints",
+                domain_size,
+                n_constraints
+            ));
+        }
+        let vk = FflonkVerifyingKey {
+            n_public,
+            domain_size,
+            k1: read_field(&mut header)?,
+            k2: read_field(&mut header)?,
+            w3: read_field(&mut header)?,
+            w4: read_field(&mut header)?,
+            w8: read_field(&mut header)?,
+            wr: read_field(&mut header)?,
+            x_2: read_g2(&mut header)?,
+            c0: read_g1(&mut header)?,
+        };
+        vk.check_roots()?;
+
+        // additions & wire maps
+        let mut additions_section = file.section(ADDITIONS_SECTION)?;
+        let additions = (0..n_additions)
+            .map(|_| {
+                Ok((
+                    additions_section.read_u32()? as usize,
+                    additions_section.read_u32()? as usize,
+                    read_field(&mut additions_section)?,
+                    read_field(&mut additions_section)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let [a_map, b_map, c_map] = [A_MAP_SECTION, B_MAP_SECTION, C_MAP_SECTION].map(|id| {
+            let mut section = file.section(id)?;
+            (0..n_constraints)
+                .map(|_| Ok(section.read_u32()? as usize))
+                .collect::<Result<Vec<_>>>()
+        });
+
+        // polynomials, where only the coefficients are read & the extended evaluations are skipped
+        let [ql, qr, qm, qo, qc, s1, s2, s3] = [
+            QL_SECTION,
+            QR_SECTION,
+            QM_SECTION,
+            QO_SECTION,
+            QC_SECTION,
+            SIGMA1_SECTION,
+            SIGMA2_SECTION,
+            SIGMA3_SECTION,
+        ]
+        .map(|id| read_polynomial(&mut file.section(id)?, domain_size));
+
+        let mut ptau_section = file.section(PTAU_SECTION)?;
+        let ptau = (0..ptau_section.remaining() / (2 * N8))
+            .map(|_| read_g1(&mut ptau_section))
+            .collect::<Result<Vec<_>>>()?;
+        if ptau.len() < 9 * domain_size + 18 {
+            return Err(eyre!(
+                "expected at least {} powers of tau, got {}",
+                9 * domain_size + 18,
+                ptau.len()
+            ));
+        }
+
+        Ok(Self {
+            vk,
+            n_vars,
+            additions,
+            a_map: a_map?,
+            b_map: b_map?,
+            c_map: c_map?,
+            ql: ql?,
+            qr: qr?,
+            qm: qm?,
+            qo: qo?,
+            qc: qc?,
+            s1: s1?,
+            s2: s2?,
+            s3: s3?,
+            ptau,
+        })
+    }
+}
+
+impl FflonkVerifyingKey {
+    /// Returns the evaluation domain of the circuit.
+    pub(crate) fn domain(&self) -> Result<Radix2EvaluationDomain<Fr>> {
+        Radix2EvaluationDomain::<Fr>::new(self.domain_size)
+            .filter(|d| d.size() == self.domain_size)
+            .ok_or_eyre("could not create the evaluation domain")
+    }
+
+    /// Ensures that the roots of unity have the expected orders, as the openings rely on them.
+    fn check_roots(&self) -> Result<()> {
+        let is_primitive =
+            |w: Fr, order: u64| w.pow([order]).is_one() && (1..order).all(|k| !w.pow([k]).is_one());
+        if !is_primitive(self.w3, 3) || !is_primitive(self.w4, 4) || !is_primitive(self.w8, 8) {
+            return Err(eyre!("invalid roots of unity in the FFLONK header"));
+        }
+        if self.wr.pow([3]) != self.domain()?.group_gen {
+            return Err(eyre!("wr is not a cube root of the domain generator"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::{fmt, io::Read, path::Path, str::FromStr};
+
+/// Number of bytes read from the start of a file to detect its format.
+const SNIFF_LEN: u64 = 4096;
+
+/// Format of an input file, detected from its contents rather than its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    /// Binary witness, as written by the witness calculators of Circom.
+    Wtns,
+    /// Binary R1CS, as written by `circom --r1cs`.
+    R1cs,
+    /// Binary prover key of SnarkJS.
+    Zkey,
+    /// JSON, e.g. a witness exported by `snarkjs wtns export json` or an R1CS by `snarkjs r1cs export json`.
+    Json,
+}
+
+impl FileFormat {
+    /// All formats that can be detected.
+    pub const ALL: [FileFormat; 4] = [Self::Wtns, Self::R1cs, Self::Zkey, Self::Json];
+
+    /// Name of the format, which is the magic of the binary formats.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Wtns => "wtns",
+            Self::R1cs => "r1cs",
+            Self::Zkey => "zkey",
+            Self::Json => "json",
+        }
+    }
+
+    /// Detects the format from the first bytes of a file, i.e. the magic of the binary formats,
+    /// or the opening `[` or `{` of JSON.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes.get(..4) {
+            Some(b"wtns") => return Some(Self::Wtns),
+            Some(b"r1cs") => return Some(Self::R1cs),
+            Some(b"zkey") => return Some(Self::Zkey),
+            _ => {}
+        }
+
+        let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+        match text.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'[' | b'{') => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Detects the format of a file from its contents, see [`Self::detect`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut bytes = Vec::new();
+        std::fs::File::open(path)
+            .and_then(|file| file.take(SNIFF_LEN).read_to_end(&mut bytes))
+            .map_err(|e| eyre!("could not read {}: {}", path.display(), e))?;
+
+        Self::detect(&bytes).ok_or_else(|| {
+            eyre!(
+                "could not detect the format of {}, expected one of {}",
+                path.display(),
+                Self::ALL.map(|f| f.name()).join(", ")
+            )
+        })
+    }
+
+    /// Returns the given format if there is one, otherwise detects the format of the file.
+    ///
+    /// This is how the loaders take an explicit override, e.g. for a file that can not be detected.
+    pub fn resolve(path: impl AsRef<Path>, format: Option<Self>) -> Result<Self> {
+        match format {
+            Some(format) => Ok(format),
+            None => Self::from_file(path),
+        }
+    }
+}
+
+impl fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|f| f.name() == s)
+            .ok_or_else(|| eyre!("unknown file format {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CIRCUIT_DIR: &str = "example/build/multiplier_30";
+
+    #[test]
+    fn test_detect_format() -> eyre::Result<()> {
+        let dir = Path::new(CIRCUIT_DIR);
+        for (path, format) in [
+            (dir.join("default/witness.wtns"), FileFormat::Wtns),
+            (dir.join("default/witness.wtns.json"), FileFormat::Json),
+            (dir.join("multiplier_30.r1cs"), FileFormat::R1cs),
+            (dir.join("multiplier_30.r1cs.json"), FileFormat::Json),
+            (dir.join("groth16_pkey.zkey"), FileFormat::Zkey),
+        ] {
+            assert_eq!(FileFormat::from_file(&path)?, format, "{}", path.display());
+        }
+
+        // the name of the file does not matter, only its contents
+        let path = std::env::temp_dir().join("circomkit-ffi-format.wtns");
+        std::fs::write(&path, "\u{feff}\n  {\"a\": 1}")?;
+        assert_eq!(FileFormat::from_file(&path)?, FileFormat::Json);
+        std::fs::write(&path, "not a witness")?;
+        assert!(FileFormat::from_file(&path).is_err());
+        assert_eq!(
+            FileFormat::resolve(&path, Some(FileFormat::Wtns))?,
+            FileFormat::Wtns
+        );
+        std::fs::remove_file(&path)?;
+
+        assert_eq!("zkey".parse::<FileFormat>()?, FileFormat::Zkey);
+        assert!("txt".parse::<FileFormat>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+//! Exports Groth16 proofs & verifying keys over BN254 in the binary format of [gnark](https://github.com/Consensys/gnark).
+//!
+//! The layouts follow `WriteTo` (compressed) and `WriteRawTo` (uncompressed) of gnark v0.10 onwards,
+//! where the points are encoded as in [gnark-crypto](https://github.com/Consensys/gnark-crypto):
+//!
+//! - field elements are big-endian, and `E2` elements are written as `A1 || A0`
+//! - the two most significant bits of the first byte are flags for compression & the point at infinity
+//! - slices are prefixed with their length as a big-endian `u32`
+
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{Proof, ProvingKey, VerifyingKey};
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsGroth16VerificationKey, SnarkjsPublicInputs};
+
+/// Flag for an uncompressed point at infinity.
+const UNCOMPRESSED_INFINITY: u8 = 0b01 << 6;
+/// Flag for a compressed point with the lexicographically smallest `y`.
+const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+/// Flag for a compressed point with the lexicographically largest `y`.
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+/// Flag for a compressed point at infinity.
+const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+
+/// Encodes a proof in gnark's format, i.e. `Ar || Bs || Krs || Commitments || CommitmentPok`.
+///
+/// Circom circuits have no commitments, so `Commitments` is empty and `CommitmentPok` is the point at infinity.
+pub fn proof_to_gnark(proof: &Proof<Bn254>, compress: bool) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_g1(&mut bytes, &proof.a, compress);
+    write_g2(&mut bytes, &proof.b, compress);
+    write_g1(&mut bytes, &proof.c, compress);
+    write_u32(&mut bytes, 0);
+    write_g1(&mut bytes, &G1Affine::zero(), compress);
+
+    bytes
+}
+
+/// Encodes a verifying key in gnark's format, i.e.
+/// `[α]1 || [β]1 || [β]2 || [γ]2 || [δ]1 || [δ]2 || K || PublicAndCommitmentCommitted || CommitmentKeys`.
+///
+/// Arkworks verifying keys do not have `[β]1` and `[δ]1`, which are not used by gnark's verifier either;
+/// they are written as the point at infinity here. Use [`proving_key_to_gnark_vk`] to have them set.
+pub fn verifying_key_to_gnark(vk: &VerifyingKey<Bn254>, compress: bool) -> Vec<u8> {
+    write_verifying_key(vk, &G1Affine::zero(), &G1Affine::zero(), compress)
+}
+
+/// Encodes the verifying key of a proving key in gnark's format, see [`verifying_key_to_gnark`].
+pub fn proving_key_to_gnark_vk(pk: &ProvingKey<Bn254>, compress: bool) -> Vec<u8> {
+    write_verifying_key(&pk.vk, &pk.beta_g1, &pk.delta_g1, compress)
+}
+
+/// Encodes public signals as a gnark public witness, i.e. `nbPublic || nbSecret || len || elements`.
+pub fn public_witness_to_gnark(public_signals: &[Fr]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_u32(&mut bytes, public_signals.len() as u32);
+    write_u32(&mut bytes, 0);
+    write_u32(&mut bytes, public_signals.len() as u32);
+    for signal in public_signals {
+        bytes.extend(signal.into_bigint().to_bytes_be());
+    }
+
+    bytes
+}
+
+/// A SnarkJS proof, verification key & public signals in gnark's format, as hex strings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GnarkExport {
+    /// The proof, to be read with `groth16.Proof.ReadFrom`.
+    pub proof: String,
+    /// The verifying key, to be read with `groth16.VerifyingKey.ReadFrom`.
+    pub verifying_key: String,
+    /// The public witness, to be read with `witness.Witness.ReadFrom`.
+    pub public_witness: String,
+}
+
+impl GnarkExport {
+    /// Converts SnarkJS objects to gnark's binary format.
+    pub fn new(
+        proof: &SnarkjsGroth16Proof,
+        vkey: &SnarkjsGroth16VerificationKey,
+        public_signals: &SnarkjsPublicInputs,
+        compress: bool,
+    ) -> Result<Self> {
+        let proof = Proof::try_from(proof)?;
+        let vk = VerifyingKey::try_from(vkey)?;
+        let public_signals = public_signals.to_arkworks::<Fr>()?;
+
+        Ok(Self {
+            proof: hex::encode(proof_to_gnark(&proof, compress)),
+            verifying_key: hex::encode(verifying_key_to_gnark(&vk, compress)),
+            public_witness: hex::encode(public_witness_to_gnark(&public_signals)),
+        })
+    }
+}
+
+fn write_verifying_key(
+    vk: &VerifyingKey<Bn254>,
+    beta_g1: &G1Affine,
+    delta_g1: &G1Affine,
+    compress: bool,
+) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    write_g1(&mut bytes, &vk.alpha_g1, compress);
+    write_g1(&mut bytes, beta_g1, compress);
+    write_g2(&mut bytes, &vk.beta_g2, compress);
+    write_g2(&mut bytes, &vk.gamma_g2, compress);
+    write_g1(&mut bytes, delta_g1, compress);
+    write_g2(&mut bytes, &vk.delta_g2, compress);
+
+    write_u32(&mut bytes, vk.gamma_abc_g1.len() as u32);
+    for k in &vk.gamma_abc_g1 {
+        write_g1(&mut bytes, k, compress);
+    }
+
+    // `PublicAndCommitmentCommitted` is an empty `[][]uint64`, and there are no `CommitmentKeys`
+    write_u32(&mut bytes, 0);
+    write_u32(&mut bytes, 0);
+
+    bytes
+}
+
+#[inline]
+fn write_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend(value.to_be_bytes());
+}
+
+#[inline]
+fn write_fq(bytes: &mut Vec<u8>, value: &Fq) {
+    bytes.extend(value.into_bigint().to_bytes_be());
+}
+
+/// Returns whether `y > -y`, comparing their canonical integer representations.
+#[inline]
+fn is_lexicographically_largest(y: &Fq) -> bool {
+    y.into_bigint() > (-*y).into_bigint()
+}
+
+/// Same as [`is_lexicographically_largest`] but for `E2`, where `A1` is compared first.
+#[inline]
+fn is_lexicographically_largest_fq2(y: &Fq2) -> bool {
+    if y.c1.is_zero() {
+        is_lexicographically_largest(&y.c0)
+    } else {
+        is_lexicographically_largest(&y.c1)
+    }
+}
+
+fn write_g1(bytes: &mut Vec<u8>, p: &G1Affine, compress: bool) {
+    let start = bytes.len();
+    match (p.xy(), compress) {
+        (None, true) => {
+            bytes.extend([0u8; 32]);
+            bytes[start] |= COMPRESSED_INFINITY;
+        }
+        (None, false) => {
+            bytes.extend([0u8; 64]);
+            bytes[start] |= UNCOMPRESSED_INFINITY;
+        }
+        (Some((x, y)), true) => {
+            write_fq(bytes, &x);
+            bytes[start] |= if is_lexicographically_largest(&y) {
+                COMPRESSED_LARGEST
+            } else {
+                COMPRESSED_SMALLEST
+            };
+        }
+        (Some((x, y)), false) => {
+            write_fq(bytes, &x);
+            write_fq(bytes, &y);
+        }
+    }
+}
+
+fn write_g2(bytes: &mut Vec<u8>, p: &G2Affine, compress: bool) {
+    let start = bytes.len();
+    match (p.xy(), compress) {
+        (None, true) => {
+            bytes.extend([0u8; 64]);
+            bytes[start] |= COMPRESSED_INFINITY;
+        }
+        (None, false) => {
+            bytes.extend([0u8; 128]);
+            bytes[start] |= UNCOMPRESSED_INFINITY;
+        }
+        (Some((x, y)), true) => {
+            write_fq(bytes, &x.c1);
+            write_fq(bytes, &x.c0);
+            bytes[start] |= if is_lexicographically_largest_fq2(&y) {
+                COMPRESSED_LARGEST
+            } else {
+                COMPRESSED_SMALLEST
+            };
+        }
+        (Some((x, y)), false) => {
+            write_fq(bytes, &x.c1);
+            write_fq(bytes, &x.c0);
+            write_fq(bytes, &y.c1);
+            write_fq(bytes, &y.c0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::read_snarkjs_json;
+    use num_bigint::BigUint;
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_gnark_export() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let proof: SnarkjsGroth16Proof =
+            read_snarkjs_json(dir.join("default").join("groth16_proof.json"))?;
+        let public_signals: SnarkjsPublicInputs =
+            read_snarkjs_json(dir.join("default").join("public.json"))?;
+        let vkey: SnarkjsGroth16VerificationKey = read_snarkjs_json(dir.join("groth16_vkey.json"))?;
+
+        let compressed = GnarkExport::new(&proof, &vkey, &public_signals, true)?;
+        let raw = GnarkExport::new(&proof, &vkey, &public_signals, false)?;
+
+        // Ar, Bs, Krs, empty Commitments and CommitmentPok
+        assert_eq!(compressed.proof.len(), 2 * (32 + 64 + 32 + 4 + 32));
+        assert_eq!(raw.proof.len(), 2 * (64 + 128 + 64 + 4 + 64));
+
+        // uncompressed Ar is simply `x || y` in big-endian
+        let ar_x = format!("{:064x}", proof.pi_a[0].parse::<BigUint>()?);
+        let ar_y = format!("{:064x}", proof.pi_a[1].parse::<BigUint>()?);
+        assert!(raw.proof.starts_with(&format!("{}{}", ar_x, ar_y)));
+
+        // compressed Ar has the flags at the most significant bits of `x`
+        let flags = u8::from_str_radix(&compressed.proof[..2], 16)? & 0b1100_0000;
+        assert!(flags == COMPRESSED_LARGEST || flags == COMPRESSED_SMALLEST);
+        assert_eq!(compressed.proof[2..64], ar_x[2..]);
+
+        // uncompressed Bs is `x.A1 || x.A0 || y.A1 || y.A0`, where SnarkJS gives `[A0, A1]`
+        let [bs_x, bs_y] = proof
+            .pi_b
+            .clone()
+            .map(|c| c.map(|s| format!("{:064x}", s.parse::<BigUint>().unwrap())));
+        let bs_start = 2 * 64;
+        assert_eq!(
+            raw.proof[bs_start..bs_start + 2 * 128],
+            format!("{}{}{}{}", bs_x[1], bs_x[0], bs_y[1], bs_y[0])
+        );
+
+        // compressed Bs has the flags at the most significant bits of `x.A1`, and `y` is the
+        // largest if its `A1` (or `A0` when `A1` is zero) is larger than `(p - 1) / 2`
+        let bs_start = 2 * 32;
+        let flags = u8::from_str_radix(&compressed.proof[bs_start..bs_start + 2], 16)?;
+        assert_eq!(
+            compressed.proof[bs_start + 2..bs_start + 2 * 64],
+            format!("{}{}", bs_x[1], bs_x[0])[2..]
+        );
+        let half = (BigUint::from(Fq::MODULUS) - 1u32) / 2u32;
+        let [y0, y1] = proof.pi_b[1].clone().map(|s| s.parse::<BigUint>().unwrap());
+        let largest = if y1 == BigUint::ZERO {
+            y0 > half
+        } else {
+            y1 > half
+        };
+        let expected = if largest {
+            COMPRESSED_LARGEST
+        } else {
+            COMPRESSED_SMALLEST
+        };
+        assert_eq!(flags & 0b1100_0000, expected);
+
+        // negating Bs flips its flag, and keeps its `x`
+        let mut negated = Proof::<Bn254>::try_from(&proof)?;
+        negated.b = -negated.b;
+        let negated = hex::encode(proof_to_gnark(&negated, true));
+        let negated_flags = u8::from_str_radix(&negated[bs_start..bs_start + 2], 16)?;
+        assert_eq!(negated_flags & 0b1100_0000, expected ^ 0b0100_0000);
+        assert_eq!(
+            negated[bs_start + 2..bs_start + 2 * 64],
+            compressed.proof[bs_start + 2..bs_start + 2 * 64]
+        );
+
+        // α, β, β, γ, δ, δ, K with its length, and two empty slices
+        let n_k = vkey.n_public + 1;
+        assert_eq!(
+            compressed.verifying_key.len(),
+            2 * (32 * 3 + 64 * 3 + 4 + 32 * n_k + 4 + 4)
+        );
+
+        // nbPublic, nbSecret and the vector itself
+        assert_eq!(
+            raw.public_witness,
+            format!(
+                "{:08x}{:08x}{:08x}{:064x}",
+                1,
+                0,
+                1,
+                public_signals.0[0].parse::<BigUint>()?
+            )
+        );
+
+        Ok(())
+    }
+}
@@ -1,40 +1,272 @@
-use eyre::{eyre, Result};
+use eyre::{eyre, Context, OptionExt, Result};
+use icicle_runtime::{get_registered_devices, load_backend, load_backend_from_env_or_default};
 use icicle_snark::{groth16_prove, CacheManager};
-use std::path::Path;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsOutput, SnarkjsPublicInputs};
+use crate::format::FileFormat;
+use crate::prime::CircomPrime;
+use crate::prover::{ProveRequest, Prover, ProverCapabilities};
+use crate::snarkjs::{
+    SnarkjsGroth16Proof, SnarkjsGroth16VerificationKey, SnarkjsOutput, SnarkjsPublicInputs,
+};
 
-const ALLOWED_DEVICES: [&str; 3] = ["CPU", "CUDA", "METAL"];
+/// Environment variable that ICICLE reads the installation directory of its backends from.
+pub const BACKEND_INSTALL_DIR_ENV: &str = "ICICLE_BACKEND_INSTALL_DIR";
 
+/// A device that ICICLE can prove on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IcicleDevice {
+    #[default]
+    Cpu,
+    Cuda,
+    Metal,
+}
+
+impl IcicleDevice {
+    /// All devices supported by ICICLE.
+    pub const ALL: [IcicleDevice; 3] = [Self::Cpu, Self::Cuda, Self::Metal];
+
+    /// Name of the device as ICICLE expects it, e.g. `CPU`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cpu => "CPU",
+            Self::Cuda => "CUDA",
+            Self::Metal => "METAL",
+        }
+    }
+}
+
+impl fmt::Display for IcicleDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for IcicleDevice {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.to_uppercase();
+        Self::ALL
+            .into_iter()
+            .find(|d| d.name() == s)
+            .ok_or_else(|| eyre!("device must be one of {:?}", Self::ALL.map(|d| d.name())))
+    }
+}
+
+/// Configuration of the ICICLE prover.
+#[derive(Debug, Clone, Default)]
+pub struct IcicleConfig {
+    /// Device to prove on.
+    pub device: IcicleDevice,
+    /// Installation directory of the ICICLE backends, e.g. `/opt/icicle/lib/backend`.
+    ///
+    /// If not given, ICICLE falls back to [`BACKEND_INSTALL_DIR_ENV`] or its default directory.
+    pub backend_dir: Option<PathBuf>,
+}
+
+impl IcicleConfig {
+    /// Creates a configuration for the given device, with the backend directory read from [`BACKEND_INSTALL_DIR_ENV`].
+    pub fn from_env(device: IcicleDevice) -> Self {
+        Self {
+            device,
+            backend_dir: std::env::var_os(BACKEND_INSTALL_DIR_ENV).map(PathBuf::from),
+        }
+    }
+
+    /// Loads the backends from the configured backend directory, or from ICICLE's own default if there is none.
+    ///
+    /// The directory is passed to ICICLE directly instead of through [`BACKEND_INSTALL_DIR_ENV`],
+    /// as setting environment variables is not thread-safe.
+    #[tracing::instrument(skip_all, fields(backend = "icicle", device = %self.device))]
+    fn load_backend(&self) -> Result<()> {
+        match &self.backend_dir {
+            Some(backend_dir) => {
+                if !backend_dir.is_dir() {
+                    return Err(eyre!(
+                        "ICICLE backend directory {} does not exist",
+                        backend_dir.display()
+                    ));
+                }
+
+                load_backend(path_to_str(backend_dir)?)
+            }
+            None => load_backend_from_env_or_default(),
+        }
+        .map_err(|e| eyre!("could not load ICICLE backends: {:?}", e))
+    }
+}
+
+/// Loads the backends with the given configuration, and returns the names of the devices that ICICLE registered,
+/// e.g. `["CPU", "CUDA"]` on a machine with the CUDA backend installed.
+///
+/// The CPU device is always available, other devices are only listed if their backend could be loaded.
+pub fn list_devices(config: &IcicleConfig) -> Result<Vec<String>> {
+    config.load_backend()?;
+
+    get_registered_devices().map_err(|e| eyre!("could not list ICICLE devices: {:?}", e))
+}
+
+/// A prover for a single prover key, which keeps the data that ICICLE computes from the key
+/// (e.g. the points moved to the device) cached across proofs.
+pub struct IcicleSession {
+    config: IcicleConfig,
+    pkey_path: String,
+    cache: CacheManager,
+}
+
+impl IcicleSession {
+    /// Loads the backends with the given configuration, and makes sure that its device is available.
+    ///
+    /// The prover key is read on the first proof, and reused by the proofs after that.
+    #[tracing::instrument(
+        name = "open_session",
+        skip_all,
+        fields(backend = "icicle", path = %pkey_path.as_ref().display()),
+    )]
+    pub fn new(pkey_path: impl AsRef<Path>, config: IcicleConfig) -> Result<Self> {
+        let pkey_path = pkey_path.as_ref();
+        let devices = list_devices(&config)?;
+        if !devices.iter().any(|d| d == config.device.name()) {
+            return Err(eyre!(
+                "device {} is not available, loaded devices are {:?}",
+                config.device,
+                devices
+            ));
+        }
+
+        Ok(Self {
+            pkey_path: path_to_str(pkey_path)?.to_string(),
+            cache: CacheManager::default(),
+            config,
+        })
+    }
+
+    /// Configuration of the session.
+    pub fn config(&self) -> &IcicleConfig {
+        &self.config
+    }
+
+    /// Proves the circuit of the prover key with an existing witness.
+    ///
+    /// On the first proof of the session, this includes loading the prover key to the device.
+    #[tracing::instrument(
+        name = "prove_circuit",
+        skip_all,
+        fields(
+            backend = "icicle",
+            device = %self.config.device,
+            path = %wtns_path.as_ref().display(),
+        ),
+    )]
+    pub fn prove(&mut self, wtns_path: impl AsRef<Path>) -> Result<SnarkjsOutput> {
+        let wtns_path = wtns_path.as_ref();
+        // ICICLE parses the witness itself, and only reads binary witnesses
+        let format = FileFormat::from_file(wtns_path)?;
+        if format != FileFormat::Wtns {
+            return Err(eyre!(
+                "witness {} is in {} format, ICICLE requires a binary witness",
+                wtns_path.display(),
+                format
+            ));
+        }
+
+        let (proof_value, public_signals_value) = groth16_prove(
+            path_to_str(wtns_path)?,
+            &self.pkey_path,
+            self.config.device.name(),
+            &mut self.cache,
+        )
+        .map_err(|e| eyre!("could not generate proof on {}: {}", self.config.device, e))?;
+
+        let proof = serde_json::from_value::<SnarkjsGroth16Proof>(proof_value)
+            .wrap_err("could not parse proof")?;
+        let public_signals = serde_json::from_value::<SnarkjsPublicInputs>(public_signals_value)
+            .wrap_err("could not parse public signals")?;
+
+        Ok(SnarkjsOutput {
+            proof,
+            public_signals,
+            calldata: None,
+        })
+    }
+}
+
+/// Proves a circuit with an existing witness and prover key, on the given device.
+///
+/// The backend directory is read from [`BACKEND_INSTALL_DIR_ENV`], see [`prove_with_config`] to configure it.
 pub fn prove_with_existing_witness(
     wtns_path: impl AsRef<Path>,
     pkey_path: impl AsRef<Path>,
     device: &str,
 ) -> Result<SnarkjsOutput> {
-    let device = device.to_uppercase();
-    if !ALLOWED_DEVICES.contains(&device.as_str()) {
-        return Err(eyre::eyre!("device must be one of {:?}", ALLOWED_DEVICES));
+    let config = IcicleConfig::from_env(device.parse()?);
+    prove_with_config(wtns_path, pkey_path, &config)
+}
+
+/// Proves a circuit with an existing witness and prover key, with the given configuration.
+///
+/// Nothing is cached between calls, see [`IcicleSession`] to prove many times with the same prover key.
+pub fn prove_with_config(
+    wtns_path: impl AsRef<Path>,
+    pkey_path: impl AsRef<Path>,
+    config: &IcicleConfig,
+) -> Result<SnarkjsOutput> {
+    IcicleSession::new(pkey_path, config.clone())?.prove(wtns_path)
+}
+
+/// ICICLE backend of the [`ProverRegistry`](crate::prover::ProverRegistry), requires the prover key.
+///
+/// The device defaults to the CPU, and the backend directory to [`BACKEND_INSTALL_DIR_ENV`].
+#[derive(Default)]
+pub struct IcicleProver {
+    session: Option<IcicleSession>,
+}
+
+impl Prover for IcicleProver {
+    fn capabilities(&self) -> ProverCapabilities {
+        ProverCapabilities {
+            curves: vec![CircomPrime::Bn128],
+            requires_r1cs: false,
+            requires_pkey: true,
+            devices: IcicleDevice::ALL.map(|d| d.name()).to_vec(),
+        }
+    }
+
+    fn load(&mut self, request: &ProveRequest) -> Result<()> {
+        let device = match &request.device {
+            Some(device) => device.parse()?,
+            None => IcicleDevice::default(),
+        };
+        let mut config = IcicleConfig::from_env(device);
+        if let Some(backend_dir) = &request.backend_dir {
+            config.backend_dir = Some(backend_dir.clone());
+        }
+
+        self.session = Some(IcicleSession::new(request.require_pkey("ICICLE")?, config)?);
+        Ok(())
+    }
+
+    fn prove(&mut self, request: &ProveRequest) -> Result<SnarkjsOutput> {
+        self.session
+            .as_mut()
+            .ok_or_eyre("ICICLE prover is not loaded")?
+            .prove(&request.wtns_path)
+    }
+
+    fn verify(&self, vkey: &SnarkjsGroth16VerificationKey, output: &SnarkjsOutput) -> Result<bool> {
+        crate::arkworks::verify_snarkjs(vkey, &output.proof, &output.public_signals)
     }
+}
 
-    let (proof_value, public_signals_value) = groth16_prove(
-        wtns_path.as_ref().as_os_str().to_str().unwrap(),
-        pkey_path.as_ref().as_os_str().to_str().unwrap(),
-        // &proof_path,
-        // &public_path,
-        &device,
-        &mut CacheManager::default(),
-    )
-    .map_err(|e| eyre!("could not generate proof: {}", e))?;
-
-    let proof =
-        serde_json::from_value::<SnarkjsGroth16Proof>(proof_value).expect("could not parse proof");
-    let public_signals = serde_json::from_value::<SnarkjsPublicInputs>(public_signals_value)
-        .expect("could not parse public signals");
-
-    Ok(SnarkjsOutput {
-        proof,
-        public_signals,
-    })
+/// ICICLE takes paths as strings.
+fn path_to_str(path: &Path) -> Result<&str> {
+    path.to_str()
+        .ok_or_else(|| eyre!("path {} is not valid UTF-8", path.display()))
 }
 
 #[cfg(test)]
@@ -49,24 +281,62 @@ mod tests {
     /// Run with:
     ///
     /// ```sh
-    /// cargo test --package circomkit-ffi --lib -- icicle::tests::test_icicle_with_witness --exact --show-output
+    /// cargo test --features icicle --lib -- icicle::tests::test_icicle_with_witness --exact --show-output
     /// ```
+    ///
+    /// The backends are loaded from `ICICLE_BACKEND_INSTALL_DIR` if it is set.
     #[test]
+    #[cfg(target_os = "linux")]
     fn test_icicle_with_witness() -> eyre::Result<()> {
-        std::env::set_var(
-            "ICICLE_BACKEND_INSTALL_DIR",
-            "/Users/erhant/.ingonyama/icicle",
-        );
         let dir = Path::new("example/build").join(CIRCUIT);
         let zkey = dir.join("groth16_pkey").with_extension("zkey");
         let witness = dir
             .join("default") // input name
             .join("witness")
             .with_extension("wtns");
-        let device = "CPU"; //CPU
 
-        let snarkjs_out =
-            prove_with_existing_witness(&witness, &zkey, device).expect("could not prove");
+        let config = IcicleConfig::from_env(IcicleDevice::Cpu);
+        let snarkjs_out = prove_with_config(&witness, &zkey, &config)?;
         check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "icicle")
     }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_icicle_session() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let zkey = dir.join("groth16_pkey").with_extension("zkey");
+        let witness = dir
+            .join("default") // input name
+            .join("witness")
+            .with_extension("wtns");
+
+        let config = IcicleConfig::from_env(IcicleDevice::Cpu);
+        assert!(list_devices(&config)?.contains(&"CPU".to_string()));
+
+        // proofs after the first one should reuse the cached prover key
+        let mut session = IcicleSession::new(&zkey, config)?;
+        for _ in 0..2 {
+            let snarkjs_out = session.prove(&witness)?;
+            check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "icicle_session")?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_icicle_config() -> eyre::Result<()> {
+        assert_eq!("cpu".parse::<IcicleDevice>()?, IcicleDevice::Cpu);
+        assert_eq!("CUDA".parse::<IcicleDevice>()?, IcicleDevice::Cuda);
+        assert!("TPU".parse::<IcicleDevice>().is_err());
+
+        // should fail before proving if the backend directory is missing
+        let config = IcicleConfig {
+            device: IcicleDevice::Cpu,
+            backend_dir: Some(PathBuf::from("/path/to/nowhere")),
+        };
+        let err = prove_with_config("witness.wtns", "pkey.zkey", &config).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+
+        Ok(())
+    }
 }
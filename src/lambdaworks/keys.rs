@@ -0,0 +1,228 @@
+//! Binary format for the Groth16 keys of Lambdaworks, so that a setup can be reused across proofs.
+//!
+//! The file follows the layout of Circom binary files (see [`BinFile`]) with the magic string `lwgk`
+//! and version `1`. Field elements are little-endian in canonical form (i.e. not in Montgomery form),
+//! each taking `n8 = 48` bytes. Points are given in affine coordinates `(x, y)`, where the coordinates
+//! of G2 points are `(c0, c1)` pairs, and the identity is `(0, 0)`.
+//!
+//! Key files are written by [`super::setup`] and trusted when read back: field elements must be reduced
+//! and points must be on the curve, but their subgroup is not checked, as doing so for every point of
+//! a key is rather costly.
+//!
+//! 1. header: `n8`
+//! 2. `[α]₁`, `[β]₁`, `[β]₂`, `[γ]₂`, `[δ]₁`, `[δ]₂`
+//! 3. `K_i(τ)` in G1 for the public signals, along with the constant wire at the start
+//! 4. `A_i(τ)` in G1 for each wire
+//! 5. `B_i(τ)` in G1 for each wire
+//! 6. `B_i(τ)` in G2 for each wire
+//! 7. `K_i(τ) / δ` in G1 for the private signals
+//! 8. `τ^i * t(τ) / δ` in G1 for the powers of `τ`
+//!
+//! Sections 3 to 8 start with a `u32` number of points. The verifying key is given by the first three
+//! sections, so it can be read from the same file with [`read_verifying_key`].
+
+use eyre::{eyre, Result};
+use lambdaworks_groth16::common::{G1Point, G2Point, Pairing};
+use lambdaworks_groth16::{ProvingKey, VerifyingKey};
+use lambdaworks_math::cyclic_group::IsGroup;
+use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::field_extension::{
+    BLS12381PrimeField, Degree2ExtensionField,
+};
+use lambdaworks_math::elliptic_curve::traits::{FromAffine, IsPairing};
+use lambdaworks_math::field::element::FieldElement;
+use lambdaworks_math::traits::ByteConversion;
+
+use crate::binfile::{write_bin_file, BinFile, SectionReader};
+
+type Fq = FieldElement<BLS12381PrimeField>;
+type Fq2 = FieldElement<Degree2ExtensionField>;
+
+const MAGIC: &[u8; 4] = b"lwgk";
+const VERSION: u32 = 1;
+const N8: usize = 48;
+
+const HEADER_SECTION: u32 = 1;
+const POINTS_SECTION: u32 = 2;
+const VERIFIER_K_SECTION: u32 = 3;
+const L_SECTION: u32 = 4;
+const R_G1_SECTION: u32 = 5;
+const R_G2_SECTION: u32 = 6;
+const PROVER_K_SECTION: u32 = 7;
+const Z_SECTION: u32 = 8;
+
+/// Serializes a proving key along with its verifying key.
+///
+/// The verifying key of Lambdaworks only keeps the pairing of `[α]₁` & `[β]₂`, so these are taken from the proving key.
+pub fn write_proving_key(pk: &ProvingKey, vk: &VerifyingKey) -> Vec<u8> {
+    let mut points = Vec::new();
+    write_g1(&mut points, &pk.alpha_g1);
+    write_g1(&mut points, &pk.beta_g1);
+    write_g2(&mut points, &pk.beta_g2);
+    write_g2(&mut points, &vk.gamma_g2);
+    write_g1(&mut points, &pk.delta_g1);
+    write_g2(&mut points, &pk.delta_g2);
+
+    write_bin_file(
+        MAGIC,
+        VERSION,
+        &[
+            (HEADER_SECTION, (N8 as u32).to_le_bytes().to_vec()),
+            (POINTS_SECTION, points),
+            (
+                VERIFIER_K_SECTION,
+                write_points(&vk.verifier_k_tau_g1, write_g1),
+            ),
+            (L_SECTION, write_points(&pk.l_tau_g1, write_g1)),
+            (R_G1_SECTION, write_points(&pk.r_tau_g1, write_g1)),
+            (R_G2_SECTION, write_points(&pk.r_tau_g2, write_g2)),
+            (
+                PROVER_K_SECTION,
+                write_points(&pk.prover_k_tau_g1, write_g1),
+            ),
+            (Z_SECTION, write_points(&pk.z_powers_of_tau_g1, write_g1)),
+        ],
+    )
+}
+
+/// Parses a proving key along with its verifying key, as written by [`write_proving_key`].
+pub fn read_proving_key(bytes: &[u8]) -> Result<(ProvingKey, VerifyingKey)> {
+    let vk = read_verifying_key(bytes)?;
+    let file = parse(bytes)?;
+
+    let mut points = file.section(POINTS_SECTION)?;
+    let alpha_g1 = read_g1(&mut points)?;
+    let beta_g1 = read_g1(&mut points)?;
+    let beta_g2 = read_g2(&mut points)?;
+    let _gamma_g2 = read_g2(&mut points)?;
+    let delta_g1 = read_g1(&mut points)?;
+    let delta_g2 = read_g2(&mut points)?;
+
+    let pk = ProvingKey {
+        alpha_g1,
+        beta_g1,
+        beta_g2,
+        delta_g1,
+        delta_g2,
+        l_tau_g1: read_points(&file, L_SECTION, read_g1)?,
+        r_tau_g1: read_points(&file, R_G1_SECTION, read_g1)?,
+        r_tau_g2: read_points(&file, R_G2_SECTION, read_g2)?,
+        prover_k_tau_g1: read_points(&file, PROVER_K_SECTION, read_g1)?,
+        z_powers_of_tau_g1: read_points(&file, Z_SECTION, read_g1)?,
+    };
+    if pk.l_tau_g1.len() != pk.r_tau_g1.len() || pk.r_tau_g1.len() != pk.r_tau_g2.len() {
+        return Err(eyre!("mismatching number of wires in the proving key"));
+    }
+
+    Ok((pk, vk))
+}
+
+/// Parses only the verifying key from the contents of a key file, skipping the rest of the proving key.
+pub fn read_verifying_key(bytes: &[u8]) -> Result<VerifyingKey> {
+    let file = parse(bytes)?;
+
+    let mut points = file.section(POINTS_SECTION)?;
+    let alpha_g1 = read_g1(&mut points)?;
+    let _beta_g1 = read_g1(&mut points)?;
+    let beta_g2 = read_g2(&mut points)?;
+    let gamma_g2 = read_g2(&mut points)?;
+    let _delta_g1 = read_g1(&mut points)?;
+    let delta_g2 = read_g2(&mut points)?;
+
+    Ok(VerifyingKey {
+        alpha_g1_times_beta_g2: Pairing::compute(&alpha_g1, &beta_g2)
+            .map_err(|e| eyre!("could not compute the pairing of alpha & beta: {:?}", e))?,
+        delta_g2,
+        gamma_g2,
+        verifier_k_tau_g1: read_points(&file, VERIFIER_K_SECTION, read_g1)?,
+    })
+}
+
+/// Parses the sections of a key file, checking its field size.
+fn parse(bytes: &[u8]) -> Result<BinFile> {
+    let file = BinFile::parse(bytes, MAGIC)?;
+
+    let n8 = file.section(HEADER_SECTION)?.read_u32()? as usize;
+    if n8 != N8 {
+        return Err(eyre!("expected field elements of {} bytes, got {}", N8, n8));
+    }
+
+    Ok(file)
+}
+
+fn write_fq(bytes: &mut Vec<u8>, e: &Fq) {
+    bytes.extend(e.to_bytes_le());
+}
+
+/// Reads a field element, rejecting values that are not smaller than the modulus.
+fn read_fq(reader: &mut SectionReader) -> Result<Fq> {
+    let bytes = reader.read_bytes(N8)?;
+    let e = Fq::from_bytes_le(bytes).map_err(|e| eyre!("could not read field element: {:?}", e))?;
+
+    // the element is reduced when parsed, so a larger value would not round-trip
+    if e.to_bytes_le() != bytes {
+        return Err(eyre!("field element is not smaller than the modulus"));
+    }
+
+    Ok(e)
+}
+
+fn write_g1(bytes: &mut Vec<u8>, p: &G1Point) {
+    if p.is_neutral_element() {
+        bytes.extend([0u8; 2 * N8]);
+        return;
+    }
+
+    let p = p.to_affine();
+    write_fq(bytes, p.x());
+    write_fq(bytes, p.y());
+}
+
+/// Reads a G1 point, only checking that it is on the curve.
+fn read_g1(reader: &mut SectionReader) -> Result<G1Point> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    if x == Fq::zero() && y == Fq::zero() {
+        return Ok(G1Point::neutral_element());
+    }
+
+    G1Point::from_affine(x, y).map_err(|e| eyre!("G1 point is not on the curve: {:?}", e))
+}
+
+fn write_g2(bytes: &mut Vec<u8>, p: &G2Point) {
+    if p.is_neutral_element() {
+        bytes.extend([0u8; 4 * N8]);
+        return;
+    }
+
+    let p = p.to_affine();
+    for c in p.x().value().iter().chain(p.y().value()) {
+        write_fq(bytes, c);
+    }
+}
+
+/// Reads a G2 point, only checking that it is on the curve.
+fn read_g2(reader: &mut SectionReader) -> Result<G2Point> {
+    let x = Fq2::new([read_fq(reader)?, read_fq(reader)?]);
+    let y = Fq2::new([read_fq(reader)?, read_fq(reader)?]);
+    if x == Fq2::zero() && y == Fq2::zero() {
+        return Ok(G2Point::neutral_element());
+    }
+
+    G2Point::from_affine(x, y).map_err(|e| eyre!("G2 point is not on the curve: {:?}", e))
+}
+
+fn write_points<P>(points: &[P], write: impl Fn(&mut Vec<u8>, &P)) -> Vec<u8> {
+    let mut bytes = (points.len() as u32).to_le_bytes().to_vec();
+    points.iter().for_each(|p| write(&mut bytes, p));
+    bytes
+}
+
+fn read_points<P>(
+    file: &BinFile,
+    section_type: u32,
+    read: impl Fn(&mut SectionReader) -> Result<P>,
+) -> Result<Vec<P>> {
+    let mut section = file.section(section_type)?;
+    let n = section.read_u32()? as usize;
+    (0..n).map(|_| read(&mut section)).collect()
+}
@@ -1,66 +1,342 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use eyre::{eyre, Context};
+use eyre::{eyre, Context, OptionExt};
 use lambdaworks_circom_adapter::*;
 use lambdaworks_groth16::common::FrElement;
+use lambdaworks_groth16::QuadraticArithmeticProgram;
 use lambdaworks_math::traits::ByteConversion;
 
+use crate::format::FileFormat;
+use crate::prime::CircomPrime;
+use crate::prover::{ProveRequest, Prover, ProverCapabilities};
 use crate::snarkjs::*;
-use crate::witness::parse_witness_to_elems;
+use crate::witness::{parse_witness_json, parse_witness_to_elems};
 
 mod snarkjs;
 // mod zkey; // TODO: !!!
 
+pub mod keys;
+
+/// File name of the proving key (which includes the verifying key) written by [`setup`], see [`keys`] for its format.
+pub const PKEY_FILENAME: &str = "lambdaworks_pkey.bin";
+/// File name of the verification key (in SnarkJS format) written by [`setup`].
+pub const VKEY_FILENAME: &str = "lambdaworks_vkey.json";
+
+/// Proves a circuit with an existing witness, using a random setup that is discarded afterwards.
+///
+/// See [`prove_with_witness_and_vkey`] to keep the verifying key of the setup, or [`setup`] to reuse a setup.
 pub fn prove_with_witness(
     r1cs_path: impl AsRef<Path>,
     wtns_path: impl AsRef<Path>,
 ) -> eyre::Result<SnarkjsOutput> {
-    let wtns_path = wtns_path.as_ref();
+    prove_with_witness_and_vkey(r1cs_path, wtns_path).map(|(out, _)| out)
+}
+
+/// Proves a circuit with an existing witness using a random setup, and returns the verifying key
+/// of that setup as well so that the proof can be verified later, e.g. with [`verify`].
+pub fn prove_with_witness_and_vkey(
+    r1cs_path: impl AsRef<Path>,
+    wtns_path: impl AsRef<Path>,
+) -> eyre::Result<(SnarkjsOutput, SnarkjsGroth16VerificationKey)> {
+    let (qap, wtns, pubs) = load_circuit(r1cs_path, None, wtns_path, None)?;
+    Ok(prove_with_random_setup(&qap, &wtns, &pubs))
+}
+
+/// Runs a random setup for the circuit, and writes the keys to the given directory, so that
+/// proofs of the same circuit share a verifier.
+///
+/// The proving key is written to [`PKEY_FILENAME`], and the verification key to [`VKEY_FILENAME`],
+/// which is returned as well.
+#[tracing::instrument(
+    skip_all,
+    fields(backend = "lambdaworks", path = %r1cs_path.as_ref().display()),
+)]
+pub fn setup(
+    r1cs_path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> eyre::Result<SnarkjsGroth16VerificationKey> {
     let r1cs_path = r1cs_path.as_ref();
+    let out_dir = out_dir.as_ref();
+    ensure_json_r1cs(r1cs_path, None)?;
+
+    // the witness is not needed for the setup, but the conversion expects one with the right length
+    let r1cs_json: serde_json::Value = read_snarkjs_json(r1cs_path)?;
+    let n_vars = r1cs_json["nVars"]
+        .as_u64()
+        .ok_or_eyre("could not read the number of variables of R1CS")?;
+    let r1cs = read_circom_r1cs(r1cs_path)
+        .map_err(|e| eyre!("could not load R1CS {}: {:?}", r1cs_path.display(), e))?;
+    let (qap, _, _) = circom_to_lambda(r1cs, vec![FrElement::zero(); n_vars as usize]);
+    let (proving_key, verifying_key) = lambdaworks_groth16::setup(&qap);
+    let vkey = snarkjs::snarkjs_vkey(&proving_key, &verifying_key);
+
+    std::fs::create_dir_all(out_dir)
+        .wrap_err_with(|| format!("could not create {}", out_dir.display()))?;
+    std::fs::write(
+        out_dir.join(PKEY_FILENAME),
+        keys::write_proving_key(&proving_key, &verifying_key),
+    )?;
+    std::fs::write(out_dir.join(VKEY_FILENAME), vkey.to_string())?;
 
-    if !r1cs_path.to_string_lossy().ends_with(".json") {
-        panic!("R1CS file must be in JSON format");
+    Ok(vkey)
+}
+
+/// Proves a circuit with an existing witness, using the keys written by [`setup`].
+pub fn prove_with_existing_keys(
+    r1cs_path: impl AsRef<Path>,
+    wtns_path: impl AsRef<Path>,
+    pkey_path: impl AsRef<Path>,
+) -> eyre::Result<SnarkjsOutput> {
+    let (proving_key, verifying_key) = load_keys(pkey_path.as_ref())?;
+    let (qap, wtns, pubs) = load_circuit(r1cs_path, None, wtns_path, None)?;
+    if wtns.len() != proving_key.l_tau_g1.len() {
+        return Err(eyre!("proving key does not match the circuit"));
     }
-    let r1cs = read_circom_r1cs(r1cs_path).unwrap();
 
-    // if wtns path ends with JSON, use `load_witness_json`, otherwise, use `load_witness`
-    let wtns = if wtns_path.ends_with(".json") {
-        read_circom_witness(wtns_path).map_err(|e| {
-            eyre!(
-                "could not load witness JSON from {}: {:?}",
+    Ok(prove_with_keys(
+        &qap,
+        &wtns,
+        &pubs,
+        &proving_key,
+        &verifying_key,
+    ))
+}
+
+/// Lambdaworks backend of the [`ProverRegistry`](crate::prover::ProverRegistry), requires the R1CS in JSON format.
+///
+/// If a prover key written by [`setup`] is given, the proofs share its verifier; otherwise, each proof
+/// is made with a random setup that is discarded afterwards.
+#[derive(Default)]
+pub struct LambdaworksProver {
+    r1cs_path: PathBuf,
+    r1cs_format: Option<FileFormat>,
+    keys: Option<(
+        lambdaworks_groth16::ProvingKey,
+        lambdaworks_groth16::VerifyingKey,
+    )>,
+}
+
+impl Prover for LambdaworksProver {
+    fn capabilities(&self) -> ProverCapabilities {
+        ProverCapabilities {
+            curves: vec![CircomPrime::Bls12381],
+            requires_r1cs: true,
+            requires_pkey: false,
+            devices: vec![],
+        }
+    }
+
+    fn load(&mut self, request: &ProveRequest) -> eyre::Result<()> {
+        let r1cs_path = request.require_r1cs("Lambdaworks")?;
+        ensure_json_r1cs(r1cs_path, request.r1cs_format)?;
+
+        self.keys = request.pkey_path.as_deref().map(load_keys).transpose()?;
+        self.r1cs_path = r1cs_path.clone();
+        self.r1cs_format = request.r1cs_format;
+        Ok(())
+    }
+
+    fn prove(&mut self, request: &ProveRequest) -> eyre::Result<SnarkjsOutput> {
+        if self.r1cs_path.as_os_str().is_empty() {
+            return Err(eyre!("Lambdaworks prover is not loaded"));
+        }
+
+        let (qap, wtns, pubs) = load_circuit(
+            &self.r1cs_path,
+            self.r1cs_format,
+            &request.wtns_path,
+            request.wtns_format,
+        )?;
+        let Some((proving_key, verifying_key)) = &self.keys else {
+            return Ok(prove_with_random_setup(&qap, &wtns, &pubs).0);
+        };
+        if wtns.len() != proving_key.l_tau_g1.len() {
+            return Err(eyre!("proving key does not match the circuit"));
+        }
+
+        Ok(prove_with_keys(
+            &qap,
+            &wtns,
+            &pubs,
+            proving_key,
+            verifying_key,
+        ))
+    }
+
+    fn verify(
+        &self,
+        vkey: &SnarkjsGroth16VerificationKey,
+        output: &SnarkjsOutput,
+    ) -> eyre::Result<bool> {
+        verify(vkey, &output.proof, &output.public_signals)
+    }
+}
+
+/// Reads the keys written by [`setup`].
+#[tracing::instrument(
+    name = "load_proving_key",
+    skip_all,
+    fields(backend = "lambdaworks", path = %pkey_path.display()),
+)]
+fn load_keys(
+    pkey_path: &Path,
+) -> eyre::Result<(
+    lambdaworks_groth16::ProvingKey,
+    lambdaworks_groth16::VerifyingKey,
+)> {
+    std::fs::read(pkey_path)
+        .map_err(eyre::Report::from)
+        .and_then(|bytes| keys::read_proving_key(&bytes))
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))
+}
+
+/// Loads an R1CS (in JSON format) and a witness, and converts them to the QAP, witness & public inputs of Lambdaworks.
+///
+/// The formats of the files are detected from their contents, unless they are given.
+fn load_circuit(
+    r1cs_path: impl AsRef<Path>,
+    r1cs_format: Option<FileFormat>,
+    wtns_path: impl AsRef<Path>,
+    wtns_format: Option<FileFormat>,
+) -> eyre::Result<(QuadraticArithmeticProgram, Vec<FrElement>, Vec<FrElement>)> {
+    let wtns_path = wtns_path.as_ref();
+    let r1cs_path = r1cs_path.as_ref();
+    ensure_json_r1cs(r1cs_path, r1cs_format)?;
+    let r1cs =
+        tracing::info_span!("load_r1cs", backend = "lambdaworks", path = %r1cs_path.display())
+            .in_scope(|| read_circom_r1cs(r1cs_path))
+            .map_err(|e| eyre!("could not load R1CS {}: {:?}", r1cs_path.display(), e))?;
+
+    let _span =
+        tracing::info_span!("load_witness", backend = "lambdaworks", path = %wtns_path.display())
+            .entered();
+    let wtns = match FileFormat::resolve(wtns_path, wtns_format)? {
+        FileFormat::Json => read_json_circom_witness(wtns_path),
+        FileFormat::Wtns => read_raw_circom_witness(wtns_path),
+        format => {
+            return Err(eyre!(
+                "witness {} is in {} format, expected a binary or JSON witness",
                 wtns_path.display(),
-                e
-            )
-        })?
-    } else {
-        read_raw_circom_witness(wtns_path).wrap_err("could not load witness")?
-    };
+                format
+            ))
+        }
+    }
+    .map_err(|e| eyre!("could not load witness {}: {}", wtns_path.display(), e))?;
+
+    Ok(circom_to_lambda(r1cs, wtns))
+}
+
+/// Ensures that the R1CS is in JSON format, i.e. as exported by `snarkjs r1cs export json`,
+/// which is detected from its contents unless a format is given.
+fn ensure_json_r1cs(r1cs_path: &Path, r1cs_format: Option<FileFormat>) -> eyre::Result<()> {
+    match FileFormat::resolve(r1cs_path, r1cs_format)? {
+        FileFormat::Json => Ok(()),
+        format => Err(eyre!(
+            "R1CS {} is in {} format, Lambdaworks requires a JSON R1CS",
+            r1cs_path.display(),
+            format
+        )),
+    }
+}
 
-    let (qap, wtns, pubs) = circom_to_lambda(r1cs, wtns);
+/// Proves with a random setup, and returns the verifying key of that setup along with the proof.
+fn prove_with_random_setup(
+    qap: &QuadraticArithmeticProgram,
+    wtns: &[FrElement],
+    pubs: &[FrElement],
+) -> (SnarkjsOutput, SnarkjsGroth16VerificationKey) {
+    let (proving_key, verifying_key) = tracing::info_span!("setup", backend = "lambdaworks")
+        .in_scope(|| lambdaworks_groth16::setup(qap));
+    let out = prove_with_keys(qap, wtns, pubs, &proving_key, &verifying_key);
 
-    let (proving_key, verifying_key) = lambdaworks_groth16::setup(&qap);
-    let proof = lambdaworks_groth16::Prover::prove(&wtns, &qap, &proving_key);
+    (out, snarkjs::snarkjs_vkey(&proving_key, &verifying_key))
+}
+
+/// Proves with the given keys, and returns the proof & public signals in SnarkJS format.
+#[tracing::instrument(name = "prove_circuit", skip_all, fields(backend = "lambdaworks"))]
+fn prove_with_keys(
+    qap: &QuadraticArithmeticProgram,
+    wtns: &[FrElement],
+    pubs: &[FrElement],
+    proving_key: &lambdaworks_groth16::ProvingKey,
+    verifying_key: &lambdaworks_groth16::VerifyingKey,
+) -> SnarkjsOutput {
+    let proof = lambdaworks_groth16::Prover::prove(wtns, qap, proving_key);
 
     debug_assert!(
-        lambdaworks_groth16::verify(&verifying_key, &proof, &pubs),
+        lambdaworks_groth16::verify(verifying_key, &proof, pubs),
         "proof is not accepted"
     );
 
-    let snarkjs_proof = SnarkjsGroth16Proof::from(&proof);
-    let snarkjs_public_inputs = SnarkjsPublicInputs::from_lambdaworks(pubs);
-    // TODO: export verifying key from Lambda as well for this to work with snarkjs
+    // the public inputs of Lambdaworks include the constant wire, which SnarkJS omits
+    let pubs = match pubs.split_first() {
+        Some((_, rest)) if pubs.len() == verifying_key.verifier_k_tau_g1.len() => rest,
+        _ => pubs,
+    };
 
-    Ok(SnarkjsOutput {
-        proof: snarkjs_proof,
-        public_signals: snarkjs_public_inputs,
-    })
+    SnarkjsOutput {
+        proof: SnarkjsGroth16Proof::from(&proof),
+        public_signals: SnarkjsPublicInputs::from_lambdaworks(pubs),
+        calldata: None,
+    }
+}
+
+/// Verifies a Lambdaworks proof (over BLS12-381) against a verification key exported by
+/// [`prove_with_witness_and_vkey`], with the public signals excluding the constant wire.
+pub fn verify(
+    vkey: &SnarkjsGroth16VerificationKey,
+    proof: &SnarkjsGroth16Proof,
+    public_signals: &SnarkjsPublicInputs,
+) -> eyre::Result<bool> {
+    let vk = lambdaworks_groth16::VerifyingKey::try_from(vkey)?;
+    let proof = lambdaworks_groth16::Proof::try_from(proof)?;
+    if public_signals.0.len() != vkey.n_public {
+        return Err(eyre!(
+            "expected {} public signals, got {}",
+            vkey.n_public,
+            public_signals.0.len()
+        ));
+    }
+
+    let pubs = std::iter::once(FrElement::one())
+        .chain(public_signals.to_lambdaworks()?)
+        .collect::<Vec<_>>();
+
+    Ok(lambdaworks_groth16::verify(&vk, &proof, &pubs))
+}
+
+/// Like `read_circom_witness`, but accepts the values of [`parse_witness_json`] instead of decimal strings only,
+/// and fails instead of panicking on an invalid value.
+fn read_json_circom_witness(wtns_path: impl AsRef<Path>) -> Result<Vec<FrElement>, std::io::Error> {
+    let json = std::fs::read_to_string(wtns_path)?;
+    // the elements are over the scalar field of BLS12-381, see `SnarkjsPublicInputs::to_lambdaworks`
+    parse_witness_json(&json, &CircomPrime::Bls12381.modulus())?
+        .iter()
+        .map(|value| {
+            FrElement::from_hex(&value.to_str_radix(16)).map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", e))
+            })
+        })
+        .collect()
 }
 
 /// Like `read_raw_circom_witness`, but actually reads raw witness file instead of JSON.
-#[inline]
+///
+/// The witness must be over the scalar field of BLS12-381, i.e. of a circuit compiled with `--prime bls12381`.
 fn read_raw_circom_witness(wtns_path: impl AsRef<Path>) -> Result<Vec<FrElement>, std::io::Error> {
     let wtns_data = std::fs::read(wtns_path)?;
-    parse_witness_to_elems(&wtns_data, |bytes| FrElement::from_bytes_le(bytes).unwrap())
+    parse_witness_to_elems(&wtns_data, CircomPrime::Bls12381, FrElement::from_bytes_le)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, elem)| {
+            elem.map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Invalid witness value at index {}: {:?}", i, e),
+                )
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -68,30 +344,149 @@ mod tests {
     use super::*;
 
     const CIRCUIT: &str = "multiplier_30";
+    /// Same circuit, compiled with `circom --prime bls12381` as Lambdaworks proves over BLS12-381.
+    const CIRCUIT_BLS12_381: &str = "multiplier_30_bls12381";
 
     #[test]
     fn test_lambdaworks_witness_reader() -> eyre::Result<()> {
-        let dir = Path::new("example/build").join(CIRCUIT);
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
         let wtns_path = dir
             .join("default") // input name
             .join("witness")
             .with_extension("wtns");
-        let wtns = read_raw_circom_witness(wtns_path).unwrap();
+        let wtns = read_raw_circom_witness(&wtns_path).unwrap();
         assert_eq!(wtns[0], FrElement::from(1)); // constant
 
+        let wtns = read_json_circom_witness(wtns_path.with_extension("wtns.json"))?;
+        assert_eq!(wtns[0], FrElement::from(1));
+
+        // a witness over BN254 is rejected, instead of being read into the wrong field
+        let bn128_wtns_path = Path::new("example/build")
+            .join(CIRCUIT)
+            .join("default")
+            .join("witness")
+            .with_extension("wtns");
+        let err = read_raw_circom_witness(bn128_wtns_path).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Witness is over prime bn128, expected bls12381"),
+            "{}",
+            err
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lambdaworks_with_witness() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
+        let r1cs_path = dir.join(CIRCUIT_BLS12_381).with_extension("r1cs.json");
+        let wtns_path = dir
+            .join("default") // input name
+            .join("witness")
+            .with_extension("wtns");
+
+        let out = prove_with_witness(r1cs_path, wtns_path)?;
+        assert_eq!(out.proof.curve, "bls12381");
+        assert_eq!(out.public_signals.0.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lambdaworks_format_detection() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
+        let r1cs_path = dir.join(CIRCUIT_BLS12_381).with_extension("r1cs.json");
+        let wtns_path = dir.join("default").join("witness").with_extension("wtns");
+
+        // the JSON witness is read as JSON, which it was not when detected by its path
+        let (_, wtns, _) = load_circuit(
+            &r1cs_path,
+            None,
+            wtns_path.with_extension("wtns.json"),
+            None,
+        )?;
+        assert_eq!(wtns[0], FrElement::from(1));
+
+        // a binary R1CS is rejected by its contents, and so is a witness forced into the wrong format
+        let err = load_circuit(
+            dir.join(CIRCUIT_BLS12_381).with_extension("r1cs"),
+            None,
+            &wtns_path,
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("requires a JSON R1CS"), "{}", err);
+        assert!(load_circuit(&r1cs_path, None, &wtns_path, Some(FileFormat::Json)).is_err());
+
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_lambdaworks_with_witness() -> eyre::Result<()> {
-        let dir = Path::new("example/build").join(CIRCUIT);
-        let r1cs_path = dir.join(CIRCUIT).with_extension("r1cs.json");
+    #[test]
+    fn test_lambdaworks_verify() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
+        let r1cs_path = dir.join(CIRCUIT_BLS12_381).with_extension("r1cs.json");
         let wtns_path = dir
             .join("default") // input name
             .join("witness")
             .with_extension("wtns");
 
-        let _ = prove_with_witness(r1cs_path, wtns_path);
+        let (out, vkey) = prove_with_witness_and_vkey(r1cs_path, wtns_path)?;
+
+        // verifying key should survive a JSON round-trip
+        let vkey: SnarkjsGroth16VerificationKey = serde_json::from_str(&vkey.to_string())?;
+        assert!(verify(&vkey, &out.proof, &out.public_signals)?);
+
+        // should reject tampered public signals
+        let mut public_signals = out.public_signals.clone();
+        public_signals.0[0] = "42".to_string();
+        assert!(!verify(&vkey, &out.proof, &public_signals)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_lambdaworks_setup() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT_BLS12_381);
+        let r1cs_path = dir.join(CIRCUIT_BLS12_381).with_extension("r1cs.json");
+        let wtns_path = dir
+            .join("default") // input name
+            .join("witness")
+            .with_extension("wtns");
+        let keys_dir = std::env::temp_dir()
+            .join("circomkit-ffi-lambdaworks-setup")
+            .join(CIRCUIT_BLS12_381);
+
+        let vkey = setup(&r1cs_path, &keys_dir)?;
+        let pkey_path = keys_dir.join(PKEY_FILENAME);
+
+        // the verifying key should be the same when read from either file
+        let vkey_json: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(keys_dir.join(VKEY_FILENAME))?;
+        assert_eq!(vkey_json.to_string(), vkey.to_string());
+        let vk = keys::read_verifying_key(&std::fs::read(&pkey_path)?)?;
+        assert_eq!(vk.verifier_k_tau_g1.len(), vkey.n_public + 1);
+
+        // proofs from the same keys should share the verifier
+        for _ in 0..2 {
+            let out = prove_with_existing_keys(&r1cs_path, &wtns_path, &pkey_path)?;
+            assert!(verify(&vkey, &out.proof, &out.public_signals)?);
+        }
+
+        // `[α]₁` starts the points section, right after the header section
+        let bytes = std::fs::read(&pkey_path)?;
+        let alpha_x = 12 + (12 + 4) + 12;
+
+        // should reject a coordinate that is not reduced
+        let mut unreduced = bytes.clone();
+        unreduced[alpha_x..alpha_x + 48].fill(0xff);
+        assert!(keys::read_proving_key(&unreduced).is_err());
+
+        // should reject a point that is not on the curve
+        let mut off_curve = bytes.clone();
+        off_curve[alpha_x] ^= 1;
+        assert!(keys::read_proving_key(&off_curve).is_err());
+
         Ok(())
     }
 }
@@ -1,30 +1,32 @@
+use eyre::{eyre, Result};
+use lambdaworks_groth16::common::{FrElement, G1Point, G2Point, Pairing};
+use lambdaworks_math::cyclic_group::IsGroup;
+use lambdaworks_math::elliptic_curve::short_weierstrass::curves::bls12_381::field_extension::{
+    BLS12381PrimeField, Degree2ExtensionField,
+};
+use lambdaworks_math::elliptic_curve::traits::{FromAffine, IsPairing};
 use lambdaworks_math::field::{element::FieldElement, traits::IsPrimeField};
+use num_bigint::BigUint;
 
-use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsPublicInputs};
+use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsGroth16VerificationKey, SnarkjsPublicInputs};
 
 type LambdaworksProof = lambdaworks_groth16::Proof;
+type LambdaworksVerifyingKey = lambdaworks_groth16::VerifyingKey;
+type Fq2 = FieldElement<Degree2ExtensionField>;
 
 impl From<&LambdaworksProof> for SnarkjsGroth16Proof {
     fn from(proof: &lambdaworks_groth16::Proof) -> Self {
+        // the points are in projective coordinates, so they must be normalized first
+        let [pi_a, pi_c] = [&proof.pi1, &proof.pi3].map(|p| {
+            let p = p.to_affine();
+            [to_decimal(p.x()), to_decimal(p.y())]
+        });
+        let pi_b = proof.pi2.to_affine();
+
         Self {
-            pi_a: [
-                proof.pi1.x().representative().to_string(),
-                proof.pi1.y().representative().to_string(),
-            ],
-            pi_b: [
-                [
-                    proof.pi2.x().value()[0].representative().to_string(),
-                    proof.pi2.x().value()[1].representative().to_string(),
-                ],
-                [
-                    proof.pi2.y().value()[0].representative().to_string(),
-                    proof.pi2.y().value()[1].representative().to_string(),
-                ],
-            ],
-            pi_c: [
-                proof.pi3.x().representative().to_string(),
-                proof.pi3.y().representative().to_string(),
-            ],
+            pi_a,
+            pi_b: [fq2_to_strings(pi_b.x()), fq2_to_strings(pi_b.y())],
+            pi_c,
             protocol: "groth16".to_string(),
             curve: "bls12381".to_string(),
         }
@@ -37,16 +39,202 @@ impl From<LambdaworksProof> for SnarkjsGroth16Proof {
     }
 }
 
+impl TryFrom<&SnarkjsGroth16Proof> for LambdaworksProof {
+    type Error = eyre::Report;
+
+    fn try_from(proof: &SnarkjsGroth16Proof) -> Result<Self> {
+        if proof.curve != "bls12381" {
+            return Err(eyre!(
+                "expected a proof over BLS12-381, got {}",
+                proof.curve
+            ));
+        }
+
+        Ok(Self {
+            pi1: parse_g1(&proof.pi_a)?,
+            pi2: parse_g2(&proof.pi_b)?,
+            pi3: parse_g1(&proof.pi_c)?,
+        })
+    }
+}
+
+/// Exports the verifying key of a Lambdaworks setup, along with `[α]₁` & `[β]₂` from its proving key
+/// as the verifying key of Lambdaworks only keeps their pairing.
+pub(super) fn snarkjs_vkey(
+    pk: &lambdaworks_groth16::ProvingKey,
+    vk: &LambdaworksVerifyingKey,
+) -> SnarkjsGroth16VerificationKey {
+    let alphabeta = vk.alpha_g1_times_beta_g2.value();
+
+    SnarkjsGroth16VerificationKey {
+        protocol: "groth16".to_string(),
+        curve: "bls12381".to_string(),
+        n_public: vk.verifier_k_tau_g1.len() - 1,
+        vk_alpha_1: g1_to_projective_strings(&pk.alpha_g1),
+        vk_beta_2: g2_to_projective_strings(&pk.beta_g2),
+        vk_gamma_2: g2_to_projective_strings(&vk.gamma_g2),
+        vk_delta_2: g2_to_projective_strings(&vk.delta_g2),
+        vk_alphabeta_12: [0, 1].map(|i| alphabeta[i].value().clone().map(|c| fq2_to_strings(&c))),
+        ic: vk
+            .verifier_k_tau_g1
+            .iter()
+            .map(g1_to_projective_strings)
+            .collect(),
+    }
+}
+
+impl TryFrom<&SnarkjsGroth16VerificationKey> for LambdaworksVerifyingKey {
+    type Error = eyre::Report;
+
+    fn try_from(vk: &SnarkjsGroth16VerificationKey) -> Result<Self> {
+        if vk.curve != "bls12381" {
+            return Err(eyre!(
+                "expected a verification key over BLS12-381, got {}",
+                vk.curve
+            ));
+        }
+        if vk.ic.len() != vk.n_public + 1 {
+            return Err(eyre!(
+                "expected {} IC points for {} public signals, got {}",
+                vk.n_public + 1,
+                vk.n_public,
+                vk.ic.len()
+            ));
+        }
+
+        // the pairing is recomputed instead of trusting `vk_alphabeta_12`
+        let alpha_g1 = parse_g1(&vk.vk_alpha_1)?;
+        let beta_g2 = parse_g2(&vk.vk_beta_2)?;
+        let alpha_g1_times_beta_g2 = Pairing::compute(&alpha_g1, &beta_g2)
+            .map_err(|e| eyre!("could not compute the pairing of alpha & beta: {:?}", e))?;
+
+        Ok(Self {
+            alpha_g1_times_beta_g2,
+            delta_g2: parse_g2(&vk.vk_delta_2)?,
+            gamma_g2: parse_g2(&vk.vk_gamma_2)?,
+            verifier_k_tau_g1: vk
+                .ic
+                .iter()
+                .map(|p| parse_g1(p))
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
 impl SnarkjsPublicInputs {
     pub fn from_lambdaworks<F: IsPrimeField>(
         public_signals: impl AsRef<[FieldElement<F>]>,
     ) -> Self {
-        Self(
-            public_signals
-                .as_ref()
-                .iter()
-                .map(|s| s.representative().to_string())
-                .collect(),
-        )
+        Self(public_signals.as_ref().iter().map(to_decimal).collect())
+    }
+
+    /// Parses the public signals into scalars of BLS12-381, making sure that they are smaller than the modulus.
+    pub fn to_lambdaworks(&self) -> Result<Vec<FrElement>> {
+        self.0.iter().map(|s| parse_field(s)).collect()
     }
 }
+
+/// Returns the decimal string of a field element, as its representative is displayed in hexadecimal.
+fn to_decimal<F: IsPrimeField>(e: &FieldElement<F>) -> String {
+    let hex = e.representative().to_string();
+    BigUint::parse_bytes(hex.trim_start_matches("0x").as_bytes(), 16)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Parses a decimal string into a field element, making sure that it is smaller than the modulus.
+fn parse_field<F: IsPrimeField>(s: &str) -> Result<FieldElement<F>> {
+    let value = s
+        .parse::<BigUint>()
+        .map_err(|e| eyre!("could not parse {} as a decimal: {}", s, e))?;
+    let e = FieldElement::<F>::from_hex(&value.to_str_radix(16))
+        .map_err(|e| eyre!("could not parse {} as a field element: {:?}", s, e))?;
+
+    // the element is reduced when parsed, so a larger value would not round-trip
+    if to_decimal(&e) != value.to_string() {
+        return Err(eyre!("{} is not smaller than the field modulus", s));
+    }
+
+    Ok(e)
+}
+
+fn parse_fq2([c0, c1]: &[String; 2]) -> Result<Fq2> {
+    Ok(Fq2::new([parse_field(c0)?, parse_field(c1)?]))
+}
+
+fn fq2_to_strings(c: &Fq2) -> [String; 2] {
+    [to_decimal(&c.value()[0]), to_decimal(&c.value()[1])]
+}
+
+/// Parses a G1 point given in affine (`[x, y]`) or projective (`[x, y, z]`) coordinates.
+///
+/// Projective coordinates are expected to be normalized, i.e. `z` must be `1`, or `0` for the identity.
+fn parse_g1(coords: &[String]) -> Result<G1Point> {
+    let (x, y) = match coords {
+        [x, y] => (x, y),
+        [_, _, z] if z == "0" => return Ok(G1Point::neutral_element()),
+        [x, y, z] if z == "1" => (x, y),
+        [_, _, z] => return Err(eyre!("expected z coordinate to be 1, got {}", z)),
+        _ => {
+            return Err(eyre!(
+                "expected 2 or 3 coordinates for G1, got {}",
+                coords.len()
+            ))
+        }
+    };
+
+    G1Point::from_affine(parse_field::<BLS12381PrimeField>(x)?, parse_field(y)?)
+        .map_err(|e| eyre!("point is not in G1: {:?}", e))
+}
+
+/// Parses a G2 point given in affine (`[x, y]`) or projective (`[x, y, z]`) coordinates,
+/// where each coordinate is an `[c0, c1]` pair.
+///
+/// Projective coordinates are expected to be normalized, i.e. `z` must be `[1, 0]`, or `[0, 0]` for the identity.
+fn parse_g2(coords: &[[String; 2]]) -> Result<G2Point> {
+    let (x, y) = match coords {
+        [x, y] => (x, y),
+        [x, y, z] => {
+            let z = parse_fq2(z)?;
+            if z == Fq2::zero() {
+                return Ok(G2Point::neutral_element());
+            } else if z != Fq2::one() {
+                return Err(eyre!("expected z coordinate to be [1, 0]"));
+            }
+            (x, y)
+        }
+        _ => {
+            return Err(eyre!(
+                "expected 2 or 3 coordinates for G2, got {}",
+                coords.len()
+            ))
+        }
+    };
+
+    G2Point::from_affine(parse_fq2(x)?, parse_fq2(y)?)
+        .map_err(|e| eyre!("point is not in G2: {:?}", e))
+}
+
+/// Returns the projective coordinates of a G1 point, as SnarkJS exports them.
+fn g1_to_projective_strings(p: &G1Point) -> [String; 3] {
+    if p.is_neutral_element() {
+        return ["0", "1", "0"].map(String::from);
+    }
+
+    let p = p.to_affine();
+    [to_decimal(p.x()), to_decimal(p.y()), "1".to_string()]
+}
+
+/// Returns the projective coordinates of a G2 point, as SnarkJS exports them.
+fn g2_to_projective_strings(p: &G2Point) -> [[String; 2]; 3] {
+    if p.is_neutral_element() {
+        return [["0", "0"], ["1", "0"], ["0", "0"]].map(|c| c.map(String::from));
+    }
+
+    let p = p.to_affine();
+    [
+        fq2_to_strings(p.x()),
+        fq2_to_strings(p.y()),
+        ["1".to_string(), "0".to_string()],
+    ]
+}
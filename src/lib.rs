@@ -13,8 +13,53 @@ pub mod icicle;
 /// [Lambdaworks](https://github.com/lambdaclass/lambdaworks)
 pub mod lambdaworks;
 
+/// PLONK prover for SnarkJS PLONK keys.
+pub mod plonk;
+
+/// FFLONK prover for SnarkJS FFLONK keys.
+pub mod fflonk;
+
+/// Solidity verifier contracts & calldata.
+pub mod solidity;
+
+/// [gnark](https://github.com/Consensys/gnark) compatibility layer.
+pub mod gnark;
+
+/// Primes supported by Circom.
+pub mod prime;
+
+/// Detection of the formats of the input files.
+pub mod format;
+
+/// Common interface & registry of the Groth16 prover backends.
+pub mod prover;
+
+/// Thread & memory limits of the provers.
+pub mod config;
+
+/// Per-stage timings of the prover backends.
+pub mod bench;
+
+/// Log level & callback for the tracing spans of the provers.
+pub mod logging;
+
+mod binfile;
+
 mod witness;
 
+/// Attaches Solidity calldata to the prover output, if requested.
+#[inline]
+fn attach_calldata(
+    out: snarkjs::SnarkjsOutput,
+    calldata: bool,
+) -> eyre::Result<snarkjs::SnarkjsOutput> {
+    if calldata {
+        out.with_calldata()
+    } else {
+        Ok(out)
+    }
+}
+
 /// Given a string input, returns the same.
 /// Should be used for testing purposes of the FFI logic.
 #[no_mangle]
@@ -30,12 +75,16 @@ pub extern "C" fn echo(input: *const c_char) -> CString {
 }
 
 /// Generate an Arkworks proof from a given witness, R1CS and prover key path.
+///
+/// The curve (BN254 or BLS12-381) is detected from the prime in the R1CS header.
+/// If `calldata` is set, the output includes Solidity calldata for the proof as well, which is only supported for BN254.
 #[no_mangle]
 #[allow(improper_ctypes_definitions)]
 pub extern "C" fn arkworks_prove(
     wtns_path_ptr: *const c_char,
     r1cs_path_ptr: *const c_char,
     pkey_path_ptr: *const c_char,
+    calldata: bool,
 ) -> CString {
     let [wtns_path, r1cs_path, pkey_path] =
         [wtns_path_ptr, r1cs_path_ptr, pkey_path_ptr].map(|ptr| {
@@ -47,12 +96,41 @@ pub extern "C" fn arkworks_prove(
             .unwrap()
         });
 
-    // due to internals of Arkworks we need `tokio` runtime even if nothing is async within the thread
-    match tokio::runtime::Builder::new_current_thread()
-        .enable_io()
-        .build()
+    match arkworks::prove_with_existing_witness(r1cs_path, wtns_path, pkey_path)
+        .and_then(|out| attach_calldata(out, calldata))
+    {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Generate an Arkworks proof from a given witness and prover key path, without the R1CS.
+///
+/// The constraints are read from the prover key (`.zkey`) instead, whose header also gives the curve (BN254 or BLS12-381).
+/// If `calldata` is set, the output includes Solidity calldata for the proof as well, which is only supported for BN254.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn arkworks_prove_zkey(
+    wtns_path_ptr: *const c_char,
+    pkey_path_ptr: *const c_char,
+    calldata: bool,
+) -> CString {
+    let [wtns_path, pkey_path] = [wtns_path_ptr, pkey_path_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
         .unwrap()
-        .block_on(async { arkworks::prove_with_existing_witness(r1cs_path, wtns_path, pkey_path) })
+    });
+
+    match arkworks::prove_with_zkey(wtns_path, pkey_path)
+        .and_then(|out| attach_calldata(out, calldata))
     {
         Ok(snarkjs_out) => {
             let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
@@ -64,16 +142,16 @@ pub extern "C" fn arkworks_prove(
     }
 }
 
-/// Generate a Lambdaworks proof from a given witness, R1CS.
+/// Generate a PLONK proof from a given witness and SnarkJS PLONK prover key path (BN254 only).
 ///
-/// It creates its own prover key within.
+/// The output is a SnarkJS PLONK proof & public signals, which can be verified with `snarkjs plonk verify`.
 #[no_mangle]
 #[allow(improper_ctypes_definitions)]
-pub extern "C" fn lambdaworks_prove(
+pub extern "C" fn plonk_prove(
     wtns_path_ptr: *const c_char,
-    r1cs_path_ptr: *const c_char,
+    pkey_path_ptr: *const c_char,
 ) -> CString {
-    let [wtns_path, r1cs_path] = [wtns_path_ptr, r1cs_path_ptr].map(|ptr| {
+    let [wtns_path, pkey_path] = [wtns_path_ptr, pkey_path_ptr].map(|ptr| {
         unsafe {
             assert!(!ptr.is_null());
             CStr::from_ptr(ptr)
@@ -82,7 +160,7 @@ pub extern "C" fn lambdaworks_prove(
         .unwrap()
     });
 
-    match lambdaworks::prove_with_witness(r1cs_path, wtns_path) {
+    match plonk::prove_with_existing_witness(wtns_path, pkey_path) {
         Ok(snarkjs_out) => {
             let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
             CString::new(output).unwrap()
@@ -93,18 +171,16 @@ pub extern "C" fn lambdaworks_prove(
     }
 }
 
-/// Generate an ICICLE proof from a given witness, R1CS, and a device type.
+/// Generate a FFLONK proof from a given witness and SnarkJS FFLONK prover key path (BN254 only).
 ///
-/// The device type can be one of: `CPU`, `CUDA`, `METAL`.
-#[cfg(feature = "icicle")]
+/// The output is a SnarkJS FFLONK proof & public signals, which can be verified with `snarkjs fflonk verify`.
 #[no_mangle]
 #[allow(improper_ctypes_definitions)]
-pub extern "C" fn icicle_prove(
+pub extern "C" fn fflonk_prove(
     wtns_path_ptr: *const c_char,
     pkey_path_ptr: *const c_char,
-    device_ptr: *const c_char,
 ) -> CString {
-    let [wtns_path, pkey_path, device] = [wtns_path_ptr, pkey_path_ptr, device_ptr].map(|ptr| {
+    let [wtns_path, pkey_path] = [wtns_path_ptr, pkey_path_ptr].map(|ptr| {
         unsafe {
             assert!(!ptr.is_null());
             CStr::from_ptr(ptr)
@@ -113,7 +189,7 @@ pub extern "C" fn icicle_prove(
         .unwrap()
     });
 
-    match icicle::prove_with_existing_witness(wtns_path, pkey_path, device) {
+    match fflonk::prove_with_existing_witness(wtns_path, pkey_path) {
         Ok(snarkjs_out) => {
             let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
             CString::new(output).unwrap()
@@ -123,3 +199,673 @@ pub extern "C" fn icicle_prove(
         }
     }
 }
+
+/// Generate a Groth16 proof with the backend of the given name, e.g. `arkworks`, `lambdaworks` or `icicle`.
+///
+/// The request is a JSON object with the witness path, and the R1CS & prover key paths as the backend requires,
+/// see [`prover::ProveRequest`]. The output includes Solidity calldata for the proof if the request asks for it.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn prove(backend_ptr: *const c_char, request_ptr: *const c_char) -> CString {
+    let [backend, request] = [backend_ptr, request_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    match serde_json::from_str::<prover::ProveRequest>(request)
+        .map_err(|e| eyre::eyre!("could not parse request: {}", e))
+        .and_then(|request| prover::ProverRegistry::default().prove(backend, &request))
+    {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Returns the capabilities of each backend that [`prove`] accepts, as a JSON object keyed by the backend name.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn prover_backends() -> CString {
+    let backends = prover::ProverRegistry::default().capabilities();
+    CString::new(serde_json::to_string_pretty(&backends).unwrap()).unwrap()
+}
+
+/// Benchmarks a backend by proving a witness for a number of iterations, and returns the mean, min & max
+/// time of each stage (parsing, witness map, MSMs & serialization) as JSON, see [`bench::BenchmarkReport`].
+///
+/// The R1CS & prover key paths can be empty for backends that don't require them.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn benchmark(
+    backend_ptr: *const c_char,
+    r1cs_path_ptr: *const c_char,
+    wtns_path_ptr: *const c_char,
+    pkey_path_ptr: *const c_char,
+    iterations: u32,
+) -> CString {
+    let [backend, r1cs_path, wtns_path, pkey_path] =
+        [backend_ptr, r1cs_path_ptr, wtns_path_ptr, pkey_path_ptr].map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    let optional_path = |path: &str| (!path.is_empty()).then(|| path.into());
+    let request = prover::ProveRequest {
+        wtns_path: wtns_path.into(),
+        r1cs_path: optional_path(r1cs_path),
+        pkey_path: optional_path(pkey_path),
+        ..Default::default()
+    };
+    match bench::benchmark(backend, &request, iterations as usize) {
+        Ok(report) => CString::new(serde_json::to_string_pretty(&report).unwrap()).unwrap(),
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Sets the thread & memory limits of the provers from a JSON object, see [`config::ProverConfig`],
+/// and returns the configuration in effect. An empty string leaves the configuration as is.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn prover_config(config_ptr: *const c_char) -> CString {
+    let config_json = unsafe {
+        assert!(!config_ptr.is_null());
+        CStr::from_ptr(config_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    let result = if config_json.is_empty() {
+        Ok(config::config())
+    } else {
+        serde_json::from_str::<config::ProverConfig>(config_json)
+            .map_err(|e| eyre::eyre!("could not parse config: {}", e))
+            .and_then(|config| config::set_config(config.clone()).map(|_| config))
+    };
+
+    match result {
+        Ok(config) => CString::new(serde_json::to_string_pretty(&config).unwrap()).unwrap(),
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Sets the maximum level of the logs, one of `off`, `error`, `warn`, `info`, `debug` or `trace`.
+///
+/// Logs are off by default. Returns `false` if the level is invalid, or if the logger could not be installed.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn set_log_level(level_ptr: *const c_char) -> bool {
+    let level = unsafe {
+        assert!(!level_ptr.is_null());
+        CStr::from_ptr(level_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    match level.parse() {
+        Ok(level) => logging::set_log_level(level).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Registers a function that receives each log line, instead of printing it to `stderr`.
+///
+/// Passing a null function unregisters the current one.
+#[no_mangle]
+pub extern "C" fn set_log_callback(callback: Option<logging::LogCallback>) {
+    logging::set_log_callback(callback);
+}
+
+/// Returns the statistics of a given R1CS file, such as the number of wires & constraints.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn r1cs_info(r1cs_path_ptr: *const c_char) -> CString {
+    let r1cs_path = unsafe {
+        assert!(!r1cs_path_ptr.is_null());
+        CStr::from_ptr(r1cs_path_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    match arkworks::r1cs_info(r1cs_path) {
+        Ok(info) => {
+            let output = serde_json::to_string_pretty(&info).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Renders a Solidity verifier contract from a given SnarkJS Groth16 verification key (BN254 only).
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn solidity_verifier(vkey_path_ptr: *const c_char) -> CString {
+    let vkey_path = unsafe {
+        assert!(!vkey_path_ptr.is_null());
+        CStr::from_ptr(vkey_path_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    match snarkjs::read_snarkjs_json(vkey_path).and_then(|vkey| solidity::groth16_verifier(&vkey)) {
+        Ok(contract) => CString::new(contract).unwrap(),
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Returns the Solidity calldata for a given SnarkJS Groth16 proof & public signals (BN254 only).
+///
+/// The output is a JSON object with the calldata as SnarkJS prints it (`text`),
+/// and the ABI-encoded call to `verifyProof` (`abi`).
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn solidity_calldata(
+    proof_path_ptr: *const c_char,
+    public_path_ptr: *const c_char,
+) -> CString {
+    let [proof_path, public_path] = [proof_path_ptr, public_path_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    match snarkjs::read_snarkjs_json(proof_path).and_then(|proof| {
+        let public_signals = snarkjs::read_snarkjs_json(public_path)?;
+        solidity::SolidityCalldata::new(&proof, &public_signals)
+    }) {
+        Ok(calldata) => {
+            let output = serde_json::to_string_pretty(&calldata).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Encodes a given SnarkJS Groth16 proof (BN254 only) into binary, either compressed (128 bytes) or
+/// uncompressed (256 bytes), and returns it as a string w.r.t the given encoding, one of: `hex`, `base64`.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn encode_proof(
+    proof_path_ptr: *const c_char,
+    compressed: bool,
+    encoding_ptr: *const c_char,
+) -> CString {
+    let [proof_path, encoding] = [proof_path_ptr, encoding_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    let encoded = encoding
+        .parse::<arkworks::TextEncoding>()
+        .and_then(|encoding| {
+            let proof: snarkjs::SnarkjsGroth16Proof = snarkjs::read_snarkjs_json(proof_path)?;
+            proof.to_encoded(compressed, encoding)
+        });
+    match encoded {
+        Ok(encoded) => CString::new(encoded).unwrap(),
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Decodes a binary SnarkJS Groth16 proof (BN254 only) that is encoded with `encode_proof`,
+/// and returns the proof object.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn decode_proof(
+    data_ptr: *const c_char,
+    compressed: bool,
+    encoding_ptr: *const c_char,
+) -> CString {
+    let [data, encoding] = [data_ptr, encoding_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    let proof = encoding
+        .parse::<arkworks::TextEncoding>()
+        .and_then(|encoding| {
+            snarkjs::SnarkjsGroth16Proof::from_encoded(data, compressed, encoding)
+        });
+    match proof {
+        Ok(proof) => {
+            let output = serde_json::to_string_pretty(&proof).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Re-randomizes a given SnarkJS Groth16 proof (BN254 only) with its verification key, both given as JSON,
+/// and returns the new proof, which can not be linked to the given one but verifies against the same public signals.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn rerandomize_proof(
+    vkey_json_ptr: *const c_char,
+    proof_json_ptr: *const c_char,
+) -> CString {
+    let [vkey_json, proof_json] = [vkey_json_ptr, proof_json_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    let proof = (|| -> eyre::Result<snarkjs::SnarkjsGroth16Proof> {
+        let vkey = serde_json::from_str(vkey_json)
+            .map_err(|e| eyre::eyre!("could not parse verification key: {}", e))?;
+        let proof = serde_json::from_str(proof_json)
+            .map_err(|e| eyre::eyre!("could not parse proof: {}", e))?;
+        arkworks::rerandomize_snarkjs(&vkey, &proof)
+    })();
+    match proof {
+        Ok(proof) => {
+            let output = serde_json::to_string_pretty(&proof).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Converts a given SnarkJS Groth16 proof, verification key & public signals (BN254 only) into
+/// the binary format of gnark, either compressed or uncompressed.
+///
+/// The output is a JSON object with hex-encoded `proof`, `verifyingKey` and `publicWitness`.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn gnark_export(
+    proof_path_ptr: *const c_char,
+    vkey_path_ptr: *const c_char,
+    public_path_ptr: *const c_char,
+    compressed: bool,
+) -> CString {
+    let [proof_path, vkey_path, public_path] = [proof_path_ptr, vkey_path_ptr, public_path_ptr]
+        .map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    let export = (|| -> eyre::Result<gnark::GnarkExport> {
+        let proof = snarkjs::read_snarkjs_json(proof_path)?;
+        let vkey = snarkjs::read_snarkjs_json(vkey_path)?;
+        let public_signals = snarkjs::read_snarkjs_json(public_path)?;
+        gnark::GnarkExport::new(&proof, &vkey, &public_signals, compressed)
+    })();
+    match export {
+        Ok(export) => {
+            let output = serde_json::to_string_pretty(&export).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Generate a Lambdaworks proof from a given witness, R1CS.
+///
+/// It creates its own prover key within; if `vkey_path` is not empty, the verification key of that setup
+/// is written there (in SnarkJS format) so that the proof can be verified later with `lambdaworks_verify`.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn lambdaworks_prove(
+    wtns_path_ptr: *const c_char,
+    r1cs_path_ptr: *const c_char,
+    vkey_path_ptr: *const c_char,
+) -> CString {
+    let [wtns_path, r1cs_path, vkey_path] =
+        [wtns_path_ptr, r1cs_path_ptr, vkey_path_ptr].map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    match lambdaworks::prove_with_witness_and_vkey(r1cs_path, wtns_path).and_then(|(out, vkey)| {
+        if !vkey_path.is_empty() {
+            std::fs::write(vkey_path, vkey.to_string())?;
+        }
+        Ok(out)
+    }) {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Run a Lambdaworks setup for a given R1CS (in JSON format), writing the keys to the output directory.
+///
+/// The proving key is written to `lambdaworks_pkey.bin` (see `lambdaworks::keys` for its format),
+/// and the SnarkJS verification key to `lambdaworks_vkey.json`, which is returned as well.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn lambdaworks_setup(
+    r1cs_path_ptr: *const c_char,
+    out_dir_ptr: *const c_char,
+) -> CString {
+    let [r1cs_path, out_dir] = [r1cs_path_ptr, out_dir_ptr].map(|ptr| {
+        unsafe {
+            assert!(!ptr.is_null());
+            CStr::from_ptr(ptr)
+        }
+        .to_str()
+        .unwrap()
+    });
+
+    match lambdaworks::setup(r1cs_path, out_dir) {
+        Ok(vkey) => {
+            let output = serde_json::to_string_pretty(&vkey).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Generate a Lambdaworks proof from a given witness, R1CS and the prover key written by `lambdaworks_setup`.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn lambdaworks_prove_with_pkey(
+    wtns_path_ptr: *const c_char,
+    r1cs_path_ptr: *const c_char,
+    pkey_path_ptr: *const c_char,
+) -> CString {
+    let [wtns_path, r1cs_path, pkey_path] =
+        [wtns_path_ptr, r1cs_path_ptr, pkey_path_ptr].map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    match lambdaworks::prove_with_existing_keys(r1cs_path, wtns_path, pkey_path) {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Verify a Lambdaworks proof from given SnarkJS verification key, proof and public signals (as JSON strings),
+/// where the verification key is the one written by `lambdaworks_prove` or `lambdaworks_setup`.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn lambdaworks_verify(
+    vkey_json_ptr: *const c_char,
+    proof_json_ptr: *const c_char,
+    public_json_ptr: *const c_char,
+) -> bool {
+    let [vkey_json, proof_json, public_json] = [vkey_json_ptr, proof_json_ptr, public_json_ptr]
+        .map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    let is_valid = (|| -> eyre::Result<bool> {
+        let vkey = serde_json::from_str(vkey_json)
+            .map_err(|e| eyre::eyre!("could not parse verification key: {}", e))?;
+        let proof = serde_json::from_str(proof_json)
+            .map_err(|e| eyre::eyre!("could not parse proof: {}", e))?;
+        let public_signals = serde_json::from_str(public_json)
+            .map_err(|e| eyre::eyre!("could not parse public signals: {}", e))?;
+        lambdaworks::verify(&vkey, &proof, &public_signals)
+    })();
+    match is_valid {
+        Ok(is_valid) => is_valid,
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Generate an ICICLE proof from a given witness, prover key, and a device type.
+///
+/// The device type can be one of: `CPU`, `CUDA`, `METAL`. The backends are loaded from `backend_dir`,
+/// or from the `ICICLE_BACKEND_INSTALL_DIR` environment variable if it is empty.
+///
+/// If `calldata` is set, the output includes Solidity calldata for the proof as well.
+#[cfg(feature = "icicle")]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn icicle_prove(
+    wtns_path_ptr: *const c_char,
+    pkey_path_ptr: *const c_char,
+    device_ptr: *const c_char,
+    backend_dir_ptr: *const c_char,
+    calldata: bool,
+) -> CString {
+    let [wtns_path, pkey_path, device, backend_dir] =
+        [wtns_path_ptr, pkey_path_ptr, device_ptr, backend_dir_ptr].map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    match device
+        .parse()
+        .map(|device| {
+            let mut config = icicle::IcicleConfig::from_env(device);
+            if !backend_dir.is_empty() {
+                config.backend_dir = Some(backend_dir.into());
+            }
+            config
+        })
+        .and_then(|config| icicle::prove_with_config(wtns_path, pkey_path, &config))
+        .and_then(|out| attach_calldata(out, calldata))
+    {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Returns the devices that ICICLE could load a backend for as a JSON array, e.g. `["CPU", "CUDA"]`.
+///
+/// The backends are loaded from `backend_dir`, or from the `ICICLE_BACKEND_INSTALL_DIR` environment variable if it is empty.
+#[cfg(feature = "icicle")]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn icicle_list_devices(backend_dir_ptr: *const c_char) -> CString {
+    let backend_dir = unsafe {
+        assert!(!backend_dir_ptr.is_null());
+        CStr::from_ptr(backend_dir_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    let mut config = icicle::IcicleConfig::from_env(icicle::IcicleDevice::Cpu);
+    if !backend_dir.is_empty() {
+        config.backend_dir = Some(backend_dir.into());
+    }
+
+    match icicle::list_devices(&config) {
+        Ok(devices) => CString::new(serde_json::to_string_pretty(&devices).unwrap()).unwrap(),
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// ICICLE sessions that are open, by their id.
+///
+/// Each session has a lock of its own, so that a proof only holds the lock of its session
+/// and the map is only locked while a session is looked up, added or removed.
+#[cfg(feature = "icicle")]
+type IcicleSessions =
+    std::collections::BTreeMap<u32, std::sync::Arc<std::sync::Mutex<icicle::IcicleSession>>>;
+
+#[cfg(feature = "icicle")]
+static ICICLE_SESSIONS: std::sync::Mutex<IcicleSessions> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+/// Locks the map of open ICICLE sessions.
+///
+/// The map is never left half-updated, so it is safe to use even if a thread panicked while holding the lock.
+#[cfg(feature = "icicle")]
+fn icicle_sessions() -> std::sync::MutexGuard<'static, IcicleSessions> {
+    ICICLE_SESSIONS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Opens an ICICLE session for a prover key on the given device, and returns its id.
+///
+/// The session caches the data that ICICLE computes from the prover key, so that proofs with
+/// [`icicle_session_prove`] do not repeat that work. It must be closed with [`icicle_session_close`].
+#[cfg(feature = "icicle")]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn icicle_session_open(
+    pkey_path_ptr: *const c_char,
+    device_ptr: *const c_char,
+    backend_dir_ptr: *const c_char,
+) -> u32 {
+    static NEXT_SESSION_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(1);
+
+    let [pkey_path, device, backend_dir] =
+        [pkey_path_ptr, device_ptr, backend_dir_ptr].map(|ptr| {
+            unsafe {
+                assert!(!ptr.is_null());
+                CStr::from_ptr(ptr)
+            }
+            .to_str()
+            .unwrap()
+        });
+
+    match device.parse().and_then(|device| {
+        let mut config = icicle::IcicleConfig::from_env(device);
+        if !backend_dir.is_empty() {
+            config.backend_dir = Some(backend_dir.into());
+        }
+        icicle::IcicleSession::new(pkey_path, config)
+    }) {
+        Ok(session) => {
+            let id = NEXT_SESSION_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            icicle_sessions().insert(id, std::sync::Arc::new(std::sync::Mutex::new(session)));
+            id
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Generate an ICICLE proof from a given witness, with the prover key & device of an open session.
+///
+/// If `calldata` is set, the output includes Solidity calldata for the proof as well.
+#[cfg(feature = "icicle")]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn icicle_session_prove(
+    session_id: u32,
+    wtns_path_ptr: *const c_char,
+    calldata: bool,
+) -> CString {
+    let wtns_path = unsafe {
+        assert!(!wtns_path_ptr.is_null());
+        CStr::from_ptr(wtns_path_ptr)
+    }
+    .to_str()
+    .unwrap();
+
+    // the map is only locked to clone the session out of it, so other sessions can prove (or be closed) meanwhile
+    let session = icicle_sessions().get(&session_id).cloned();
+    match session
+        .ok_or_else(|| eyre::eyre!("no open ICICLE session with id {}", session_id))
+        .and_then(|session| {
+            session
+                .lock()
+                .map_err(|_| {
+                    eyre::eyre!("ICICLE session {} panicked in a previous proof", session_id)
+                })?
+                .prove(wtns_path)
+        })
+        .and_then(|out| attach_calldata(out, calldata))
+    {
+        Ok(snarkjs_out) => {
+            let output = serde_json::to_string_pretty(&snarkjs_out).unwrap();
+            CString::new(output).unwrap()
+        }
+        Err(e) => {
+            panic!("Error: {:?}", e);
+        }
+    }
+}
+
+/// Closes an ICICLE session, releasing its cache. Returns `false` if there was no such session.
+#[cfg(feature = "icicle")]
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub extern "C" fn icicle_session_close(session_id: u32) -> bool {
+    icicle_sessions().remove(&session_id).is_some()
+}
@@ -0,0 +1,134 @@
+use eyre::{eyre, Result};
+use std::ffi::{c_char, CString};
+use std::io::{self, Write};
+use std::sync::{OnceLock, RwLock};
+use tracing_subscriber::{
+    filter::LevelFilter,
+    fmt::{format::FmtSpan, MakeWriter},
+    layer::SubscriberExt,
+    reload, Registry,
+};
+
+/// A C function that receives each formatted log line, without a trailing newline.
+///
+/// The line is only valid during the call, and the function may be called from any thread.
+pub type LogCallback = extern "C" fn(line: *const c_char);
+
+static CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+
+/// Sets the maximum level of the logs, which are off until this is called.
+///
+/// The first call installs the subscriber of the library as the global default, which fails if
+/// another subscriber is installed already, e.g. by a Rust application that uses this crate.
+pub fn set_log_level(level: LevelFilter) -> Result<()> {
+    level_handle()?
+        .reload(level)
+        .map_err(|e| eyre!("could not set log level: {}", e))
+}
+
+/// Sets the function that receives the log lines, or removes it so that the lines go to `stderr`.
+pub fn set_log_callback(callback: Option<LogCallback>) {
+    *CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = callback;
+}
+
+/// Returns the handle to change the level of the subscriber, installing the subscriber on first use.
+fn level_handle() -> Result<&'static reload::Handle<LevelFilter, Registry>> {
+    static HANDLE: OnceLock<Result<reload::Handle<LevelFilter, Registry>, String>> =
+        OnceLock::new();
+
+    HANDLE
+        .get_or_init(|| {
+            let (filter, handle) = reload::Layer::new(LevelFilter::OFF);
+            let subscriber = Registry::default().with(filter).with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(false)
+                    .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                    .with_writer(MakeLineWriter),
+            );
+
+            tracing::subscriber::set_global_default(subscriber)
+                .map(|_| handle)
+                .map_err(|e| e.to_string())
+        })
+        .as_ref()
+        .map_err(|e| eyre!("could not install log subscriber: {}", e))
+}
+
+/// Creates a [`LineWriter`] for each log line.
+struct MakeLineWriter;
+
+impl<'a> MakeWriter<'a> for MakeLineWriter {
+    type Writer = LineWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LineWriter(Vec::new())
+    }
+}
+
+/// Buffers a formatted log line, and passes it to the callback (or `stderr`) once it is complete.
+struct LineWriter(Vec<u8>);
+
+impl Write for LineWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LineWriter {
+    fn drop(&mut self) {
+        let line = String::from_utf8_lossy(&self.0);
+        let line = line.trim_end();
+        if line.is_empty() {
+            return;
+        }
+
+        match *CALLBACK.read().unwrap_or_else(|e| e.into_inner()) {
+            Some(callback) => {
+                // interior NUL bytes can only come from user data, e.g. paths
+                let line = CString::new(line.replace('\0', "")).unwrap_or_default();
+                callback(line.as_ptr());
+            }
+            None => eprintln!("{}", line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+    use std::sync::Mutex;
+
+    static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    extern "C" fn collect_line(line: *const c_char) {
+        let line = unsafe { CStr::from_ptr(line) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        LINES.lock().unwrap().push(line);
+    }
+
+    #[test]
+    fn test_log_callback() -> eyre::Result<()> {
+        set_log_callback(Some(collect_line));
+        set_log_level(LevelFilter::DEBUG)?;
+        tracing::debug!(answer = 42, "hello from the test");
+        tracing::trace!("this is filtered out");
+        set_log_level(LevelFilter::OFF)?;
+        set_log_callback(None);
+
+        let lines = LINES.lock().unwrap();
+        assert!(lines
+            .iter()
+            .any(|line| line.contains("hello from the test") && line.contains("answer=42")));
+        assert!(!lines.iter().any(|line| line.contains("filtered out")));
+
+        Ok(())
+    }
+}
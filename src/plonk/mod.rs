@@ -0,0 +1,173 @@
+use ark_bn254::{Fr, G1Affine};
+use eyre::{eyre, Result};
+use std::path::Path;
+
+use crate::arkworks::load_witness_file;
+use crate::snarkjs::{SnarkjsPlonkOutput, SnarkjsPlonkProof, SnarkjsPublicInputs};
+
+mod snarkjs;
+
+pub(crate) mod transcript;
+
+pub(crate) mod polynomial;
+
+pub(crate) mod zkey;
+pub use zkey::{PlonkProvingKey, PlonkVerifyingKey};
+
+pub(crate) mod prover;
+pub use prover::prove;
+
+mod verifier;
+pub use verifier::verify;
+
+/// A PLONK proof over BN254, with the same components as a SnarkJS PLONK proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkProof {
+    /// Commitment to the `A` wires.
+    pub a: G1Affine,
+    /// Commitment to the `B` wires.
+    pub b: G1Affine,
+    /// Commitment to the `C` wires.
+    pub c: G1Affine,
+    /// Commitment to the permutation polynomial.
+    pub z: G1Affine,
+    /// Commitment to the low part of the quotient polynomial.
+    pub t1: G1Affine,
+    /// Commitment to the middle part of the quotient polynomial.
+    pub t2: G1Affine,
+    /// Commitment to the high part of the quotient polynomial.
+    pub t3: G1Affine,
+    /// Opening proof at `xi`.
+    pub wxi: G1Affine,
+    /// Opening proof at `xi * w`.
+    pub wxiw: G1Affine,
+    /// Evaluation of `A` at `xi`.
+    pub eval_a: Fr,
+    /// Evaluation of `B` at `xi`.
+    pub eval_b: Fr,
+    /// Evaluation of `C` at `xi`.
+    pub eval_c: Fr,
+    /// Evaluation of `S1` at `xi`.
+    pub eval_s1: Fr,
+    /// Evaluation of `S2` at `xi`.
+    pub eval_s2: Fr,
+    /// Evaluation of `Z` at `xi * w`.
+    pub eval_zw: Fr,
+}
+
+/// Proves a circuit with an existing witness and a SnarkJS PLONK prover key.
+///
+/// The witness can be either a JSON or binary file, which is detected from its contents.
+pub fn prove_with_existing_witness(
+    wtns_path: impl AsRef<Path>,
+    pkey_path: impl AsRef<Path>,
+) -> Result<SnarkjsPlonkOutput> {
+    let wtns_path = wtns_path.as_ref();
+    let pkey_path = pkey_path.as_ref();
+
+    let wtns = load_witness_file::<Fr>(wtns_path, None)?;
+
+    let proving_key = PlonkProvingKey::read(pkey_path)
+        .map_err(|e| eyre!("could not load pkey {}: {}", pkey_path.display(), e))?;
+
+    let (proof, public_signals) = prove(&proving_key, &wtns)?;
+    debug_assert!(
+        verify(&proving_key.vk, &proof, &public_signals).is_ok_and(|b| b),
+        "proof is not accepted"
+    );
+
+    Ok(SnarkjsPlonkOutput {
+        proof: SnarkjsPlonkProof::from(&proof),
+        public_signals: SnarkjsPublicInputs::from_arkworks(public_signals),
+        calldata: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binfile::zkey_writer::{
+        commit, points, polynomials, write_field, write_point, ToyCircuit,
+    };
+    use crate::snarkjs::check_snarkjs_output;
+    use ark_bn254::{g2, Fq, G2Affine};
+    use ark_ec::AffineRepr;
+    use ark_ff::{One, UniformRand, Zero};
+    use ark_poly::EvaluationDomain;
+    use ark_std::rand::thread_rng;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    /// Builds the zkey of the toy circuit of [`ToyCircuit`], with the given secret `tau`.
+    fn toy_zkey(tau: Fr) -> Vec<u8> {
+        let circuit = ToyCircuit::<Fr>::new();
+        let n = circuit.domain.size();
+        let selectors = [
+            &circuit.qm,
+            &circuit.ql,
+            &circuit.qr,
+            &circuit.qo,
+            &circuit.qc,
+        ];
+
+        let mut header = Vec::new();
+        circuit.write_sizes::<Fq>(&mut header);
+        write_field(&mut header, &circuit.k1);
+        write_field(&mut header, &circuit.k2);
+        for p in selectors.into_iter().chain(&circuit.sigma) {
+            write_point(&mut header, &commit::<G1Affine>(p, tau));
+        }
+        write_point::<g2::Config>(&mut header, &(G2Affine::generator() * tau).into());
+
+        let ptau = (0..n + 6)
+            .map(|i| commit::<G1Affine>(&[vec![Fr::zero(); i], vec![Fr::one()]].concat(), tau))
+            .collect::<Vec<_>>();
+
+        let mut zkey = circuit.zkey(2, header);
+        for (id, p) in (7..).zip(selectors) {
+            zkey.section(id, polynomials(std::slice::from_ref(p)));
+        }
+        zkey.section(12, polynomials(&circuit.sigma))
+            .section(13, polynomials(&[circuit.coeffs(&[Fr::one()])]))
+            .section(14, points(&ptau));
+
+        zkey.finish()
+    }
+
+    #[test]
+    fn test_plonk_prove() -> eyre::Result<()> {
+        let mut rng = thread_rng();
+        let pk = PlonkProvingKey::from_bytes(&toy_zkey(Fr::rand(&mut rng)))?;
+        assert_eq!(pk.vk.n_public, 1);
+        assert_eq!(pk.additions.len(), 1);
+        assert_eq!(pk.a_map, vec![1, 2, 4]);
+
+        let (a, b) = (Fr::from(3), Fr::from(11));
+        let (proof, public_signals) = prove(&pk, &[Fr::one(), a * b, a, b])?;
+        assert_eq!(public_signals, vec![Fr::from(33)]);
+        assert!(verify(&pk.vk, &proof, &public_signals)?);
+        assert!(!verify(&pk.vk, &proof, &[Fr::from(34)])?);
+
+        // should not prove an unsatisfied witness
+        assert!(prove(&pk, &[Fr::one(), Fr::from(34), a, b]).is_err());
+
+        let snarkjs_proof = SnarkjsPlonkProof::from(&proof);
+        assert_eq!(snarkjs_proof.protocol, "plonk");
+        assert_eq!(snarkjs_proof.a[2], "1");
+        assert_eq!(snarkjs_proof.eval_a, proof.eval_a.to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plonk_snarkjs() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let snarkjs_out = prove_with_existing_witness(
+            dir.join("default").join("witness.wtns"),
+            dir.join("plonk_pkey.zkey"),
+        )?;
+        assert_eq!(snarkjs_out.proof.protocol, "plonk");
+
+        check_snarkjs_output(&snarkjs_out, &dir, CIRCUIT, "plonk")
+    }
+}
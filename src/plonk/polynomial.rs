@@ -0,0 +1,87 @@
+use ark_bn254::{Fr, G1Affine, G1Projective};
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{One, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+
+/// Interpolates the evaluations over the domain, and adds `(b_0 + b_1 X + ...) * Z_H(X)` for the blinders `b_i`.
+pub(crate) fn blind(domain: &Radix2EvaluationDomain<Fr>, evals: &[Fr], blinders: &[Fr]) -> Vec<Fr> {
+    let n = domain.size();
+    let mut coeffs = domain.ifft(evals);
+    coeffs.resize(n + blinders.len(), Fr::zero());
+    for (i, b) in blinders.iter().enumerate() {
+        coeffs[n + i] += b;
+        coeffs[i] -= b;
+    }
+
+    coeffs
+}
+
+/// Commits to a polynomial with KZG, assuming there are enough powers of tau.
+#[inline]
+pub(crate) fn commit(ptau: &[G1Affine], coeffs: &[Fr]) -> G1Affine {
+    G1Projective::msm_unchecked(&ptau[..coeffs.len()], coeffs).into_affine()
+}
+
+/// Evaluates a polynomial at `x` with Horner's method.
+#[inline]
+pub(crate) fn evaluate(coeffs: &[Fr], x: Fr) -> Fr {
+    coeffs.iter().rev().fold(Fr::zero(), |acc, c| acc * x + c)
+}
+
+/// Adds `scalar * p(X)` to the polynomial `acc(X)`.
+pub(crate) fn add_scaled(acc: &mut Vec<Fr>, p: &[Fr], scalar: Fr) {
+    if acc.len() < p.len() {
+        acc.resize(p.len(), Fr::zero());
+    }
+    for (a, c) in acc.iter_mut().zip(p) {
+        *a += scalar * c;
+    }
+}
+
+/// Returns the quotient of `(p(X) - p(z)) / (X - z)`, i.e. the remainder is dropped.
+pub(crate) fn divide_by_linear(coeffs: &[Fr], z: Fr) -> Vec<Fr> {
+    let mut quotient = vec![Fr::zero(); coeffs.len().saturating_sub(1)];
+    let mut acc = Fr::zero();
+    for i in (1..coeffs.len()).rev() {
+        acc = coeffs[i] + acc * z;
+        quotient[i - 1] = acc;
+    }
+
+    quotient
+}
+
+/// Returns an iterator over the powers `1, w, w^2, ...`.
+#[inline]
+pub(crate) fn omega_powers(omega: Fr) -> impl Iterator<Item = Fr> {
+    std::iter::successors(Some(Fr::one()), move |w| Some(*w * omega))
+}
+
+/// Returns the quotient of `p(X) / (X^k - c)`, i.e. the remainder is dropped.
+pub(crate) fn divide_by_vanishing(coeffs: &[Fr], k: usize, c: Fr) -> Vec<Fr> {
+    let mut remainder = coeffs.to_vec();
+    let mut quotient = vec![Fr::zero(); coeffs.len().saturating_sub(k)];
+    for i in (k..coeffs.len()).rev() {
+        let q = remainder[i];
+        quotient[i - k] = q;
+        remainder[i - k] += c * q;
+    }
+
+    quotient
+}
+
+/// Returns the evaluation of the first Lagrange polynomial `L_1(xi)`, and of the public input polynomial
+/// `PI(xi) = -sum_i w_i L_i(xi)` for the public signals `w_i`.
+pub(crate) fn lagrange_evaluations(
+    domain: &Radix2EvaluationDomain<Fr>,
+    xi: Fr,
+    public_signals: &[Fr],
+) -> (Fr, Fr) {
+    let lagrange = domain.evaluate_all_lagrange_coefficients(xi);
+    let pi = -public_signals
+        .iter()
+        .zip(&lagrange)
+        .map(|(s, l)| *s * l)
+        .sum::<Fr>();
+
+    (lagrange[0], pi)
+}
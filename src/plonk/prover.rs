@@ -0,0 +1,299 @@
+use ark_bn254::Fr;
+use ark_ff::{batch_inversion, FftField, Field, One, UniformRand, Zero};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_std::rand::thread_rng;
+use eyre::{eyre, OptionExt, Result};
+
+use super::polynomial::{
+    add_scaled, blind, commit, divide_by_linear, evaluate, lagrange_evaluations, omega_powers,
+};
+use super::{transcript::Keccak256Transcript, PlonkProof, PlonkProvingKey};
+
+/// Creates a PLONK proof for the given witness, following the rounds of the SnarkJS prover so that
+/// the proof is accepted by `snarkjs plonk verify`.
+///
+/// The witness is the one computed by Circom, i.e. without the internal signals of the additions.
+/// Returns the proof along with the public signals.
+pub fn prove(pk: &PlonkProvingKey, witness: &[Fr]) -> Result<(PlonkProof, Vec<Fr>)> {
+    let vk = &pk.vk;
+    let n = vk.domain_size;
+    let domain = Radix2EvaluationDomain::<Fr>::new(n)
+        .filter(|d| d.size() == n)
+        .ok_or_eyre("could not create the evaluation domain")?;
+    let omega = domain.group_gen;
+    if pk.ptau.len() < n + 6 {
+        return Err(eyre!(
+            "expected at least {} powers of tau, got {}",
+            n + 6,
+            pk.ptau.len()
+        ));
+    }
+
+    let signals = compute_signals(pk.n_vars, &pk.additions, witness)?;
+    let public_signals = signals[1..=vk.n_public].to_vec();
+
+    let mut rng = thread_rng();
+    let blinders: [Fr; 11] = std::array::from_fn(|_| Fr::rand(&mut rng));
+    let mut transcript = Keccak256Transcript::default();
+
+    // round 1: wire polynomials
+    let [a_evals, b_evals, c_evals] =
+        [&pk.a_map, &pk.b_map, &pk.c_map].map(|map| wire_evaluations(map, &signals, n));
+    let a = blind(&domain, &a_evals, &blinders[0..2]);
+    let b = blind(&domain, &b_evals, &blinders[2..4]);
+    let c = blind(&domain, &c_evals, &blinders[4..6]);
+    let [commit_a, commit_b, commit_c] = [&a, &b, &c].map(|p| commit(&pk.ptau, p));
+
+    // round 2: permutation polynomial
+    for p in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+        transcript.add_point(&p);
+    }
+    for s in &public_signals {
+        transcript.add_scalar(s);
+    }
+    for p in [commit_a, commit_b, commit_c] {
+        transcript.add_point(&p);
+    }
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+
+    let z_evals = permutation_evaluations(
+        &domain,
+        [&a_evals, &b_evals, &c_evals],
+        [&pk.s1, &pk.s2, &pk.s3],
+        [vk.k1, vk.k2],
+        beta,
+        gamma,
+    )?;
+    let z = blind(&domain, &z_evals, &blinders[6..9]);
+    let commit_z = commit(&pk.ptau, &z);
+
+    // round 3: quotient polynomial, computed over a coset large enough for its numerator
+    transcript.add_scalar(&beta);
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&commit_z);
+    let alpha = transcript.challenge();
+    let alpha2 = alpha.square();
+
+    let coset = Radix2EvaluationDomain::<Fr>::new(8 * n)
+        .and_then(|d| d.get_coset(Fr::GENERATOR))
+        .ok_or_eyre("could not create the quotient domain")?;
+    let mut pi_evals = public_signals.iter().map(|s| -*s).collect::<Vec<_>>();
+    pi_evals.resize(n, Fr::zero());
+    let mut l1_evals = vec![Fr::zero(); n];
+    l1_evals[0] = Fr::one();
+    let z_shifted = z
+        .iter()
+        .zip(omega_powers(omega))
+        .map(|(c, w)| *c * w)
+        .collect::<Vec<_>>();
+    let [a_ext, b_ext, c_ext, z_ext, zw_ext] = [&a, &b, &c, &z, &z_shifted].map(|p| coset.fft(p));
+    let [qm_ext, ql_ext, qr_ext, qo_ext, qc_ext, s1_ext, s2_ext, s3_ext] = [
+        &pk.qm, &pk.ql, &pk.qr, &pk.qo, &pk.qc, &pk.s1, &pk.s2, &pk.s3,
+    ]
+    .map(|p| coset.fft(p));
+    let [pi_ext, l1_ext] = [pi_evals, l1_evals].map(|evals| coset.fft(&domain.ifft(&evals)));
+    let mut zh_inv_ext = coset
+        .elements()
+        .map(|x| domain.evaluate_vanishing_polynomial(x))
+        .collect::<Vec<_>>();
+    batch_inversion(&mut zh_inv_ext);
+
+    let t_evals = coset
+        .elements()
+        .enumerate()
+        .map(|(i, x)| {
+            let (a, b, c) = (a_ext[i], b_ext[i], c_ext[i]);
+            let gate = qm_ext[i] * a * b
+                + ql_ext[i] * a
+                + qr_ext[i] * b
+                + qo_ext[i] * c
+                + qc_ext[i]
+                + pi_ext[i];
+            let perm = (a + beta * x + gamma)
+                * (b + beta * vk.k1 * x + gamma)
+                * (c + beta * vk.k2 * x + gamma)
+                * z_ext[i]
+                - (a + beta * s1_ext[i] + gamma)
+                    * (b + beta * s2_ext[i] + gamma)
+                    * (c + beta * s3_ext[i] + gamma)
+                    * zw_ext[i];
+            let first = (z_ext[i] - Fr::one()) * l1_ext[i];
+
+            (gate + alpha * perm + alpha2 * first) * zh_inv_ext[i]
+        })
+        .collect::<Vec<_>>();
+    let mut t = coset.ifft(&t_evals);
+    if t.iter().skip(3 * n + 6).any(|c| !c.is_zero()) {
+        return Err(eyre!("constraints are not satisfied by the witness"));
+    }
+    t.resize(3 * n + 6, Fr::zero());
+
+    // split as `t(X) = T1(X) + X^n T2(X) + X^2n T3(X)`, with blinders that cancel out
+    let mut t1 = t[..n].to_vec();
+    t1.push(blinders[9]);
+    let mut t2 = t[n..2 * n].to_vec();
+    t2[0] -= blinders[9];
+    t2.push(blinders[10]);
+    let mut t3 = t[2 * n..].to_vec();
+    t3[0] -= blinders[10];
+    let [commit_t1, commit_t2, commit_t3] = [&t1, &t2, &t3].map(|p| commit(&pk.ptau, p));
+
+    // round 4: evaluations
+    transcript.add_scalar(&alpha);
+    for p in [commit_t1, commit_t2, commit_t3] {
+        transcript.add_point(&p);
+    }
+    let xi = transcript.challenge();
+    let xiw = xi * omega;
+
+    let [eval_a, eval_b, eval_c] = [&a, &b, &c].map(|p| evaluate(p, xi));
+    let [eval_s1, eval_s2] = [&pk.s1, &pk.s2].map(|p| evaluate(p, xi));
+    let eval_zw = evaluate(&z, xiw);
+
+    // round 5: opening proofs
+    transcript.add_scalar(&xi);
+    for s in [eval_a, eval_b, eval_c, eval_s1, eval_s2, eval_zw] {
+        transcript.add_scalar(&s);
+    }
+    let v = transcript.challenge();
+
+    let xin = xi.pow([n as u64]);
+    let zh = xin - Fr::one();
+    let (l1, pi) = lagrange_evaluations(&domain, xi, &public_signals);
+
+    // the linearization polynomial, which evaluates to zero at `xi`
+    let betaxi = beta * xi;
+    let e_ab = (eval_a + beta * eval_s1 + gamma) * (eval_b + beta * eval_s2 + gamma);
+    let r0 = pi - l1 * alpha2 - e_ab * (eval_c + gamma) * eval_zw * alpha;
+    let mut r = vec![r0];
+    add_scaled(&mut r, &pk.qm, eval_a * eval_b);
+    add_scaled(&mut r, &pk.ql, eval_a);
+    add_scaled(&mut r, &pk.qr, eval_b);
+    add_scaled(&mut r, &pk.qo, eval_c);
+    add_scaled(&mut r, &pk.qc, Fr::one());
+    add_scaled(
+        &mut r,
+        &z,
+        alpha
+            * (eval_a + betaxi + gamma)
+            * (eval_b + betaxi * vk.k1 + gamma)
+            * (eval_c + betaxi * vk.k2 + gamma)
+            + alpha2 * l1,
+    );
+    add_scaled(&mut r, &pk.s3, -(e_ab * alpha * beta * eval_zw));
+    add_scaled(&mut r, &t1, -zh);
+    add_scaled(&mut r, &t2, -zh * xin);
+    add_scaled(&mut r, &t3, -zh * xin * xin);
+    debug_assert!(
+        evaluate(&r, xi).is_zero(),
+        "linearization is not zero at xi"
+    );
+
+    // evaluations are not subtracted, as they only affect the remainder of the division
+    let mut wxi = r;
+    let mut v_i = v;
+    for p in [&a, &b, &c, &pk.s1.coeffs, &pk.s2.coeffs] {
+        add_scaled(&mut wxi, p, v_i);
+        v_i *= v;
+    }
+    let wxi = divide_by_linear(&wxi, xi);
+    let wxiw = divide_by_linear(&z, xiw);
+
+    let proof = PlonkProof {
+        a: commit_a,
+        b: commit_b,
+        c: commit_c,
+        z: commit_z,
+        t1: commit_t1,
+        t2: commit_t2,
+        t3: commit_t3,
+        wxi: commit(&pk.ptau, &wxi),
+        wxiw: commit(&pk.ptau, &wxiw),
+        eval_a,
+        eval_b,
+        eval_c,
+        eval_s1,
+        eval_s2,
+        eval_zw,
+    };
+
+    Ok((proof, public_signals))
+}
+
+/// Computes all signals from the witness, i.e. the internal signals of the additions are appended.
+///
+/// Unknown signals are treated as zero, as SnarkJS does.
+pub(crate) fn compute_signals(
+    n_vars: usize,
+    additions: &[(usize, usize, Fr, Fr)],
+    witness: &[Fr],
+) -> Result<Vec<Fr>> {
+    let n_witness = n_vars - additions.len();
+    if witness.len() != n_witness {
+        return Err(eyre!(
+            "expected {} witness values, got {}",
+            n_witness,
+            witness.len()
+        ));
+    }
+
+    let mut signals = witness.to_vec();
+    for &(signal1, signal2, factor1, factor2) in additions {
+        let value = factor1 * signals.get(signal1).copied().unwrap_or_default()
+            + factor2 * signals.get(signal2).copied().unwrap_or_default();
+        signals.push(value);
+    }
+
+    Ok(signals)
+}
+
+/// Returns the evaluations of a wire polynomial over a domain of size `n`, given its signal ids.
+pub(crate) fn wire_evaluations(map: &[usize], signals: &[Fr], n: usize) -> Vec<Fr> {
+    let mut evals = map
+        .iter()
+        .map(|&i| signals.get(i).copied().unwrap_or_default())
+        .collect::<Vec<_>>();
+    evals.resize(n, Fr::zero());
+
+    evals
+}
+
+/// Returns the evaluations of the permutation polynomial `Z` over the domain.
+pub(crate) fn permutation_evaluations(
+    domain: &Radix2EvaluationDomain<Fr>,
+    [a_evals, b_evals, c_evals]: [&[Fr]; 3],
+    sigmas: [&[Fr]; 3],
+    [k1, k2]: [Fr; 2],
+    beta: Fr,
+    gamma: Fr,
+) -> Result<Vec<Fr>> {
+    let [s1_evals, s2_evals, s3_evals] = sigmas.map(|s| domain.fft(s));
+    let mut numerators = Vec::with_capacity(domain.size());
+    let mut denominators = Vec::with_capacity(domain.size());
+    for (i, w) in domain.elements().enumerate() {
+        numerators.push(
+            (a_evals[i] + beta * w + gamma)
+                * (b_evals[i] + beta * k1 * w + gamma)
+                * (c_evals[i] + beta * k2 * w + gamma),
+        );
+        denominators.push(
+            (a_evals[i] + beta * s1_evals[i] + gamma)
+                * (b_evals[i] + beta * s2_evals[i] + gamma)
+                * (c_evals[i] + beta * s3_evals[i] + gamma),
+        );
+    }
+    batch_inversion(&mut denominators);
+    let mut z_evals = Vec::with_capacity(domain.size());
+    let mut acc = Fr::one();
+    for (num, den) in numerators.iter().zip(&denominators) {
+        z_evals.push(acc);
+        acc *= num * den;
+    }
+    if !acc.is_one() {
+        return Err(eyre!("copy constraints are not satisfied by the witness"));
+    }
+
+    Ok(z_evals)
+}
@@ -0,0 +1,34 @@
+use crate::arkworks::g1_to_projective_strings;
+use crate::snarkjs::SnarkjsPlonkProof;
+
+use super::PlonkProof;
+
+impl From<&PlonkProof> for SnarkjsPlonkProof {
+    fn from(proof: &PlonkProof) -> Self {
+        Self {
+            a: g1_to_projective_strings(&proof.a),
+            b: g1_to_projective_strings(&proof.b),
+            c: g1_to_projective_strings(&proof.c),
+            z: g1_to_projective_strings(&proof.z),
+            t1: g1_to_projective_strings(&proof.t1),
+            t2: g1_to_projective_strings(&proof.t2),
+            t3: g1_to_projective_strings(&proof.t3),
+            wxi: g1_to_projective_strings(&proof.wxi),
+            wxiw: g1_to_projective_strings(&proof.wxiw),
+            eval_a: proof.eval_a.to_string(),
+            eval_b: proof.eval_b.to_string(),
+            eval_c: proof.eval_c.to_string(),
+            eval_s1: proof.eval_s1.to_string(),
+            eval_s2: proof.eval_s2.to_string(),
+            eval_zw: proof.eval_zw.to_string(),
+            protocol: "plonk".to_string(),
+            curve: "bn128".to_string(),
+        }
+    }
+}
+
+impl From<PlonkProof> for SnarkjsPlonkProof {
+    fn from(proof: PlonkProof) -> Self {
+        Self::from(&proof)
+    }
+}
@@ -0,0 +1,44 @@
+use ark_bn254::{Fr, G1Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use sha3::{Digest, Keccak256};
+
+/// Flag for the point at infinity in the uncompressed representation of SnarkJS.
+const INFINITY_FLAG: u8 = 0x40;
+
+/// Fiat-Shamir transcript of SnarkJS, see `Keccak256Transcript` there.
+///
+/// Points are added in uncompressed big-endian form `x || y`, and scalars as 32-byte big-endian;
+/// the challenge is the Keccak256 hash of all these, reduced modulo the scalar field.
+#[derive(Debug, Default)]
+pub(crate) struct Keccak256Transcript {
+    data: Vec<u8>,
+}
+
+impl Keccak256Transcript {
+    pub fn add_point(&mut self, p: &G1Affine) {
+        match p.xy() {
+            Some((x, y)) => {
+                self.data.extend(x.into_bigint().to_bytes_be());
+                self.data.extend(y.into_bigint().to_bytes_be());
+            }
+            None => {
+                let mut bytes = [0u8; 64];
+                bytes[0] = INFINITY_FLAG;
+                self.data.extend(bytes);
+            }
+        }
+    }
+
+    pub fn add_scalar(&mut self, s: &Fr) {
+        self.data.extend(s.into_bigint().to_bytes_be());
+    }
+
+    /// Returns the challenge for the data so far, and resets the transcript.
+    pub fn challenge(&mut self) -> Fr {
+        let hash = Keccak256::digest(&self.data);
+        self.data.clear();
+
+        Fr::from_be_bytes_mod_order(&hash)
+    }
+}
@@ -0,0 +1,111 @@
+use ark_bn254::{Bn254, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr};
+use ark_ff::{Field, One};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use eyre::{eyre, OptionExt, Result};
+
+use super::polynomial::lagrange_evaluations;
+use super::{transcript::Keccak256Transcript, PlonkProof, PlonkVerifyingKey};
+
+/// Verifies a PLONK proof with the given public signals, as done by `snarkjs plonk verify`.
+pub fn verify(vk: &PlonkVerifyingKey, proof: &PlonkProof, public_signals: &[Fr]) -> Result<bool> {
+    if public_signals.len() != vk.n_public {
+        return Err(eyre!(
+            "expected {} public signals, got {}",
+            vk.n_public,
+            public_signals.len()
+        ));
+    }
+
+    let n = vk.domain_size;
+    let domain = Radix2EvaluationDomain::<Fr>::new(n)
+        .filter(|d| d.size() == n)
+        .ok_or_eyre("could not create the evaluation domain")?;
+
+    // challenges
+    let mut transcript = Keccak256Transcript::default();
+    for p in [vk.qm, vk.ql, vk.qr, vk.qo, vk.qc, vk.s1, vk.s2, vk.s3] {
+        transcript.add_point(&p);
+    }
+    for s in public_signals {
+        transcript.add_scalar(s);
+    }
+    for p in [proof.a, proof.b, proof.c] {
+        transcript.add_point(&p);
+    }
+    let beta = transcript.challenge();
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+    transcript.add_scalar(&beta);
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&proof.z);
+    let alpha = transcript.challenge();
+    transcript.add_scalar(&alpha);
+    for p in [proof.t1, proof.t2, proof.t3] {
+        transcript.add_point(&p);
+    }
+    let xi = transcript.challenge();
+    transcript.add_scalar(&xi);
+    for s in [
+        proof.eval_a,
+        proof.eval_b,
+        proof.eval_c,
+        proof.eval_s1,
+        proof.eval_s2,
+        proof.eval_zw,
+    ] {
+        transcript.add_scalar(&s);
+    }
+    let v1 = transcript.challenge();
+    let [v2, v3, v4, v5] = [2, 3, 4, 5].map(|i| v1.pow([i]));
+    transcript.add_point(&proof.wxi);
+    transcript.add_point(&proof.wxiw);
+    let u = transcript.challenge();
+
+    // public input & first Lagrange polynomial evaluations
+    let xin = xi.pow([n as u64]);
+    let zh = xin - Fr::one();
+    let (l1, pi) = lagrange_evaluations(&domain, xi, public_signals);
+
+    // constant term of the linearization
+    let alpha2 = alpha.square();
+    let e_ab = (proof.eval_a + beta * proof.eval_s1 + gamma)
+        * (proof.eval_b + beta * proof.eval_s2 + gamma);
+    let r0 = pi - l1 * alpha2 - e_ab * (proof.eval_c + gamma) * proof.eval_zw * alpha;
+
+    // the batched commitment `[D]` & its combination with the opened commitments `[F]`
+    let betaxi = beta * xi;
+    let d2 = alpha
+        * (proof.eval_a + betaxi + gamma)
+        * (proof.eval_b + betaxi * vk.k1 + gamma)
+        * (proof.eval_c + betaxi * vk.k2 + gamma)
+        + alpha2 * l1
+        + u;
+    let d3 = e_ab * alpha * beta * proof.eval_zw;
+    let d = vk.qm * (proof.eval_a * proof.eval_b)
+        + vk.ql * proof.eval_a
+        + vk.qr * proof.eval_b
+        + vk.qo * proof.eval_c
+        + vk.qc
+        + proof.z * d2
+        - vk.s3 * d3
+        - (proof.t1 * zh + proof.t2 * (zh * xin) + proof.t3 * (zh * xin * xin));
+    let f = d + proof.a * v1 + proof.b * v2 + proof.c * v3 + vk.s1 * v4 + vk.s2 * v5;
+
+    // the batched evaluation `[E]`
+    let e = G1Affine::generator()
+        * (-r0
+            + v1 * proof.eval_a
+            + v2 * proof.eval_b
+            + v3 * proof.eval_c
+            + v4 * proof.eval_s1
+            + v5 * proof.eval_s2
+            + u * proof.eval_zw);
+
+    // e(-[Wxi] - u[Wxiw], [x]_2) * e(xi[Wxi] + u xi w[Wxiw] + [F] - [E], [1]_2) == 1
+    let a1 = proof.wxi.into_group() + proof.wxiw * u;
+    let b1 = proof.wxi * xi + proof.wxiw * (u * xi * domain.group_gen) + f - e;
+    let pairing = Bn254::multi_pairing([-a1, b1], [vk.x_2, G2Affine::generator()]);
+
+    Ok(pairing.0.is_one())
+}
@@ -0,0 +1,295 @@
+//! Reader for SnarkJS PLONK `.zkey` files over BN254.
+//!
+//! The sections are as follows, where field elements & curve points are little-endian in Montgomery form:
+//!
+//! 1. protocol id, which is `2` for PLONK
+//! 2. header: field sizes & moduli, `nVars`, `nPublic`, `domainSize`, `nAdditions`, `nConstraints`,
+//!    `k1`, `k2`, commitments to `Qm, Ql, Qr, Qo, Qc, S1, S2, S3` and `[τ]₂`
+//! 3. additions, i.e. internal signals `factor1 * signal1 + factor2 * signal2`
+//! 4. to 6. signal ids of `A`, `B` and `C` wires for each constraint
+//! 7. to 11. `Qm, Ql, Qr, Qo, Qc` polynomials, with `n` coefficients followed by `4n` evaluations
+//! 12. `S1, S2, S3` polynomials, each with `n` coefficients followed by `4n` evaluations
+//! 13. Lagrange polynomials for the public signals
+//! 14. powers of tau in G1
+
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger256, Fp, MontBackend, MontConfig, PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::DenseUVPolynomial;
+use eyre::{eyre, Result};
+use num_bigint::BigUint;
+use std::path::Path;
+
+use crate::binfile::{BinFile, SectionReader};
+
+const PLONK_PROTOCOL_ID: u32 = 2;
+
+const HEADER_SECTION: u32 = 1;
+const PLONK_HEADER_SECTION: u32 = 2;
+const ADDITIONS_SECTION: u32 = 3;
+const A_MAP_SECTION: u32 = 4;
+const B_MAP_SECTION: u32 = 5;
+const C_MAP_SECTION: u32 = 6;
+const QM_SECTION: u32 = 7;
+const QL_SECTION: u32 = 8;
+const QR_SECTION: u32 = 9;
+const QO_SECTION: u32 = 10;
+const QC_SECTION: u32 = 11;
+const SIGMA_SECTION: u32 = 12;
+const PTAU_SECTION: u32 = 14;
+
+/// Size of a field element in bytes, for both base & scalar fields of BN254.
+pub(crate) const N8: usize = 32;
+
+/// The verifying key of a PLONK circuit, i.e. what SnarkJS exports as `verification_key.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlonkVerifyingKey {
+    /// Number of public signals.
+    pub n_public: usize,
+    /// Size of the evaluation domain, a power of two.
+    pub domain_size: usize,
+    /// Coset shift for the `B` wires.
+    pub k1: Fr,
+    /// Coset shift for the `C` wires.
+    pub k2: Fr,
+    /// Commitment to the multiplication selector.
+    pub qm: G1Affine,
+    /// Commitment to the left selector.
+    pub ql: G1Affine,
+    /// Commitment to the right selector.
+    pub qr: G1Affine,
+    /// Commitment to the output selector.
+    pub qo: G1Affine,
+    /// Commitment to the constant selector.
+    pub qc: G1Affine,
+    /// Commitment to the permutation of `A` wires.
+    pub s1: G1Affine,
+    /// Commitment to the permutation of `B` wires.
+    pub s2: G1Affine,
+    /// Commitment to the permutation of `C` wires.
+    pub s3: G1Affine,
+    /// The element `τ * H` in G2.
+    pub x_2: G2Affine,
+}
+
+/// The proving key of a PLONK circuit, as read from a SnarkJS `.zkey` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlonkProvingKey {
+    /// The underlying verifying key.
+    pub vk: PlonkVerifyingKey,
+    /// Number of signals, including the internal ones from additions.
+    pub n_vars: usize,
+    /// Internal signals as `(signal1, signal2, factor1, factor2)`, computed as `factor1 * signal1 + factor2 * signal2`.
+    pub additions: Vec<(usize, usize, Fr, Fr)>,
+    /// Signal ids of `A` wires, one for each constraint.
+    pub a_map: Vec<usize>,
+    /// Signal ids of `B` wires, one for each constraint.
+    pub b_map: Vec<usize>,
+    /// Signal ids of `C` wires, one for each constraint.
+    pub c_map: Vec<usize>,
+    /// The multiplication selector polynomial.
+    pub qm: DensePolynomial<Fr>,
+    /// The left selector polynomial.
+    pub ql: DensePolynomial<Fr>,
+    /// The right selector polynomial.
+    pub qr: DensePolynomial<Fr>,
+    /// The output selector polynomial.
+    pub qo: DensePolynomial<Fr>,
+    /// The constant selector polynomial.
+    pub qc: DensePolynomial<Fr>,
+    /// The permutation polynomial of `A` wires.
+    pub s1: DensePolynomial<Fr>,
+    /// The permutation polynomial of `B` wires.
+    pub s2: DensePolynomial<Fr>,
+    /// The permutation polynomial of `C` wires.
+    pub s3: DensePolynomial<Fr>,
+    /// The elements `τ^i * G` in G1.
+    pub ptau: Vec<G1Affine>,
+}
+
+impl PlonkProvingKey {
+    /// Reads a PLONK proving key from a `.zkey` file.
+    pub fn read(zkey_path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&std::fs::read(zkey_path)?)
+    }
+
+    /// Parses a PLONK proving key from the contents of a `.zkey` file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let file = BinFile::parse(bytes, b"zkey")?;
+
+        let protocol_id = file.section(HEADER_SECTION)?.read_u32()?;
+        if protocol_id != PLONK_PROTOCOL_ID {
+            return Err(eyre!(
+                "expected a PLONK zkey (protocol {}), got protocol {}",
+                PLONK_PROTOCOL_ID,
+                protocol_id
+            ));
+        }
+
+        // header
+        let mut header = file.section(PLONK_HEADER_SECTION)?;
+        read_modulus::<Fq>(&mut header, "base")?;
+        read_modulus::<Fr>(&mut header, "scalar")?;
+        let n_vars = header.read_u32()? as usize;
+        let n_public = header.read_u32()? as usize;
+        let domain_size = header.read_u32()? as usize;
+        let n_additions = header.read_u32()? as usize;
+        let n_constraints = header.read_u32()? as usize;
+        if !domain_size.is_power_of_two() || n_constraints > domain_size {
+            return Err(eyre!(
+                "invalid domain size {} for {} constraints",
+                domain_size,
+                n_constraints
+            ));
+        }
+        let k1 = read_field(&mut header)?;
+        let k2 = read_field(&mut header)?;
+        let [qm, ql, qr, qo, qc, s1, s2, s3] = [(); 8].map(|_| read_g1(&mut header));
+        let x_2 = read_g2(&mut header)?;
+        let vk = PlonkVerifyingKey {
+            n_public,
+            domain_size,
+            k1,
+            k2,
+            qm: qm?,
+            ql: ql?,
+            qr: qr?,
+            qo: qo?,
+            qc: qc?,
+            s1: s1?,
+            s2: s2?,
+            s3: s3?,
+            x_2,
+        };
+
+        // additions & wire maps
+        let mut additions_section = file.section(ADDITIONS_SECTION)?;
+        let additions = (0..n_additions)
+            .map(|_| {
+                Ok((
+                    additions_section.read_u32()? as usize,
+                    additions_section.read_u32()? as usize,
+                    read_field(&mut additions_section)?,
+                    read_field(&mut additions_section)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let [a_map, b_map, c_map] = [A_MAP_SECTION, B_MAP_SECTION, C_MAP_SECTION].map(|id| {
+            let mut section = file.section(id)?;
+            (0..n_constraints)
+                .map(|_| Ok(section.read_u32()? as usize))
+                .collect::<Result<Vec<_>>>()
+        });
+
+        // polynomials, where only the coefficients are read & the extended evaluations are skipped
+        let [qm, ql, qr, qo, qc] = [QM_SECTION, QL_SECTION, QR_SECTION, QO_SECTION, QC_SECTION]
+            .map(|id| read_polynomial(&mut file.section(id)?, domain_size));
+        let mut sigma_section = file.section(SIGMA_SECTION)?;
+        let [s1, s2, s3] = [(); 3].map(|_| read_polynomial(&mut sigma_section, domain_size));
+
+        let mut ptau_section = file.section(PTAU_SECTION)?;
+        let ptau = (0..ptau_section.remaining() / (2 * N8))
+            .map(|_| read_g1(&mut ptau_section))
+            .collect::<Result<Vec<_>>>()?;
+        if ptau.len() < domain_size + 6 {
+            return Err(eyre!(
+                "expected at least {} powers of tau, got {}",
+                domain_size + 6,
+                ptau.len()
+            ));
+        }
+
+        Ok(Self {
+            vk,
+            n_vars,
+            additions,
+            a_map: a_map?,
+            b_map: b_map?,
+            c_map: c_map?,
+            qm: qm?,
+            ql: ql?,
+            qr: qr?,
+            qo: qo?,
+            qc: qc?,
+            s1: s1?,
+            s2: s2?,
+            s3: s3?,
+            ptau,
+        })
+    }
+}
+
+/// Reads the size & modulus of a field, and ensures that they match the given field.
+pub(crate) fn read_modulus<F: PrimeField>(reader: &mut SectionReader, name: &str) -> Result<()> {
+    let n8 = reader.read_u32()? as usize;
+    let modulus = BigUint::from_bytes_le(reader.read_bytes(n8)?);
+    let expected: BigUint = F::MODULUS.into();
+    if modulus != expected {
+        return Err(eyre!(
+            "only BN254 is supported, got {} field modulus {}",
+            name,
+            modulus
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reads a field element in Montgomery form.
+pub(crate) fn read_field<C: MontConfig<4>>(
+    reader: &mut SectionReader,
+) -> Result<Fp<MontBackend<C, 4>, 4>> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(reader.read_bytes(N8)?.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into()?);
+    }
+
+    let bigint = BigInteger256::new(limbs);
+    if bigint >= C::MODULUS {
+        return Err(eyre!("field element {} is not reduced", bigint));
+    }
+
+    // the element is already in Montgomery form, so it is not converted again
+    Ok(Fp::new_unchecked(bigint))
+}
+
+pub(crate) fn read_g1(reader: &mut SectionReader) -> Result<G1Affine> {
+    let x: Fq = read_field(reader)?;
+    let y: Fq = read_field(reader)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() {
+        return Err(eyre!("G1 point is not on the curve"));
+    }
+
+    Ok(p)
+}
+
+pub(crate) fn read_g2(reader: &mut SectionReader) -> Result<G2Affine> {
+    let x = Fq2::new(read_field(reader)?, read_field(reader)?);
+    let y = Fq2::new(read_field(reader)?, read_field(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(eyre!("G2 point is not in the subgroup"));
+    }
+
+    Ok(p)
+}
+
+/// Reads the `n` coefficients of a polynomial, and skips its `4n` evaluations afterwards.
+pub(crate) fn read_polynomial(reader: &mut SectionReader, n: usize) -> Result<DensePolynomial<Fr>> {
+    let coeffs = (0..n)
+        .map(|_| read_field(reader))
+        .collect::<Result<Vec<_>>>()?;
+    reader.read_bytes(4 * n * N8)?;
+
+    Ok(DensePolynomial::from_coefficients_vec(coeffs))
+}
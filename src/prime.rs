@@ -0,0 +1,183 @@
+use eyre::{eyre, Result};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path, str::FromStr};
+
+use crate::{arkworks::read_r1cs_prime, format::FileFormat, witness::parse_witness_prime};
+
+/// A prime that Circom can compile circuits over, i.e. the values of `circom --prime`.
+///
+/// The prime is written to the header of both `.r1cs` and `.wtns` files, so that the field
+/// (and thereby the backend) of a circuit can be detected before its elements are parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CircomPrime {
+    /// Scalar field of BN254, the default prime of Circom.
+    Bn128,
+    /// Scalar field of BLS12-381.
+    Bls12381,
+    /// Scalar field of BLS12-377.
+    Bls12377,
+    /// The 64-bit Goldilocks prime `2^64 - 2^32 + 1`.
+    Goldilocks,
+    /// Scalar field of Grumpkin, i.e. the base field of BN254.
+    Grumpkin,
+    /// Base field of Pallas, i.e. the scalar field of Vesta.
+    Pallas,
+    /// Base field of Vesta, i.e. the scalar field of Pallas.
+    Vesta,
+    /// Base field of secp256r1, i.e. the scalar field of secq256r1.
+    Secq256r1,
+}
+
+impl CircomPrime {
+    /// All primes known to Circom.
+    pub const ALL: [CircomPrime; 8] = [
+        Self::Bn128,
+        Self::Bls12381,
+        Self::Bls12377,
+        Self::Goldilocks,
+        Self::Grumpkin,
+        Self::Pallas,
+        Self::Vesta,
+        Self::Secq256r1,
+    ];
+
+    /// Name of the prime as given to `circom --prime`, which SnarkJS uses as the curve name as well.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bn128 => "bn128",
+            Self::Bls12381 => "bls12381",
+            Self::Bls12377 => "bls12377",
+            Self::Goldilocks => "goldilocks",
+            Self::Grumpkin => "grumpkin",
+            Self::Pallas => "pallas",
+            Self::Vesta => "vesta",
+            Self::Secq256r1 => "secq256r1",
+        }
+    }
+
+    /// The prime as a decimal string.
+    fn decimal(&self) -> &'static str {
+        match self {
+            Self::Bn128 => {
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            }
+            Self::Bls12381 => {
+                "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+            }
+            Self::Bls12377 => {
+                "8444461749428370424248824938781546531375899335154063827935233455917409239041"
+            }
+            Self::Goldilocks => "18446744069414584321",
+            Self::Grumpkin => {
+                "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+            }
+            Self::Pallas => {
+                "28948022309329048855892746252171976963363056481941560715954676764349967630337"
+            }
+            Self::Vesta => {
+                "28948022309329048855892746252171976963363056481941647379679742748393362948097"
+            }
+            Self::Secq256r1 => {
+                "115792089210356248762697446949407573530086143415290314195533631308867097853951"
+            }
+        }
+    }
+
+    /// The prime itself.
+    pub fn modulus(&self) -> BigUint {
+        BigUint::from_str(self.decimal()).unwrap()
+    }
+
+    /// Number of bytes of a field element, as Circom writes them (a multiple of 8).
+    pub fn n8(&self) -> usize {
+        self.modulus().bits().div_ceil(64) as usize * 8
+    }
+
+    /// Returns the Circom prime with the given modulus, or an error for an unsupported prime.
+    pub fn from_modulus(modulus: &BigUint) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.modulus() == *modulus)
+            .ok_or_else(|| eyre!("unsupported prime {}", modulus))
+    }
+
+    /// Reads the prime from the header of a binary witness (`.wtns`) or R1CS (`.r1cs`) file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        match FileFormat::detect(&bytes) {
+            Some(FileFormat::Wtns) => Ok(parse_witness_prime(&bytes)?),
+            Some(FileFormat::R1cs) => read_r1cs_prime(&bytes),
+            _ => Err(eyre!(
+                "could not read prime of {}, expected a witness or R1CS file",
+                path.display()
+            )),
+        }
+    }
+}
+
+impl fmt::Display for CircomPrime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for CircomPrime {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| eyre!("unknown prime {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ff::PrimeField;
+
+    #[test]
+    fn test_circom_primes() -> eyre::Result<()> {
+        // should match the scalar fields of the respective curves
+        let bn254: BigUint = ark_bn254::Fr::MODULUS.into();
+        let grumpkin: BigUint = ark_bn254::Fq::MODULUS.into();
+        let bls12_381: BigUint = ark_bls12_381::Fr::MODULUS.into();
+        assert_eq!(CircomPrime::from_modulus(&bn254)?, CircomPrime::Bn128);
+        assert_eq!(CircomPrime::from_modulus(&grumpkin)?, CircomPrime::Grumpkin);
+        assert_eq!(
+            CircomPrime::from_modulus(&bls12_381)?,
+            CircomPrime::Bls12381
+        );
+        assert_eq!(
+            CircomPrime::Goldilocks.modulus(),
+            BigUint::from((1u128 << 64) - (1u128 << 32) + 1)
+        );
+
+        for prime in CircomPrime::ALL {
+            assert_eq!(prime.name().parse::<CircomPrime>()?, prime);
+            assert_eq!(CircomPrime::from_modulus(&prime.modulus())?, prime);
+        }
+        assert_eq!(CircomPrime::Goldilocks.n8(), 8);
+        assert_eq!(CircomPrime::Bn128.n8(), 32);
+        assert_eq!(CircomPrime::Secq256r1.n8(), 32);
+
+        assert!(CircomPrime::from_modulus(&BigUint::from(7u32)).is_err());
+
+        let dir = Path::new("example/build/multiplier_30");
+        assert_eq!(
+            CircomPrime::from_file(dir.join("multiplier_30.r1cs"))?,
+            CircomPrime::Bn128
+        );
+        assert_eq!(
+            CircomPrime::from_file(dir.join("default/witness.wtns"))?,
+            CircomPrime::Bn128
+        );
+        assert!(CircomPrime::from_file(dir.join("groth16_pkey.zkey")).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,419 @@
+use eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::bench::{Stopwatch, STAGE_PROVE};
+use crate::format::FileFormat;
+use crate::prime::CircomPrime;
+use crate::snarkjs::{SnarkjsGroth16VerificationKey, SnarkjsOutput};
+
+/// A request to prove a circuit with an existing witness.
+///
+/// Which of the files are required depends on the backend, see [`ProverCapabilities`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProveRequest {
+    /// Witness file, either binary (`.wtns`) or JSON.
+    pub wtns_path: PathBuf,
+    /// Format of the witness file, which is detected from its contents if not given.
+    #[serde(default)]
+    pub wtns_format: Option<FileFormat>,
+    /// R1CS file of the circuit.
+    #[serde(default)]
+    pub r1cs_path: Option<PathBuf>,
+    /// Format of the R1CS file, which is detected from its contents if not given.
+    #[serde(default)]
+    pub r1cs_format: Option<FileFormat>,
+    /// Prover key file, e.g. a `.zkey` for the SnarkJS-compatible backends.
+    #[serde(default)]
+    pub pkey_path: Option<PathBuf>,
+    /// Device to prove on, for backends with hardware acceleration.
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Installation directory of the device backends, for backends with hardware acceleration.
+    #[serde(default)]
+    pub backend_dir: Option<PathBuf>,
+    /// Whether to include Solidity calldata in the output.
+    #[serde(default)]
+    pub calldata: bool,
+}
+
+impl ProveRequest {
+    /// Returns the R1CS path, or an error naming the backend that requires it.
+    pub fn require_r1cs(&self, backend: &str) -> Result<&PathBuf> {
+        self.r1cs_path
+            .as_ref()
+            .ok_or_else(|| eyre!("{} requires an R1CS file", backend))
+    }
+
+    /// Returns the prover key path, or an error naming the backend that requires it.
+    pub fn require_pkey(&self, backend: &str) -> Result<&PathBuf> {
+        self.pkey_path
+            .as_ref()
+            .ok_or_else(|| eyre!("{} requires a prover key", backend))
+    }
+}
+
+/// What a prover backend supports, and which files of a [`ProveRequest`] it requires.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProverCapabilities {
+    /// Primes (i.e. curves) that the backend can prove over.
+    pub curves: Vec<CircomPrime>,
+    /// Whether the R1CS file is required.
+    pub requires_r1cs: bool,
+    /// Whether the prover key is required.
+    pub requires_pkey: bool,
+    /// Devices that the backend can prove on, empty if the backend only runs on the CPU.
+    pub devices: Vec<&'static str>,
+}
+
+/// A Groth16 prover backend.
+///
+/// A prover is first loaded with the keys & circuit of a request, and can then prove many witnesses
+/// for them, so that backends can keep whatever they precompute from the keys.
+pub trait Prover: Send {
+    /// What the backend supports.
+    fn capabilities(&self) -> ProverCapabilities;
+
+    /// Loads the keys & circuit of the request, ignoring its witness.
+    fn load(&mut self, request: &ProveRequest) -> Result<()>;
+
+    /// Proves the witness of the request with the loaded keys & circuit.
+    fn prove(&mut self, request: &ProveRequest) -> Result<SnarkjsOutput>;
+
+    /// Proves like [`Prover::prove`], recording the time spent in each stage of the proof.
+    ///
+    /// Backends that can't tell their stages apart record the whole proof as [`STAGE_PROVE`].
+    fn prove_timed(
+        &mut self,
+        request: &ProveRequest,
+        stopwatch: &mut Stopwatch,
+    ) -> Result<SnarkjsOutput> {
+        let out = self.prove(request)?;
+        stopwatch.lap(STAGE_PROVE);
+        Ok(out)
+    }
+
+    /// Verifies a proof of this backend against a SnarkJS verification key.
+    fn verify(&self, vkey: &SnarkjsGroth16VerificationKey, output: &SnarkjsOutput) -> Result<bool>;
+}
+
+/// Creates a prover that is not loaded yet.
+pub type ProverFactory = fn() -> Box<dyn Prover>;
+
+/// Prover backends by name.
+///
+/// The default registry has all backends that the library is built with, i.e. `arkworks`, `lambdaworks`,
+/// and `icicle` if the `icicle` feature is enabled.
+#[derive(Clone)]
+pub struct ProverRegistry {
+    provers: BTreeMap<&'static str, ProverFactory>,
+}
+
+impl ProverRegistry {
+    /// Creates a registry without any backends.
+    pub fn empty() -> Self {
+        Self {
+            provers: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a backend with the given name, replacing any backend with the same name.
+    pub fn register(&mut self, name: &'static str, factory: ProverFactory) -> &mut Self {
+        self.provers.insert(name, factory);
+        self
+    }
+
+    /// Names of the registered backends.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.provers.keys().copied().collect()
+    }
+
+    /// Creates the prover of the backend with the given name.
+    pub fn get(&self, name: &str) -> Result<Box<dyn Prover>> {
+        self.provers
+            .get(name)
+            .map(|factory| factory())
+            .ok_or_else(|| {
+                eyre!(
+                    "unknown backend {}, expected one of {:?}",
+                    name,
+                    self.names()
+                )
+            })
+    }
+
+    /// Capabilities of each registered backend.
+    pub fn capabilities(&self) -> BTreeMap<&'static str, ProverCapabilities> {
+        self.provers
+            .iter()
+            .map(|(name, factory)| (*name, factory().capabilities()))
+            .collect()
+    }
+
+    /// Loads the backend with the given name for the request, and proves its witness.
+    pub fn prove(&self, name: &str, request: &ProveRequest) -> Result<SnarkjsOutput> {
+        let mut prover = self.get(name)?;
+        prover.load(request)?;
+
+        let out = prover.prove(request)?;
+        if request.calldata {
+            out.with_calldata()
+        } else {
+            Ok(out)
+        }
+    }
+}
+
+impl Default for ProverRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register("arkworks", || {
+                Box::<crate::arkworks::ArkworksProver>::default()
+            })
+            .register("lambdaworks", || {
+                Box::<crate::lambdaworks::LambdaworksProver>::default()
+            });
+        #[cfg(feature = "icicle")]
+        registry.register("icicle", || Box::<crate::icicle::IcicleProver>::default());
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::{read_snarkjs_json, SnarkjsPublicInputs};
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_prover_registry() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let request: ProveRequest = serde_json::from_value(serde_json::json!({
+            "wtnsPath": dir.join("default").join("witness").with_extension("wtns"),
+            "r1csPath": dir.join(CIRCUIT).with_extension("r1cs"),
+            "pkeyPath": dir.join("groth16_pkey").with_extension("zkey"),
+        }))?;
+
+        let registry = ProverRegistry::default();
+        assert!(registry.names().contains(&"arkworks"));
+        assert!(registry.get("foobar").is_err());
+
+        let mut prover = registry.get("arkworks")?;
+        assert!(prover.capabilities().requires_pkey);
+        prover.load(&request)?;
+
+        // the loaded prover should be reusable
+        let vkey: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(dir.join("groth16_vkey").with_extension("json"))?;
+        for _ in 0..2 {
+            let out = prover.prove(&request)?;
+            assert!(prover.verify(&vkey, &out)?);
+        }
+
+        // should fail to load without the files it requires
+        let err = registry
+            .prove("arkworks", &ProveRequest::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("requires"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prover_registry_bls12_381() -> eyre::Result<()> {
+        use ark_bls12_381::{Bls12_381, Fr};
+        use ark_circom::{circom::R1CS, CircomCircuit, CircomReduction};
+        use ark_ff::{BigInteger, One, PrimeField};
+        use ark_groth16::Groth16;
+        use ark_std::rand::thread_rng;
+
+        // a toy circuit for `c = a * b` with public `c`, over the wires `[1, c, a, b]`
+        let r1cs = R1CS::<Fr> {
+            num_inputs: 2,
+            num_aux: 2,
+            num_variables: 4,
+            constraints: vec![(
+                vec![(2, Fr::one())],
+                vec![(3, Fr::one())],
+                vec![(1, Fr::one())],
+            )],
+            wire_mapping: None,
+        };
+        let proving_key =
+            Groth16::<Bls12_381, CircomReduction>::generate_random_parameters_with_reduction(
+                CircomCircuit {
+                    r1cs,
+                    witness: None,
+                },
+                &mut thread_rng(),
+            )?;
+
+        // the zkey keeps `A` & `B`, along with the constraints of the public signals that SnarkJS appends
+        let one = Fr::one();
+        let pkey = crate::arkworks::write_proving_key(
+            &proving_key,
+            &[
+                (0, 0, 2, one),
+                (1, 0, 3, one),
+                (0, 1, 0, one),
+                (0, 2, 1, one),
+            ],
+        );
+        let modulus = Fr::MODULUS.to_bytes_le();
+        let mut header = (modulus.len() as u32).to_le_bytes().to_vec();
+        header.extend(&modulus);
+        header.extend(4u32.to_le_bytes());
+        let witness = [1u64, 33, 3, 11]
+            .iter()
+            .flat_map(|w| Fr::from(*w).into_bigint().to_bytes_le())
+            .collect();
+        let wtns = crate::binfile::write_bin_file(b"wtns", 2, &[(1, header), (2, witness)]);
+
+        let dir = std::env::temp_dir().join("circomkit-ffi-registry-bls12381");
+        std::fs::create_dir_all(&dir)?;
+        let wtns_path = dir.join("witness").with_extension("wtns");
+        let pkey_path = dir.join("groth16_pkey").with_extension("zkey");
+        std::fs::write(&wtns_path, wtns)?;
+        std::fs::write(&pkey_path, pkey)?;
+
+        // without an R1CS, the constraints are read from the zkey
+        let request = ProveRequest {
+            wtns_path,
+            pkey_path: Some(pkey_path),
+            ..Default::default()
+        };
+
+        let registry = ProverRegistry::default();
+        let mut prover = registry.get("arkworks")?;
+        assert!(prover
+            .capabilities()
+            .curves
+            .contains(&CircomPrime::Bls12381));
+        prover.load(&request)?;
+
+        // the curve of the verification key should be used to verify, instead of BN254
+        let vkey = SnarkjsGroth16VerificationKey::from(&proving_key.vk);
+        assert_eq!(vkey.curve, "bls12381");
+        let out = prover.prove(&request)?;
+        assert_eq!(out.proof.curve, "bls12381");
+        assert_eq!(out.public_signals.0, ["33"]);
+        assert!(prover.verify(&vkey, &out)?);
+
+        // a verification key over BN254 should not accept it
+        let bn254_vkey: SnarkjsGroth16VerificationKey = read_snarkjs_json(
+            Path::new("example/build")
+                .join(CIRCUIT)
+                .join("groth16_vkey")
+                .with_extension("json"),
+        )?;
+        assert!(prover.verify(&bn254_vkey, &out).is_err());
+
+        Ok(())
+    }
+
+    /// Proves the default input of each circuit under `example/build` with every backend in the registry that
+    /// supports its prime, verifying each proof natively and checking its public signals against those of SnarkJS.
+    ///
+    /// Run with `--features icicle` to include ICICLE, which reads its backends from `ICICLE_BACKEND_INSTALL_DIR`.
+    #[test]
+    fn test_backends_agree() -> eyre::Result<()> {
+        let registry = ProverRegistry::default();
+
+        let mut n_compared = 0;
+        for entry in std::fs::read_dir("example/build")? {
+            let dir = entry?.path();
+            let Some(circuit) = dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let wtns_path = dir
+                .join("default") // input name
+                .join("witness")
+                .with_extension("wtns");
+            if !wtns_path.exists() {
+                continue;
+            }
+            let prime = crate::witness::parse_witness_prime(&std::fs::read(&wtns_path)?)?;
+            let expected: SnarkjsPublicInputs =
+                read_snarkjs_json(dir.join("default").join("public.json"))?;
+
+            let mut backends = Vec::new();
+            for (backend, capabilities) in registry.capabilities() {
+                // e.g. Lambdaworks only proves circuits compiled with `--prime bls12381`
+                if !capabilities.curves.contains(&prime) {
+                    continue;
+                }
+
+                let (request, vkey) = differential_request(backend, &dir, circuit, &wtns_path)?;
+                let mut prover = registry.get(backend)?;
+                prover.load(&request)?;
+
+                let out = prover.prove(&request)?;
+                assert!(
+                    prover.verify(&vkey, &out)?,
+                    "{} proof of {} is not valid",
+                    backend,
+                    circuit
+                );
+                assert_eq!(
+                    out.public_signals.0, expected.0,
+                    "{} disagrees with SnarkJS on the public signals of {}",
+                    backend, circuit
+                );
+                backends.push(backend);
+            }
+
+            if backends.is_empty() {
+                eprintln!("skipping {}: no backend supports prime {}", circuit, prime);
+            } else if backends.len() > 1 {
+                n_compared += 1;
+            }
+        }
+        assert!(
+            n_compared > 0,
+            "no circuit in example/build was proven by more than one backend"
+        );
+
+        Ok(())
+    }
+
+    /// Returns the request of a backend for a circuit, along with the verification key to check its proofs.
+    fn differential_request(
+        backend: &str,
+        dir: &Path,
+        circuit: &str,
+        wtns_path: &Path,
+    ) -> eyre::Result<(ProveRequest, SnarkjsGroth16VerificationKey)> {
+        // Lambdaworks proves over BLS12-381, so it can't use the SnarkJS keys and needs a setup of its own
+        if backend == "lambdaworks" {
+            let r1cs_path = dir.join(circuit).with_extension("r1cs.json");
+            let keys_dir = std::env::temp_dir()
+                .join("circomkit-ffi-differential")
+                .join(circuit);
+            let vkey = crate::lambdaworks::setup(&r1cs_path, &keys_dir)?;
+
+            let request = ProveRequest {
+                wtns_path: wtns_path.to_path_buf(),
+                r1cs_path: Some(r1cs_path),
+                pkey_path: Some(keys_dir.join(crate::lambdaworks::PKEY_FILENAME)),
+                ..Default::default()
+            };
+            return Ok((request, vkey));
+        }
+
+        let request = ProveRequest {
+            wtns_path: wtns_path.to_path_buf(),
+            r1cs_path: Some(dir.join(circuit).with_extension("r1cs")),
+            pkey_path: Some(dir.join("groth16_pkey").with_extension("zkey")),
+            ..Default::default()
+        };
+        let vkey: SnarkjsGroth16VerificationKey =
+            read_snarkjs_json(dir.join("groth16_vkey").with_extension("json"))?;
+        Ok((request, vkey))
+    }
+}
@@ -1,15 +1,24 @@
 use std::{ffi::OsStr, path::Path};
 
-use serde::{Deserialize, Serialize};
+use eyre::Context;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+
+use crate::solidity::SolidityCalldata;
 
 /// A Groth16 proof object, similar to how SnarkJS exports it.
+///
+/// Points are given in affine coordinates; when deserializing, the projective coordinates
+/// that SnarkJS exports are accepted as well as long as they are normalized.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnarkjsGroth16Proof {
     /// A point in G1
+    #[serde(deserialize_with = "deserialize_affine")]
     pub pi_a: [String; 2],
     /// A point in G2
+    #[serde(deserialize_with = "deserialize_affine")]
     pub pi_b: [[String; 2]; 2],
     /// A point in G1
+    #[serde(deserialize_with = "deserialize_affine")]
     pub pi_c: [String; 2],
     /// Protocol name, should be "groth16"
     pub protocol: String,
@@ -23,6 +32,153 @@ impl std::fmt::Display for SnarkjsGroth16Proof {
     }
 }
 
+/// A Groth16 verification key object, similar to how SnarkJS exports it.
+///
+/// Points are given in projective coordinates, as SnarkJS does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnarkjsGroth16VerificationKey {
+    /// Protocol name, should be "groth16"
+    pub protocol: String,
+    /// Curve name, should be "bls12381" or "bn254 / bn128 / altbn128"
+    pub curve: String,
+    /// Number of public signals
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    /// A point in G1
+    pub vk_alpha_1: [String; 3],
+    /// A point in G2
+    pub vk_beta_2: [[String; 2]; 3],
+    /// A point in G2
+    pub vk_gamma_2: [[String; 2]; 3],
+    /// A point in G2
+    pub vk_delta_2: [[String; 2]; 3],
+    /// The pairing of `vk_alpha_1` and `vk_beta_2`, an element of the target group
+    pub vk_alphabeta_12: [[[String; 2]; 3]; 2],
+    /// Points in G1 for each public signal, along with the constant term at the start
+    #[serde(rename = "IC")]
+    pub ic: Vec<[String; 3]>,
+}
+
+impl std::fmt::Display for SnarkjsGroth16VerificationKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+/// A PLONK proof object, similar to how SnarkJS exports it.
+///
+/// Points are given in projective coordinates, as SnarkJS does.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnarkjsPlonkProof {
+    /// Commitment to the `A` wires
+    #[serde(rename = "A")]
+    pub a: [String; 3],
+    /// Commitment to the `B` wires
+    #[serde(rename = "B")]
+    pub b: [String; 3],
+    /// Commitment to the `C` wires
+    #[serde(rename = "C")]
+    pub c: [String; 3],
+    /// Commitment to the permutation polynomial
+    #[serde(rename = "Z")]
+    pub z: [String; 3],
+    /// Commitment to the low part of the quotient polynomial
+    #[serde(rename = "T1")]
+    pub t1: [String; 3],
+    /// Commitment to the middle part of the quotient polynomial
+    #[serde(rename = "T2")]
+    pub t2: [String; 3],
+    /// Commitment to the high part of the quotient polynomial
+    #[serde(rename = "T3")]
+    pub t3: [String; 3],
+    /// Opening proof at the evaluation challenge
+    #[serde(rename = "Wxi")]
+    pub wxi: [String; 3],
+    /// Opening proof at the shifted evaluation challenge
+    #[serde(rename = "Wxiw")]
+    pub wxiw: [String; 3],
+    /// Evaluation of `A` at the challenge
+    pub eval_a: String,
+    /// Evaluation of `B` at the challenge
+    pub eval_b: String,
+    /// Evaluation of `C` at the challenge
+    pub eval_c: String,
+    /// Evaluation of `S1` at the challenge
+    pub eval_s1: String,
+    /// Evaluation of `S2` at the challenge
+    pub eval_s2: String,
+    /// Evaluation of `Z` at the shifted challenge
+    pub eval_zw: String,
+    /// Protocol name, should be "plonk"
+    pub protocol: String,
+    /// Curve name, should be "bn128"
+    pub curve: String,
+}
+
+impl std::fmt::Display for SnarkjsPlonkProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+/// FFLONK proof object, similar to how SnarkJS exports it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnarkjsFflonkProof {
+    /// Commitments of the proof
+    pub polynomials: SnarkjsFflonkPolynomials,
+    /// Evaluations of the proof
+    pub evaluations: SnarkjsFflonkEvaluations,
+    /// Protocol name, should be "fflonk"
+    pub protocol: String,
+    /// Curve name, should be "bn128"
+    pub curve: String,
+}
+
+impl std::fmt::Display for SnarkjsFflonkProof {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string_pretty(&self).unwrap())
+    }
+}
+
+/// Commitments of a FFLONK proof.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SnarkjsFflonkPolynomials {
+    /// Commitment to the wires & the gate quotient
+    pub c1: [String; 3],
+    /// Commitment to the permutation polynomial & its quotients
+    pub c2: [String; 3],
+    /// First opening proof
+    pub w1: [String; 3],
+    /// Second opening proof
+    pub w2: [String; 3],
+}
+
+/// Evaluations of a FFLONK proof, at the challenge unless stated otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnarkjsFflonkEvaluations {
+    pub ql: String,
+    pub qr: String,
+    pub qm: String,
+    pub qo: String,
+    pub qc: String,
+    pub s1: String,
+    pub s2: String,
+    pub s3: String,
+    pub a: String,
+    pub b: String,
+    pub c: String,
+    pub z: String,
+    /// Evaluation of `Z` at the shifted challenge
+    pub zw: String,
+    /// Evaluation of `T1` at the shifted challenge
+    pub t1w: String,
+    /// Evaluation of `T2` at the shifted challenge
+    pub t2w: String,
+    /// Inverse of the denominators of the verifier, to save it the inversions
+    pub inv: String,
+}
+
 /// Public signals object, similar to how SnarkJS exports it.
 ///
 /// Each signal is a string that should be parsed into a `BigInt`.
@@ -35,14 +191,20 @@ impl std::fmt::Display for SnarkjsPublicInputs {
     }
 }
 
+/// The output of a prover, i.e. the proof along with the public signals.
+///
+/// The proof is a Groth16 proof by default, see [`SnarkjsPlonkOutput`] and [`SnarkjsFflonkOutput`] for the others.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SnarkjsOutput {
-    pub proof: SnarkjsGroth16Proof,
+pub struct SnarkjsOutput<P = SnarkjsGroth16Proof> {
+    pub proof: P,
     pub public_signals: SnarkjsPublicInputs,
+    /// Solidity calldata for the proof & public signals, only if requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calldata: Option<SolidityCalldata>,
 }
 
-impl std::fmt::Display for SnarkjsOutput {
+impl<P: Serialize> std::fmt::Display for SnarkjsOutput<P> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -53,23 +215,101 @@ impl std::fmt::Display for SnarkjsOutput {
     }
 }
 
+/// The output of a PLONK prover.
+pub type SnarkjsPlonkOutput = SnarkjsOutput<SnarkjsPlonkProof>;
+
+/// The output of a FFLONK prover.
+pub type SnarkjsFflonkOutput = SnarkjsOutput<SnarkjsFflonkProof>;
+
+/// Deserializes a point given in affine coordinates, or in projective coordinates with `z` being one,
+/// into its affine coordinates.
+///
+/// This is required because SnarkJS exports points in projective coordinates, e.g. `[x, y, "1"]` for G1.
+fn deserialize_affine<'de, D, T>(deserializer: D) -> Result<[T; 2], D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + ProjectiveOne,
+{
+    let mut coords = Vec::<T>::deserialize(deserializer)?;
+    match coords.len() {
+        2 => {}
+        3 if coords[2].is_projective_one() => {
+            coords.pop();
+        }
+        3 => {
+            return Err(serde::de::Error::custom(
+                "expected a normalized point with z coordinate equal to one",
+            ))
+        }
+        n => return Err(serde::de::Error::invalid_length(n, &"2 or 3 coordinates")),
+    }
+
+    Ok(coords.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+/// A coordinate that can be checked to be one, used for the `z` coordinate in projective points.
+trait ProjectiveOne {
+    fn is_projective_one(&self) -> bool;
+}
+
+impl ProjectiveOne for String {
+    fn is_projective_one(&self) -> bool {
+        self == "1"
+    }
+}
+
+impl ProjectiveOne for [String; 2] {
+    fn is_projective_one(&self) -> bool {
+        self[0] == "1" && self[1] == "0"
+    }
+}
+
+/// Reads a JSON file exported by SnarkJS, such as a proof, public signals or a verification key.
+pub fn read_snarkjs_json<T: DeserializeOwned>(path: impl AsRef<Path>) -> eyre::Result<T> {
+    let path = path.as_ref();
+    let bytes =
+        std::fs::read(path).wrap_err_with(|| format!("could not read {}", path.display()))?;
+
+    serde_json::from_slice(&bytes).wrap_err_with(|| format!("could not parse {}", path.display()))
+}
+
+/// A proof object of SnarkJS, along with the name of its protocol.
+pub trait SnarkjsProof: Serialize {
+    /// Name of the protocol, as used by the commands of SnarkJS, e.g. `snarkjs plonk verify`.
+    const PROTOCOL: &'static str;
+}
+
+impl SnarkjsProof for SnarkjsGroth16Proof {
+    const PROTOCOL: &'static str = "groth16";
+}
+
+impl SnarkjsProof for SnarkjsPlonkProof {
+    const PROTOCOL: &'static str = "plonk";
+}
+
+impl SnarkjsProof for SnarkjsFflonkProof {
+    const PROTOCOL: &'static str = "fflonk";
+}
+
 /// Executes the following command:
 ///
 /// ```sh
-/// snarkjs g16v [verification_key.json] [public.json] [proof.json]
+/// snarkjs [protocol] verify [verification_key.json] [public.json] [proof.json]
 /// ```
 ///
 /// If the process fails, this may panic.
 ///
 /// Requires `snarkjs` to be installed globally.
-pub fn snarkjs_verify_groth16(
+pub fn snarkjs_verify(
+    protocol: &str,
     verification_key_path: impl AsRef<OsStr>,
     proof_path: impl AsRef<OsStr>,
     public_signals_path: impl AsRef<OsStr>,
 ) -> std::io::Result<std::process::Output> {
     std::process::Command::new("snarkjs")
         .args([
-            OsStr::new("g16v"), // short for "groth16 verify"
+            OsStr::new(protocol),
+            OsStr::new("verify"),
             verification_key_path.as_ref(),
             public_signals_path.as_ref(),
             proof_path.as_ref(),
@@ -77,14 +317,36 @@ pub fn snarkjs_verify_groth16(
         .output()
 }
 
-/// Checks the output of `snarkjs` by verifying the proof.
+/// Executes the following command:
+///
+/// ```sh
+/// snarkjs g16v [verification_key.json] [public.json] [proof.json]
+/// ```
+///
+/// If the process fails, this may panic.
+///
+/// Requires `snarkjs` to be installed globally.
+pub fn snarkjs_verify_groth16(
+    verification_key_path: impl AsRef<OsStr>,
+    proof_path: impl AsRef<OsStr>,
+    public_signals_path: impl AsRef<OsStr>,
+) -> std::io::Result<std::process::Output> {
+    snarkjs_verify(
+        SnarkjsGroth16Proof::PROTOCOL,
+        verification_key_path,
+        proof_path,
+        public_signals_path,
+    )
+}
+
+/// Checks the output of `snarkjs` by verifying the proof with the command of its protocol.
 ///
 /// - If the verification fails, this function will panic.
 /// - The proof is saved as `{prefix}_{circuit_name}_proof.json`
 /// - The public signals are saved as `{prefix}_{circuit_name}_public.json`
-/// - The verification key expected from the disk as `groth16_vkey.json`
-pub fn check_snarkjs_output(
-    snarkjs_out: &SnarkjsOutput,
+/// - The verification key expected from the disk as `{protocol}_vkey.json`, e.g. `groth16_vkey.json`
+pub fn check_snarkjs_output<P: SnarkjsProof>(
+    snarkjs_out: &SnarkjsOutput<P>,
     dir: &Path,
     circuit_name: &str,
     prefix: &str,
@@ -95,7 +357,9 @@ pub fn check_snarkjs_output(
     let public_output_path = dir
         .join(format!("{}_{}_public", prefix, circuit_name))
         .with_extension("json");
-    let vkey_path = dir.join("groth16_vkey").with_extension("json");
+    let vkey_path = dir
+        .join(format!("{}_vkey", P::PROTOCOL))
+        .with_extension("json");
 
     std::fs::write(
         &proof_output_path,
@@ -105,7 +369,12 @@ pub fn check_snarkjs_output(
         &public_output_path,
         serde_json::to_string_pretty(&snarkjs_out.public_signals).unwrap(),
     )?;
-    let output = snarkjs_verify_groth16(&vkey_path, &proof_output_path, &public_output_path)?;
+    let output = snarkjs_verify(
+        P::PROTOCOL,
+        &vkey_path,
+        &proof_output_path,
+        &public_output_path,
+    )?;
     assert!(output.status.success());
 
     Ok(())
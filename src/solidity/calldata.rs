@@ -0,0 +1,144 @@
+use eyre::{eyre, Result};
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+
+use crate::snarkjs::{SnarkjsGroth16Proof, SnarkjsOutput, SnarkjsPublicInputs};
+
+/// Solidity calldata for the `verifyProof` function of a Groth16 verifier contract.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolidityCalldata {
+    /// The arguments as printed by `snarkjs zkey export soliditycalldata`.
+    pub text: String,
+    /// ABI-encoded call to `verifyProof`, including the function selector, as a `0x`-prefixed hex string.
+    pub abi: String,
+}
+
+impl SolidityCalldata {
+    /// Creates the calldata for the given proof & public signals.
+    ///
+    /// The coordinates of `pi_b` are swapped w.r.t SnarkJS proof, as the pairing precompile
+    /// expects G2 points as `[x.c1, x.c0], [y.c1, y.c0]`, see EIP-197.
+    pub fn new(proof: &SnarkjsGroth16Proof, public_signals: &SnarkjsPublicInputs) -> Result<Self> {
+        if !matches!(proof.curve.as_str(), "bn128" | "bn254" | "altbn128") {
+            return Err(eyre!(
+                "calldata is only supported for BN254, got {}",
+                proof.curve
+            ));
+        }
+
+        let [a, b, c, inputs] = [
+            proof.pi_a.to_vec(),
+            vec![
+                proof.pi_b[0][1].clone(),
+                proof.pi_b[0][0].clone(),
+                proof.pi_b[1][1].clone(),
+                proof.pi_b[1][0].clone(),
+            ],
+            proof.pi_c.to_vec(),
+            public_signals.0.clone(),
+        ]
+        .map(|values| {
+            values
+                .iter()
+                .map(|v| to_word(v))
+                .collect::<Result<Vec<_>>>()
+        });
+        let (a, b, c, inputs) = (a?, b?, c?, inputs?);
+
+        let hex_list = |words: &[[u8; 32]]| {
+            words
+                .iter()
+                .map(|w| format!("\"0x{}\"", hex::encode(w)))
+                .collect::<Vec<_>>()
+        };
+        let [a_hex, b_hex, c_hex, inputs_hex] = [&a, &b, &c, &inputs].map(|w| hex_list(w));
+        let text = format!(
+            "[{}],[[{}],[{}]],[{}],[{}]",
+            a_hex.join(", "),
+            b_hex[..2].join(", "),
+            b_hex[2..].join(", "),
+            c_hex.join(", "),
+            inputs_hex.join(",")
+        );
+
+        // all arguments are static, so they are simply concatenated after the selector
+        let signature = format!(
+            "verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[{}])",
+            inputs.len()
+        );
+        let mut abi = Keccak256::digest(signature.as_bytes())[..4].to_vec();
+        for word in a.iter().chain(&b).chain(&c).chain(&inputs) {
+            abi.extend_from_slice(word);
+        }
+
+        Ok(Self {
+            text,
+            abi: format!("0x{}", hex::encode(abi)),
+        })
+    }
+}
+
+impl SnarkjsOutput {
+    /// Returns the Solidity calldata for this proof & public signals.
+    #[inline]
+    pub fn to_calldata(&self) -> Result<SolidityCalldata> {
+        SolidityCalldata::new(&self.proof, &self.public_signals)
+    }
+
+    /// Attaches the Solidity calldata to this output.
+    #[inline]
+    pub fn with_calldata(mut self) -> Result<Self> {
+        self.calldata = Some(self.to_calldata()?);
+        Ok(self)
+    }
+}
+
+/// Converts a decimal string to a 32-byte big-endian word.
+fn to_word(value: &str) -> Result<[u8; 32]> {
+    let bytes = value
+        .parse::<BigUint>()
+        .map_err(|e| eyre!("could not parse {} as a decimal: {}", value, e))?
+        .to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(eyre!("{} does not fit in 256 bits", value));
+    }
+
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::read_snarkjs_json;
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_solidity_calldata() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT).join("default");
+        let proof: SnarkjsGroth16Proof = read_snarkjs_json(dir.join("groth16_proof.json"))?;
+        let public_signals: SnarkjsPublicInputs = read_snarkjs_json(dir.join("public.json"))?;
+
+        let calldata = SolidityCalldata::new(&proof, &public_signals)?;
+        assert_eq!(
+            calldata.abi.len(),
+            2 + 2 * (4 + 32 * (8 + public_signals.0.len()))
+        );
+
+        // pi_b coordinates should be swapped
+        let words = calldata.text.split(", ").collect::<Vec<_>>();
+        let b01 = format!("{:064x}", proof.pi_b[0][1].parse::<BigUint>()?);
+        assert!(words[1].ends_with(&format!("[[\"0x{}\"", b01)));
+
+        // public signal `1073741824` is `2^30`
+        assert!(calldata
+            .text
+            .ends_with("[\"0x0000000000000000000000000000000000000000000000000000000040000000\"]"));
+
+        Ok(())
+    }
+}
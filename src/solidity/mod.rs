@@ -0,0 +1,123 @@
+use eyre::{eyre, Result};
+
+use crate::snarkjs::SnarkjsGroth16VerificationKey;
+
+mod calldata;
+pub use calldata::SolidityCalldata;
+
+/// The Groth16 verifier template, adapted from SnarkJS's `verifier_groth16.sol.ejs`.
+const GROTH16_VERIFIER_TEMPLATE: &str = include_str!("verifier_groth16.sol");
+
+/// Renders a Solidity verifier contract for Groth16 proofs over BN254.
+///
+/// The contract has the same ABI as the one exported by `snarkjs zkey export solidityverifier`, that is:
+///
+/// ```solidity
+/// function verifyProof(uint[2] _pA, uint[2][2] _pB, uint[2] _pC, uint[N] _pubSignals) public view returns (bool)
+/// ```
+pub fn groth16_verifier(vkey: &SnarkjsGroth16VerificationKey) -> Result<String> {
+    if !matches!(vkey.curve.as_str(), "bn128" | "bn254" | "altbn128") {
+        return Err(eyre!(
+            "verifier contracts are only supported for BN254, got {}",
+            vkey.curve
+        ));
+    }
+    if vkey.ic.len() != vkey.n_public + 1 {
+        return Err(eyre!(
+            "expected {} IC points for {} public signals, got {}",
+            vkey.n_public + 1,
+            vkey.n_public,
+            vkey.ic.len()
+        ));
+    }
+
+    // G2 coordinates are given as `c1, c0` to the pairing precompile, see EIP-197
+    let mut contract = GROTH16_VERIFIER_TEMPLATE
+        .replace("{{alphax}}", &vkey.vk_alpha_1[0])
+        .replace("{{alphay}}", &vkey.vk_alpha_1[1]);
+    for (name, point) in [
+        ("beta", &vkey.vk_beta_2),
+        ("gamma", &vkey.vk_gamma_2),
+        ("delta", &vkey.vk_delta_2),
+    ] {
+        contract = contract
+            .replace(&format!("{{{{{}x1}}}}", name), &point[0][1])
+            .replace(&format!("{{{{{}x2}}}}", name), &point[0][0])
+            .replace(&format!("{{{{{}y1}}}}", name), &point[1][1])
+            .replace(&format!("{{{{{}y2}}}}", name), &point[1][0]);
+    }
+
+    let ic_constants = vkey
+        .ic
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            format!(
+                "    uint256 constant IC{i}x = {};\n    uint256 constant IC{i}y = {};\n\n",
+                p[0], p[1]
+            )
+        })
+        .collect::<String>();
+    let ic_mul_acc = (1..=vkey.n_public)
+        .map(|i| {
+            format!(
+                "                g1_mulAccC(_pVk, IC{i}x, IC{i}y, calldataload(add(pubSignals, {})))\n",
+                (i - 1) * 32
+            )
+        })
+        .collect::<String>();
+    let check_fields = (0..vkey.n_public)
+        .map(|i| {
+            format!(
+                "            checkField(calldataload(add(_pubSignals, {})))\n",
+                i * 32
+            )
+        })
+        .collect::<String>();
+
+    Ok(contract
+        .replace("{{ic_constants}}", &ic_constants)
+        .replace("{{ic_mul_acc}}", &ic_mul_acc)
+        .replace("{{check_fields}}", &check_fields)
+        .replace("{{n_public}}", &vkey.n_public.to_string()))
+}
+
+/// Renders a Solidity verifier contract for Groth16 proofs over BN254, from an Arkworks verifying key.
+///
+/// See [`groth16_verifier`] for details.
+pub fn groth16_verifier_from_arkworks(
+    vk: &ark_groth16::VerifyingKey<ark_bn254::Bn254>,
+) -> Result<String> {
+    groth16_verifier(&SnarkjsGroth16VerificationKey::from(vk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snarkjs::read_snarkjs_json;
+    use std::path::Path;
+
+    const CIRCUIT: &str = "multiplier_30";
+
+    #[test]
+    fn test_groth16_verifier() -> eyre::Result<()> {
+        let dir = Path::new("example/build").join(CIRCUIT);
+        let vkey_path = dir.join("groth16_vkey").with_extension("json");
+        let vkey: SnarkjsGroth16VerificationKey = read_snarkjs_json(vkey_path)?;
+
+        let contract = groth16_verifier(&vkey)?;
+        assert!(!contract.contains("{{"));
+        assert!(contract.contains("uint[1] calldata _pubSignals"));
+        assert!(contract.contains(&format!("uint256 constant IC1y = {};", vkey.ic[1][1])));
+
+        // should be the same when going through Arkworks
+        let ark_vk = ark_groth16::VerifyingKey::<ark_bn254::Bn254>::try_from(&vkey)?;
+        assert_eq!(groth16_verifier_from_arkworks(&ark_vk)?, contract);
+        assert_eq!(
+            SnarkjsGroth16VerificationKey::from(&ark_vk).vk_alphabeta_12,
+            vkey.vk_alphabeta_12
+        );
+
+        Ok(())
+    }
+}
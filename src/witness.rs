@@ -1,86 +1,251 @@
-use num_bigint::BigInt;
+use num_bigint::BigUint;
+use serde_json::value::RawValue;
 use std::io;
 
-/// Parses a witness file as generated by witness calculators of Circom.
+use crate::binfile::BinFile;
+use crate::prime::CircomPrime;
+
+/// Parses a witness file as generated by witness calculators of Circom,
+/// ensuring that it is over the `expected` prime.
 ///
 /// Taken from my PR at: [zkmopro/witnesscalc_adapter](https://github.com/zkmopro/witnesscalc_adapter/pull/11)
 /// Note that we use little-endian byte order for all values here.
 pub fn parse_witness_to_elems<T>(
     buffer: &[u8],
+    expected: CircomPrime,
     chunk_to_elem: impl Fn(&[u8]) -> T,
 ) -> io::Result<Vec<T>> {
-    let mut pos = 0;
+    let (prime, elems) = parse_witness(buffer)?;
+    if prime != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Witness is over prime {}, expected {}.", prime, expected),
+        ));
+    }
+
+    Ok(elems.chunks(prime.n8()).map(chunk_to_elem).collect())
+}
+
+/// Reads the prime of a witness file, i.e. the `--prime` its circuit was compiled with.
+pub fn parse_witness_prime(buffer: &[u8]) -> io::Result<CircomPrime> {
+    parse_witness(buffer).map(|(prime, _)| prime)
+}
+
+/// Parses a witness JSON file, i.e. the array of values exported by `snarkjs wtns export json`,
+/// into values below the given modulus.
+///
+/// Values can be decimal strings, JSON numbers or `0x` prefixed hex strings, and negative values are
+/// reduced modulo the prime. The error points to the index of the first value that is not valid.
+pub fn parse_witness_json(json: &str, modulus: &BigUint) -> io::Result<Vec<BigUint>> {
+    // values are kept raw, so that numbers of any size are read without losing precision
+    let values: Vec<&RawValue> = serde_json::from_str(json).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid witness JSON: {}", e),
+        )
+    })?;
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            parse_witness_value(value.get(), modulus).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "Invalid witness value {} at index {}: {}",
+                        value.get(),
+                        i,
+                        e
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parses a single value of a witness JSON file, given as its raw JSON.
+fn parse_witness_value(raw: &str, modulus: &BigUint) -> Result<BigUint, &'static str> {
+    let value = if raw.starts_with('"') {
+        serde_json::from_str::<String>(raw).map_err(|_| "not a string")?
+    } else {
+        raw.to_string()
+    };
+
+    let (negative, digits) = match value.trim().strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value.trim()),
+    };
+    let magnitude = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(digits.as_bytes(), 10),
+    }
+    .ok_or("not an integer")?;
+
+    if &magnitude >= modulus {
+        return Err("not below the field modulus");
+    }
+
+    if negative && magnitude != BigUint::ZERO {
+        Ok(modulus - magnitude)
+    } else {
+        Ok(magnitude)
+    }
+}
+
+/// Parses a witness file into its prime & the bytes of its elements.
+///
+/// The file has two sections:
+/// - `1`: the header, i.e. `n8` the number of bytes per element (`u32`), the prime (`n8` bytes)
+///   and the number of witness values (`u32`)
+/// - `2`: the witness values, `n8` bytes each
+fn parse_witness(buffer: &[u8]) -> io::Result<(CircomPrime, &[u8])> {
+    let invalid = |e: eyre::Report| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid witness file: {}.", e),
+        )
+    };
+    let file = BinFile::parse(buffer, b"wtns").map_err(invalid)?;
 
-    // skip format bytes (4 bytes)
-    // ensure that this says "wtns" in ASCII
-    if &buffer[pos..pos + 4] != b"wtns" {
+    let mut header = file
+        .section(1)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Witness header not found."))?;
+    let n8 = header.read_u32().map_err(invalid)? as usize;
+    let q = BigUint::from_bytes_le(header.read_bytes(n8).map_err(invalid)?);
+    let prime = CircomPrime::from_modulus(&q)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    if prime.n8() != n8 {
         return Err(io::Error::new(
             io::ErrorKind::InvalidData,
-            "Invalid witness file format.",
+            format!("Invalid element size {} for prime {}.", n8, prime),
         ));
     }
-    pos += 4;
-
-    // read version (4 bytes)
-    let _version = u32::from_le_bytes(buffer[pos..pos + 4].try_into().unwrap());
-    pos += 4;
-
-    // read number of sections (4 bytes)
-    let n_sections = u32::from_le_bytes(buffer[pos..pos + 4].try_into().unwrap());
-    pos += 4;
-
-    // number of 8 bit integers per field element
-    let mut n8 = 0;
-
-    // iterate through sections to find section_id = 2 (witness data)
-    //
-    // each section has:
-    // - section id (4 bytes)
-    // - section length (8 bytes)
-    for _ in 0..n_sections {
-        let section_id = u32::from_le_bytes(buffer[pos..pos + 4].try_into().unwrap());
-        pos += 4;
-
-        let section_length = u64::from_le_bytes(buffer[pos..pos + 8].try_into().unwrap());
-        pos += 8;
-
-        match section_id {
-            // [section 1]
-            // - `n8` number of 8 bit integers per field element (4 bytes / u32)
-            // - the field `q` value (32 bytes)
-            // - number of witness values (4 bytes / `u32`)
-            1 => {
-                n8 = u32::from_le_bytes(buffer[pos..pos + 4].try_into().unwrap());
-                pos += 4;
-
-                let _q = BigInt::from_signed_bytes_le(&buffer[pos..pos + 32]);
-                pos += 32;
-
-                let _n_witness_values =
-                    u32::from_le_bytes(buffer[pos..pos + 4].try_into().unwrap());
-                pos += 4;
-            }
-
-            // [section 2]
-            // - witness data (`n8` bytes per element, section_length bytes total)
-            2 => {
-                // read & convert witness bytes into field elements
-                let bigints: Vec<T> = buffer[pos..pos + section_length as usize]
-                    .chunks(usize::try_from(n8).unwrap())
-                    .map(chunk_to_elem)
-                    .collect();
-
-                return Ok(bigints);
-            }
-            // skip any other section
-            _ => {
-                pos += section_length as usize;
-            }
+    let _n_witness_values = header.read_u32().map_err(invalid)?;
+
+    let mut data = file
+        .section(2)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Witness section not found."))?;
+    let elems = data.read_bytes(data.remaining()).map_err(invalid)?;
+    if elems.len() % n8 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Witness section of {} bytes is not made of {}-byte elements.",
+                elems.len(),
+                n8
+            ),
+        ));
+    }
+
+    Ok((prime, elems))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_witness_prime() -> io::Result<()> {
+        let wtns_path = Path::new("example/build/multiplier_30/default/witness.wtns");
+        let wtns_data = std::fs::read(wtns_path)?;
+        assert_eq!(parse_witness_prime(&wtns_data)?, CircomPrime::Bn128);
+
+        let elems = parse_witness_to_elems(&wtns_data, CircomPrime::Bn128, BigUint::from_bytes_le)?;
+        assert_eq!(elems[0], BigUint::from(1u32)); // constant
+
+        // should not parse the witness over another prime
+        assert!(parse_witness_to_elems(&wtns_data, CircomPrime::Bls12381, |_| ()).is_err());
+
+        // a Goldilocks witness has 8-byte elements
+        let n8 = CircomPrime::Goldilocks.n8();
+        let mut header = (n8 as u32).to_le_bytes().to_vec();
+        header.extend(CircomPrime::Goldilocks.modulus().to_bytes_le());
+        header.extend(2u32.to_le_bytes());
+        let mut witness = 1u64.to_le_bytes().to_vec();
+        witness.extend(42u64.to_le_bytes());
+
+        let mut wtns_data = b"wtns".to_vec();
+        wtns_data.extend(2u32.to_le_bytes());
+        wtns_data.extend(2u32.to_le_bytes());
+        for (id, data) in [(1u32, header), (2, witness)] {
+            wtns_data.extend(id.to_le_bytes());
+            wtns_data.extend((data.len() as u64).to_le_bytes());
+            wtns_data.extend(data);
         }
+        let elems =
+            parse_witness_to_elems(&wtns_data, CircomPrime::Goldilocks, BigUint::from_bytes_le)?;
+        assert_eq!(elems, vec![BigUint::from(1u32), BigUint::from(42u32)]);
+        assert!(parse_witness_to_elems(&wtns_data, CircomPrime::Bn128, |_| ()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_witness_truncated() -> io::Result<()> {
+        let wtns_path = Path::new("example/build/multiplier_30/default/witness.wtns");
+        let wtns_data = std::fs::read(wtns_path)?;
+
+        // should return an error instead of panicking, wherever the file is cut
+        for len in [0, 4, 11, 12, 20, 40, 80, wtns_data.len() - 1] {
+            let err = parse_witness_prime(&wtns_data[..len]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", err);
+        }
+
+        // should not trust the length of a section either
+        let mut wtns_data = wtns_data;
+        wtns_data[16..24].copy_from_slice(&u64::MAX.to_le_bytes()); // length of the first section
+        assert!(parse_witness_prime(&wtns_data).is_err());
+
+        Ok(())
     }
 
-    Err(io::Error::new(
-        io::ErrorKind::InvalidData,
-        "Witness section not found.",
-    ))
+    #[test]
+    fn test_witness_json() -> io::Result<()> {
+        let modulus = CircomPrime::Bn128.modulus();
+        let big = "1".repeat(40); // does not fit in `u128`
+        let json = format!(r#"["1", 2, "0x1F", "-1", -2, {}, "{}"]"#, big, big);
+        let values = parse_witness_json(&json, &modulus)?;
+        assert_eq!(
+            values,
+            vec![
+                BigUint::from(1u32),
+                BigUint::from(2u32),
+                BigUint::from(31u32),
+                &modulus - 1u32,
+                &modulus - 2u32,
+                big.parse().unwrap(),
+                big.parse().unwrap(),
+            ]
+        );
+
+        // witness JSON as exported by SnarkJS
+        let json =
+            std::fs::read_to_string("example/build/multiplier_30/default/witness.wtns.json")?;
+        let values = parse_witness_json(&json, &modulus)?;
+        assert_eq!(values[0], BigUint::from(1u32)); // constant
+
+        // invalid values should point to their index
+        for (json, index) in [
+            (format!(r#"["1", "{}"]"#, modulus), 1),
+            (r#"["1", "2", "0xZZ"]"#.to_string(), 2),
+            (r#"[1.5]"#.to_string(), 0),
+            (r#"["1", null]"#.to_string(), 1),
+            (r#"["", "1"]"#.to_string(), 0),
+        ] {
+            let err = parse_witness_json(&json, &modulus).unwrap_err();
+            assert!(
+                err.to_string().contains(&format!("at index {}", index)),
+                "{}",
+                err
+            );
+        }
+        assert!(parse_witness_json(r#"{"1": "2"}"#, &modulus).is_err());
+
+        Ok(())
+    }
 }
@@ -12,6 +12,7 @@ describe("ffi", () => {
 
   // we use these specifically for testing
   const circuitName = "multiplier_30";
+  const circuitNameBls12381 = "multiplier_30_bls12381";
   const inputName = "default";
 
   let circomkit: Circomkit;
@@ -96,16 +97,15 @@ describe("ffi", () => {
     it("should generate a valid Lambdaworks proof with CircomkitFFIBun", () => {
       const lib = new CircomkitFFIBun(libpath);
 
-      const [witnessPath, r1csPath, pkeyPath] = [
-        circomkit.path.ofCircuitWithInput(circuitName, inputName, "wtns"),
-        circomkit.path.ofCircuit(circuitName, "r1cs") + ".json",
-        circomkit.path.ofCircuit(circuitName, "pkey"),
+      // Lambdaworks proves over BLS12-381, so it needs the circuit compiled with `--prime bls12381`
+      const [witnessPath, r1csPath] = [
+        circomkit.path.ofCircuitWithInput(circuitNameBls12381, inputName, "wtns"),
+        circomkit.path.ofCircuit(circuitNameBls12381, "r1cs") + ".json",
       ].map((path) => import.meta.dir + "/../example/" + path);
 
       // Check if required files exist before attempting to prove
       expect(existsSync(witnessPath)).toBeTrue();
       expect(existsSync(r1csPath)).toBeTrue();
-      expect(existsSync(pkeyPath)).toBeTrue();
 
       const result = lib.lambdaworks_prove(witnessPath, r1csPath);
 